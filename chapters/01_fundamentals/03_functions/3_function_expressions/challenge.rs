@@ -36,6 +36,10 @@ fn grade_message(score: i32) -> &'static str {
 }
 
 // TODO: Fix this function to calculate factorial using a block expression
+//
+// Once the block hands back `result`, try factorial(13): 13! no longer fits
+// in the u32 this returns. `big_factorial` in the unit testing chapter
+// (05_testing/01_unit_testing) returns a `BigUint`, which has no such limit.
 fn factorial(n: u32) -> u32 {
     // This implementation has a syntax error
     {
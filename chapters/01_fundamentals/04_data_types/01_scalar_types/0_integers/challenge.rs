@@ -31,6 +31,9 @@ fn main() {
     // TODO: This function should add two u8 values, but handle overflow
     // by saturating at 255 (returning the value as a Some).
     // It should never return None.
+    // Saturating throws the real total away. When you need it, the
+    // `BigUint` in 05_testing/01_unit_testing keeps it: 255 + 1 there is
+    // just 256 (see `test_big_add`).
     pub fn add_without_overflow(a: u8, b: u8) -> Option<u8> {
         // This has an error in the overflow handling logic
         let result = a + b;
@@ -43,6 +46,11 @@ fn main() {
     
     // TODO: This function should calculate the sum of all integers from
     // 1 to n (inclusive), but it has logical errors
+    //
+    // Once it works, try sum_up_to(92682): that's the first n whose sum no
+    // longer fits in a u32. `big_sum_up_to` in the unit testing chapter
+    // (05_testing/01_unit_testing) returns a `BigUint` instead, and gets
+    // sum_up_to(u32::MAX) = 9223372034707292160 exactly.
     pub fn sum_up_to(n: u32) -> u32 {
         let mut sum = 0;
         
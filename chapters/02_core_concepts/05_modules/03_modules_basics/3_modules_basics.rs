@@ -91,6 +91,9 @@ fn main() {
     farm::crops::list();
     farm::crops::plant();
     
+    // farm_school_scheduler.rs keeps these modules but backs them with a
+    // real farm, garden and school, plus a scheduler for the day's tasks
+
     //------------------------------------------------------
     // MODULE ORGANIZATION
     //------------------------------------------------------
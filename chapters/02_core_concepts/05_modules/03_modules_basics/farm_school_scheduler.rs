@@ -0,0 +1,1078 @@
+// Farm, School and Scheduler: The Module Examples Backed by Real State
+//
+// In 3_modules_basics.rs, `plant`, `water`, `feed`, `harvest`, `tend`,
+// `enroll` and `show_farm_status` only print. This file keeps the same module
+// hierarchy, but each module now owns real data:
+//
+// - `garden`: flower beds that dry out; `tend` waters the dry ones using
+//   the private `water`.
+// - `farm`: plots and animals. `farm::crops` plants, waters and harvests
+//   plots, `farm::animals` feeds the animals, and both reach the farm's
+//   private helpers through `super`.
+// - `school`: courses with a teacher, a time slot and a capacity, and
+//   `school::students::enroll` that respects it.
+// - `scheduler`: turns the day's chores and classes into an ordered plan,
+//   and reports tasks that overlap for the same person or start before the
+//   task they depend on has finished.
+//
+// Try the module exercises yourself before reading this file!
+
+fn main() {
+    println!("Farm, garden and school, organized with modules!");
+
+    use farm::animals::Species;
+    use farm::crops::Crop;
+    use scheduler::Time;
+
+    let mut garden = garden::Garden::new();
+    garden::plant(&mut garden, "Tulips", 0);
+    garden::plant(&mut garden, "Roses", 0);
+
+    let mut farm = farm::Farm::new("Green Acres", 3);
+    farm::animals::add(&mut farm, "Daisy", Species::Cow).unwrap();
+    farm::animals::add(&mut farm, "Clucky", Species::Chicken).unwrap();
+    farm::crops::plant(&mut farm, 0, Crop::Wheat, 0).unwrap();
+    farm::crops::plant(&mut farm, 1, Crop::Corn, 0).unwrap();
+
+    let mut school = school::School::new();
+    school::courses::add(&mut school, "AG101", "Soil Basics", "Sam", 2, Time::hm(8, 0), 60).unwrap();
+    school::courses::add(&mut school, "RS101", "Intro to Rust", "Alex", 30, Time::hm(10, 0), 90).unwrap();
+    school::students::enroll(&mut school, "Ana", "AG101").unwrap();
+    school::students::enroll(&mut school, "Ben", "AG101").unwrap();
+    if let Err(e) = school::students::enroll(&mut school, "Cy", "AG101") {
+        println!("Cy can't enroll: {}", e);
+    }
+
+    // Four days later the wheat is ripe, and Sam, the farmer, also has a
+    // class to teach
+    let day = 4;
+    let schedule = scheduler::daily_schedule(&farm, &garden, &school, day, "Sam");
+    println!("\nPlan for day {}:\n{}", day, schedule.plan());
+
+    farm::animals::care(&mut farm, day);
+    farm::crops::water(&mut farm, 1, day).unwrap();
+    let crop = farm::crops::harvest(&mut farm, 0, day).unwrap();
+    println!("\nHarvested {:?}", crop);
+    garden::tend(&mut garden, day);
+    println!("\n{}", farm::show_farm_status(&farm, day));
+}
+
+//------------------------------------------------------
+// GARDEN
+//------------------------------------------------------
+
+pub mod garden {
+    /// Days since the season started
+    pub type Day = u32;
+
+    /// Beds that haven't been watered for this many days are dry
+    pub const DRY_AFTER_DAYS: Day = 2;
+
+    pub struct Bed {
+        pub flower: String,
+        last_watered: Day,
+    }
+
+    impl Bed {
+        pub fn last_watered(&self) -> Day {
+            self.last_watered
+        }
+
+        pub fn is_dry(&self, day: Day) -> bool {
+            day.saturating_sub(self.last_watered) >= DRY_AFTER_DAYS
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Garden {
+        beds: Vec<Bed>,
+    }
+
+    impl Garden {
+        pub fn new() -> Garden {
+            Garden { beds: Vec::new() }
+        }
+
+        pub fn beds(&self) -> &[Bed] {
+            &self.beds
+        }
+    }
+
+    /// Plants a new bed, watered on the day it is planted
+    pub fn plant(garden: &mut Garden, flower: &str, day: Day) {
+        garden.beds.push(Bed {
+            flower: flower.to_string(),
+            last_watered: day,
+        });
+    }
+
+    // Private: only `tend` decides when a bed gets water
+    fn water(bed: &mut Bed, day: Day) {
+        bed.last_watered = day;
+    }
+
+    /// Waters every dry bed; returns how many were watered
+    pub fn tend(garden: &mut Garden, day: Day) -> usize {
+        let mut watered = 0;
+        for bed in garden.beds.iter_mut().filter(|bed| bed.is_dry(day)) {
+            water(bed, day);
+            watered += 1;
+        }
+        watered
+    }
+
+    pub fn needs_tending(garden: &Garden, day: Day) -> bool {
+        garden.beds.iter().any(|bed| bed.is_dry(day))
+    }
+}
+
+//------------------------------------------------------
+// FARM
+//------------------------------------------------------
+
+pub mod farm {
+    use std::fmt;
+
+    pub use crate::garden::Day;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum FarmError {
+        UnknownPlot(usize),
+        PlotInUse(usize),
+        PlotEmpty(usize),
+        NotReady { plot: usize, days_left: Day },
+        UnknownAnimal(String),
+        DuplicateAnimal(String),
+    }
+
+    impl fmt::Display for FarmError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                FarmError::UnknownPlot(plot) => write!(f, "there is no plot {}", plot),
+                FarmError::PlotInUse(plot) => write!(f, "plot {} is already planted", plot),
+                FarmError::PlotEmpty(plot) => write!(f, "nothing is growing on plot {}", plot),
+                FarmError::NotReady { plot, days_left } => {
+                    write!(f, "plot {} is ready to harvest in {} day(s)", plot, days_left)
+                }
+                FarmError::UnknownAnimal(name) => write!(f, "there is no animal called {}", name),
+                FarmError::DuplicateAnimal(name) => write!(f, "there is already an animal called {}", name),
+            }
+        }
+    }
+
+    impl std::error::Error for FarmError {}
+
+    pub struct Farm {
+        pub name: String,
+        plots: Vec<crops::Plot>,
+        animals: Vec<animals::Animal>,
+    }
+
+    impl Farm {
+        /// A farm with empty plots numbered from 0
+        pub fn new(name: &str, plot_count: usize) -> Farm {
+            Farm {
+                name: name.to_string(),
+                plots: (0..plot_count).map(crops::Plot::empty).collect(),
+                animals: Vec::new(),
+            }
+        }
+
+        pub fn plots(&self) -> &[crops::Plot] {
+            &self.plots
+        }
+
+        pub fn animals(&self) -> &[animals::Animal] {
+            &self.animals
+        }
+    }
+
+    pub fn overview(farm: &Farm) -> String {
+        let planted = farm.plots.iter().filter(|plot| plot.crop().is_some()).count();
+        format!(
+            "{} has {} animal(s) and {} plot(s), {} planted",
+            farm.name,
+            farm.animals.len(),
+            farm.plots.len(),
+            planted
+        )
+    }
+
+    // Private helpers; the nested modules reach them through `super`
+    fn plot_mut(farm: &mut Farm, plot: usize) -> Result<&mut crops::Plot, FarmError> {
+        farm.plots.get_mut(plot).ok_or(FarmError::UnknownPlot(plot))
+    }
+
+    fn animal_mut<'a>(farm: &'a mut Farm, name: &str) -> Result<&'a mut animals::Animal, FarmError> {
+        farm.animals
+            .iter_mut()
+            .find(|animal| animal.name == name)
+            .ok_or_else(|| FarmError::UnknownAnimal(name.to_string()))
+    }
+
+    //--------------------------------------------------
+    // Animals
+    //--------------------------------------------------
+
+    pub mod animals {
+        use super::{Day, Farm, FarmError};
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Species {
+            Cow,
+            Chicken,
+            Sheep,
+        }
+
+        pub struct Animal {
+            pub name: String,
+            pub species: Species,
+            last_fed: Option<Day>,
+        }
+
+        impl Animal {
+            pub fn last_fed(&self) -> Option<Day> {
+                self.last_fed
+            }
+
+            /// Animals are fed once a day
+            pub fn is_hungry(&self, day: Day) -> bool {
+                self.last_fed != Some(day)
+            }
+        }
+
+        pub fn add(farm: &mut Farm, name: &str, species: Species) -> Result<(), FarmError> {
+            if farm.animals.iter().any(|animal| animal.name == name) {
+                return Err(FarmError::DuplicateAnimal(name.to_string()));
+            }
+            farm.animals.push(Animal {
+                name: name.to_string(),
+                species,
+                last_fed: None,
+            });
+            Ok(())
+        }
+
+        /// "Daisy (Cow)" for every animal
+        pub fn list(farm: &Farm) -> Vec<String> {
+            farm.animals
+                .iter()
+                .map(|animal| format!("{} ({:?})", animal.name, animal.species))
+                .collect()
+        }
+
+        pub fn hungry(farm: &Farm, day: Day) -> Vec<&Animal> {
+            farm.animals.iter().filter(|animal| animal.is_hungry(day)).collect()
+        }
+
+        /// Feeds every hungry animal; returns the names of those fed
+        pub fn care(farm: &mut Farm, day: Day) -> Vec<String> {
+            let names: Vec<String> = hungry(farm, day).iter().map(|animal| animal.name.clone()).collect();
+            for name in &names {
+                // The names were just taken from the farm, so this can't fail
+                let _ = feed(farm, name, day);
+            }
+            names
+        }
+
+        // Private: feeding always goes through `care`
+        fn feed(farm: &mut Farm, name: &str, day: Day) -> Result<(), FarmError> {
+            super::animal_mut(farm, name)?.last_fed = Some(day);
+            Ok(())
+        }
+    }
+
+    //--------------------------------------------------
+    // Crops
+    //--------------------------------------------------
+
+    pub mod crops {
+        use super::{Day, Farm, FarmError};
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Crop {
+            Corn,
+            Wheat,
+            Soybeans,
+        }
+
+        impl Crop {
+            pub fn days_to_harvest(&self) -> Day {
+                match self {
+                    Crop::Corn => 5,
+                    Crop::Wheat => 4,
+                    Crop::Soybeans => 6,
+                }
+            }
+
+            /// How often the crop needs water, in days
+            pub fn water_every(&self) -> Day {
+                match self {
+                    Crop::Corn => 2,
+                    Crop::Wheat => 3,
+                    Crop::Soybeans => 2,
+                }
+            }
+        }
+
+        struct Planting {
+            crop: Crop,
+            planted_on: Day,
+            last_watered: Day,
+        }
+
+        pub struct Plot {
+            id: usize,
+            planting: Option<Planting>,
+        }
+
+        impl Plot {
+            pub(super) fn empty(id: usize) -> Plot {
+                Plot { id, planting: None }
+            }
+
+            pub fn id(&self) -> usize {
+                self.id
+            }
+
+            pub fn crop(&self) -> Option<Crop> {
+                self.planting.as_ref().map(|planting| planting.crop)
+            }
+
+            pub fn needs_water(&self, day: Day) -> bool {
+                match &self.planting {
+                    // A day before the last watering isn't thirsty either
+                    Some(planting) => day.saturating_sub(planting.last_watered) >= planting.crop.water_every(),
+                    None => false,
+                }
+            }
+
+            /// Days until harvest; 0 means it can be harvested now
+            pub fn days_left(&self, day: Day) -> Option<Day> {
+                self.planting.as_ref().map(|planting| {
+                    let ready_on = planting.planted_on.saturating_add(planting.crop.days_to_harvest());
+                    ready_on.saturating_sub(day)
+                })
+            }
+
+            pub fn is_ready(&self, day: Day) -> bool {
+                self.days_left(day) == Some(0)
+            }
+        }
+
+        /// "Plot 0: Wheat" or "Plot 2: empty" for every plot
+        pub fn list(farm: &Farm) -> Vec<String> {
+            farm.plots
+                .iter()
+                .map(|plot| match plot.crop() {
+                    Some(crop) => format!("Plot {}: {:?}", plot.id, crop),
+                    None => format!("Plot {}: empty", plot.id),
+                })
+                .collect()
+        }
+
+        /// Sows a crop on an empty plot; it starts out watered
+        pub fn plant(farm: &mut Farm, plot: usize, crop: Crop, day: Day) -> Result<(), FarmError> {
+            let plot_ref = super::plot_mut(farm, plot)?;
+            if plot_ref.planting.is_some() {
+                return Err(FarmError::PlotInUse(plot));
+            }
+            plot_ref.planting = Some(Planting {
+                crop,
+                planted_on: day,
+                last_watered: day,
+            });
+            Ok(())
+        }
+
+        pub fn water(farm: &mut Farm, plot: usize, day: Day) -> Result<(), FarmError> {
+            let planting = super::plot_mut(farm, plot)?
+                .planting
+                .as_mut()
+                .ok_or(FarmError::PlotEmpty(plot))?;
+            planting.last_watered = day;
+            Ok(())
+        }
+
+        /// Harvests a ripe plot and leaves it empty
+        pub fn harvest(farm: &mut Farm, plot: usize, day: Day) -> Result<Crop, FarmError> {
+            let plot_ref = super::plot_mut(farm, plot)?;
+            match plot_ref.days_left(day) {
+                None => Err(FarmError::PlotEmpty(plot)),
+                Some(0) => {
+                    let planting = plot_ref.planting.take().ok_or(FarmError::PlotEmpty(plot))?;
+                    Ok(planting.crop)
+                }
+                Some(days_left) => Err(FarmError::NotReady { plot, days_left }),
+            }
+        }
+    }
+
+    /// A report of everything on the farm on a given day
+    pub fn show_farm_status(farm: &Farm, day: Day) -> String {
+        let mut lines = vec![format!("{} on day {}", overview(farm), day)];
+        for plot in &farm.plots {
+            let line = match (plot.crop(), plot.days_left(day)) {
+                (Some(crop), Some(0)) => format!("  Plot {}: {:?}, ready to harvest", plot.id(), crop),
+                (Some(crop), Some(days)) => format!("  Plot {}: {:?}, {} day(s) to harvest", plot.id(), crop, days),
+                _ => format!("  Plot {}: empty", plot.id()),
+            };
+            let thirsty = if plot.needs_water(day) { ", needs water" } else { "" };
+            lines.push(format!("{}{}", line, thirsty));
+        }
+        for animal in &farm.animals {
+            let fed = if animal.is_hungry(day) { "hungry" } else { "fed" };
+            lines.push(format!("  {} ({:?}): {}", animal.name, animal.species, fed));
+        }
+        lines.join("\n")
+    }
+}
+
+//------------------------------------------------------
+// SCHOOL
+//------------------------------------------------------
+
+pub mod school {
+    use crate::scheduler::Time;
+    use std::fmt;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum SchoolError {
+        UnknownCourse(String),
+        DuplicateCourse(String),
+        CourseFull { code: String, capacity: usize },
+        AlreadyEnrolled,
+        NotEnrolled,
+    }
+
+    impl fmt::Display for SchoolError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SchoolError::UnknownCourse(code) => write!(f, "there is no course {}", code),
+                SchoolError::DuplicateCourse(code) => write!(f, "course {} already exists", code),
+                SchoolError::CourseFull { code, capacity } => {
+                    write!(f, "course {} is full ({} students)", code, capacity)
+                }
+                SchoolError::AlreadyEnrolled => write!(f, "the student is already enrolled"),
+                SchoolError::NotEnrolled => write!(f, "the student is not enrolled"),
+            }
+        }
+    }
+
+    impl std::error::Error for SchoolError {}
+
+    #[derive(Default)]
+    pub struct School {
+        courses: Vec<courses::Course>,
+    }
+
+    impl School {
+        pub fn new() -> School {
+            School { courses: Vec::new() }
+        }
+
+        pub fn courses(&self) -> &[courses::Course] {
+            &self.courses
+        }
+    }
+
+    fn course_mut<'a>(school: &'a mut School, code: &str) -> Result<&'a mut courses::Course, SchoolError> {
+        school
+            .courses
+            .iter_mut()
+            .find(|course| course.code() == code)
+            .ok_or_else(|| SchoolError::UnknownCourse(code.to_string()))
+    }
+
+    pub mod courses {
+        use super::{School, SchoolError};
+        use crate::scheduler::Time;
+
+        pub struct Course {
+            code: String,
+            pub title: String,
+            pub teacher: String,
+            capacity: usize,
+            start: Time,
+            minutes: u16,
+            // Only the `school` modules can change the list, so enrollment
+            // always checks the capacity
+            pub(super) students: Vec<String>,
+        }
+
+        impl Course {
+            pub fn code(&self) -> &str {
+                &self.code
+            }
+
+            pub fn capacity(&self) -> usize {
+                self.capacity
+            }
+
+            pub fn start(&self) -> Time {
+                self.start
+            }
+
+            pub fn minutes(&self) -> u16 {
+                self.minutes
+            }
+
+            pub fn students(&self) -> &[String] {
+                &self.students
+            }
+
+            pub fn seats_left(&self) -> usize {
+                self.capacity - self.students.len()
+            }
+        }
+
+        /// Adds a course that meets every day at `start` for `minutes`
+        pub fn add(
+            school: &mut School,
+            code: &str,
+            title: &str,
+            teacher: &str,
+            capacity: usize,
+            start: Time,
+            minutes: u16,
+        ) -> Result<(), SchoolError> {
+            if school.courses.iter().any(|course| course.code == code) {
+                return Err(SchoolError::DuplicateCourse(code.to_string()));
+            }
+            school.courses.push(Course {
+                code: code.to_string(),
+                title: title.to_string(),
+                teacher: teacher.to_string(),
+                capacity,
+                start,
+                minutes,
+                students: Vec::new(),
+            });
+            Ok(())
+        }
+
+        /// "AG101 Soil Basics (2/2)" for every course
+        pub fn list(school: &School) -> Vec<String> {
+            school
+                .courses
+                .iter()
+                .map(|course| format!("{} {} ({}/{})", course.code, course.title, course.students.len(), course.capacity))
+                .collect()
+        }
+    }
+
+    pub mod students {
+        use super::{School, SchoolError};
+
+        /// Enrolls a student if there is room; returns the seats left
+        pub fn enroll(school: &mut School, student: &str, code: &str) -> Result<usize, SchoolError> {
+            let course = super::course_mut(school, code)?;
+            if course.students.iter().any(|name| name == student) {
+                return Err(SchoolError::AlreadyEnrolled);
+            }
+            if course.seats_left() == 0 {
+                return Err(SchoolError::CourseFull {
+                    code: code.to_string(),
+                    capacity: course.capacity(),
+                });
+            }
+            course.students.push(student.to_string());
+            Ok(course.seats_left())
+        }
+
+        pub fn withdraw(school: &mut School, student: &str, code: &str) -> Result<(), SchoolError> {
+            let course = super::course_mut(school, code)?;
+            let index = course
+                .students
+                .iter()
+                .position(|name| name == student)
+                .ok_or(SchoolError::NotEnrolled)?;
+            course.students.remove(index);
+            Ok(())
+        }
+
+        /// Codes of the courses a student is enrolled in
+        pub fn courses_for<'a>(school: &'a School, student: &str) -> Vec<&'a str> {
+            school
+                .courses()
+                .iter()
+                .filter(|course| course.students().iter().any(|name| name == student))
+                .map(|course| course.code())
+                .collect()
+        }
+    }
+
+    /// The school's part of the day: one entry per course
+    pub fn timetable(school: &School) -> Vec<(Time, u16, String, String)> {
+        school
+            .courses
+            .iter()
+            .map(|course| {
+                let title = format!("Teach {} {}", course.code(), course.title);
+                (course.start(), course.minutes(), title, course.teacher.clone())
+            })
+            .collect()
+    }
+}
+
+//------------------------------------------------------
+// SCHEDULER
+//------------------------------------------------------
+
+pub mod scheduler {
+    use crate::farm::{self, Day, Farm};
+    use crate::garden::{self, Garden};
+    use crate::school::{self, School};
+    use std::fmt;
+
+    /// A time of day, stored as minutes after midnight.
+    /// Arithmetic saturates: a time past the largest one stays there.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Time(u16);
+
+    impl Time {
+        pub fn hm(hours: u16, minutes: u16) -> Time {
+            Time(hours.saturating_mul(60).saturating_add(minutes))
+        }
+
+        pub fn minutes(&self) -> u16 {
+            self.0
+        }
+
+        pub fn plus(&self, minutes: u16) -> Time {
+            Time(self.0.saturating_add(minutes))
+        }
+    }
+
+    impl fmt::Display for Time {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+        }
+    }
+
+    pub type TaskId = usize;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Task {
+        pub id: TaskId,
+        pub title: String,
+        /// Who does the task; one person can't do two tasks at once
+        pub worker: String,
+        pub start: Time,
+        pub minutes: u16,
+        /// Tasks that must be finished before this one starts
+        pub after: Vec<TaskId>,
+    }
+
+    impl Task {
+        pub fn end(&self) -> Time {
+            self.start.plus(self.minutes)
+        }
+
+        fn overlaps(&self, other: &Task) -> bool {
+            self.start < other.end() && other.start < self.end()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Conflict {
+        /// The same worker is booked for both tasks at once
+        Overlap { worker: String, first: TaskId, second: TaskId },
+        /// A task starts before a task it depends on has finished
+        StartsTooEarly { task: TaskId, prerequisite: TaskId },
+        /// These tasks depend on each other in a loop and can't be ordered
+        Cycle(Vec<TaskId>),
+    }
+
+    /// The tasks in the order to do them, plus everything that doesn't fit
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Plan {
+        pub order: Vec<Task>,
+        pub conflicts: Vec<Conflict>,
+    }
+
+    impl Plan {
+        pub fn ids(&self) -> Vec<TaskId> {
+            self.order.iter().map(|task| task.id).collect()
+        }
+
+        pub fn has_conflicts(&self) -> bool {
+            !self.conflicts.is_empty()
+        }
+    }
+
+    impl fmt::Display for Plan {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let title = |id: TaskId| {
+                self.order
+                    .iter()
+                    .find(|task| task.id == id)
+                    .map_or(format!("task #{}", id), |task| format!("\"{}\"", task.title))
+            };
+
+            for task in &self.order {
+                writeln!(f, "{}-{} {:<6} {}", task.start, task.end(), task.worker, task.title)?;
+            }
+            if self.conflicts.is_empty() {
+                return write!(f, "No conflicts");
+            }
+            write!(f, "Conflicts:")?;
+            for conflict in &self.conflicts {
+                match conflict {
+                    Conflict::Overlap { worker, first, second } => {
+                        write!(f, "\n  {} is booked for {} and {} at once", worker, title(*first), title(*second))?
+                    }
+                    Conflict::StartsTooEarly { task, prerequisite } => {
+                        write!(f, "\n  {} starts before {} is done", title(*task), title(*prerequisite))?
+                    }
+                    Conflict::Cycle(ids) => {
+                        let titles: Vec<String> = ids.iter().map(|id| title(*id)).collect();
+                        write!(f, "\n  {} depend on each other", titles.join(", "))?
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Schedule {
+        tasks: Vec<Task>,
+    }
+
+    impl Schedule {
+        pub fn new() -> Schedule {
+            Schedule { tasks: Vec::new() }
+        }
+
+        pub fn add(&mut self, title: &str, worker: &str, start: Time, minutes: u16) -> TaskId {
+            let id = self.tasks.len();
+            self.tasks.push(Task {
+                id,
+                title: title.to_string(),
+                worker: worker.to_string(),
+                start,
+                minutes,
+                after: Vec::new(),
+            });
+            id
+        }
+
+        /// Makes `task` wait for `prerequisite`; unknown ids are ignored
+        pub fn after(&mut self, task: TaskId, prerequisite: TaskId) {
+            if prerequisite < self.tasks.len() {
+                if let Some(task) = self.tasks.get_mut(task) {
+                    task.after.push(prerequisite);
+                }
+            }
+        }
+
+        pub fn tasks(&self) -> &[Task] {
+            &self.tasks
+        }
+
+        /// Orders the tasks so every task comes after its prerequisites,
+        /// earliest start first, and collects the conflicts
+        pub fn plan(&self) -> Plan {
+            let mut conflicts = Vec::new();
+
+            // Kahn's algorithm, always picking the earliest ready task
+            let mut waiting_on: Vec<usize> = self.tasks.iter().map(|task| task.after.len()).collect();
+            let mut done = vec![false; self.tasks.len()];
+            let mut order = Vec::new();
+            loop {
+                let next = self
+                    .tasks
+                    .iter()
+                    .filter(|task| !done[task.id] && waiting_on[task.id] == 0)
+                    .min_by_key(|task| (task.start, task.id));
+                let Some(next) = next else { break };
+                done[next.id] = true;
+                order.push(next.clone());
+                for task in &self.tasks {
+                    waiting_on[task.id] -= task.after.iter().filter(|id| **id == next.id).count();
+                }
+            }
+            let stuck: Vec<TaskId> = self.tasks.iter().filter(|task| !done[task.id]).map(|task| task.id).collect();
+            if !stuck.is_empty() {
+                conflicts.push(Conflict::Cycle(stuck));
+            }
+
+            for task in &order {
+                for &prerequisite in &task.after {
+                    if task.start < self.tasks[prerequisite].end() {
+                        conflicts.push(Conflict::StartsTooEarly {
+                            task: task.id,
+                            prerequisite,
+                        });
+                    }
+                }
+            }
+
+            for (index, first) in self.tasks.iter().enumerate() {
+                for second in &self.tasks[index + 1..] {
+                    if first.worker == second.worker && first.overlaps(second) {
+                        conflicts.push(Conflict::Overlap {
+                            worker: first.worker.clone(),
+                            first: first.id,
+                            second: second.id,
+                        });
+                    }
+                }
+            }
+
+            Plan { order, conflicts }
+        }
+    }
+
+    /// Chores start at 6:00
+    pub const CHORES_START: (u16, u16) = (6, 0);
+
+    /// Builds the day's schedule from the actual state: the farmer feeds
+    /// hungry animals, waters thirsty plots, harvests ripe ones (after
+    /// watering) and tends the garden, one chore after another. Every
+    /// course adds a class for its teacher.
+    pub fn daily_schedule(farm: &Farm, garden: &Garden, school: &School, day: Day, farmer: &str) -> Schedule {
+        let mut schedule = Schedule::new();
+        let mut clock = Time::hm(CHORES_START.0, CHORES_START.1);
+        let mut chore = |schedule: &mut Schedule, title: String, minutes: u16| {
+            let id = schedule.add(&title, farmer, clock, minutes);
+            clock = clock.plus(minutes);
+            id
+        };
+
+        let hungry: Vec<String> = farm::animals::hungry(farm, day).iter().map(|a| a.name.clone()).collect();
+        if !hungry.is_empty() {
+            let minutes = u16::try_from(hungry.len()).unwrap_or(u16::MAX).saturating_mul(15);
+            chore(&mut schedule, format!("Feed {}", hungry.join(", ")), minutes);
+        }
+
+        let mut last_watering = None;
+        for plot in farm.plots().iter().filter(|plot| plot.needs_water(day)) {
+            last_watering = Some(chore(&mut schedule, format!("Water plot {}", plot.id()), 20));
+        }
+        for plot in farm.plots().iter().filter(|plot| plot.is_ready(day)) {
+            let harvest = chore(&mut schedule, format!("Harvest plot {}", plot.id()), 60);
+            if let Some(watering) = last_watering {
+                schedule.after(harvest, watering);
+            }
+        }
+
+        if garden::needs_tending(garden, day) {
+            chore(&mut schedule, "Tend the garden".to_string(), 30);
+        }
+
+        for (start, minutes, title, teacher) in school::timetable(school) {
+            schedule.add(&title, &teacher, start, minutes);
+        }
+        schedule
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::farm::animals::Species;
+    use super::farm::crops::Crop;
+    use super::farm::{self, Farm, FarmError};
+    use super::garden::{self, Garden};
+    use super::scheduler::{self, Conflict, Schedule, Time};
+    use super::school::{self, School, SchoolError};
+
+    fn farm() -> Farm {
+        let mut farm = Farm::new("Green Acres", 3);
+        farm::animals::add(&mut farm, "Daisy", Species::Cow).unwrap();
+        farm::animals::add(&mut farm, "Clucky", Species::Chicken).unwrap();
+        farm::crops::plant(&mut farm, 0, Crop::Wheat, 0).unwrap();
+        farm
+    }
+
+    #[test]
+    fn test_crops_grow_and_harvest() {
+        let mut farm = farm();
+
+        assert_eq!(farm::crops::plant(&mut farm, 0, Crop::Corn, 0), Err(FarmError::PlotInUse(0)));
+        assert_eq!(farm::crops::plant(&mut farm, 7, Crop::Corn, 0), Err(FarmError::UnknownPlot(7)));
+        assert_eq!(farm::crops::water(&mut farm, 1, 0), Err(FarmError::PlotEmpty(1)));
+
+        assert!(!farm.plots()[0].needs_water(2));
+        assert!(farm.plots()[0].needs_water(3));
+        farm::crops::water(&mut farm, 0, 3).unwrap();
+        assert!(!farm.plots()[0].needs_water(3));
+
+        assert_eq!(
+            farm::crops::harvest(&mut farm, 0, 1),
+            Err(FarmError::NotReady { plot: 0, days_left: 3 })
+        );
+        assert_eq!(farm::crops::harvest(&mut farm, 0, 4), Ok(Crop::Wheat));
+        assert_eq!(farm.plots()[0].crop(), None);
+
+        // Asking about a day before the last watering doesn't panic
+        farm::crops::plant(&mut farm, 1, Crop::Corn, 5).unwrap();
+        assert!(!farm.plots()[1].needs_water(2));
+        assert!(farm::show_farm_status(&farm, 2).contains("Plot 1: Corn"));
+        farm::crops::harvest(&mut farm, 1, farm::Day::MAX).unwrap();
+        assert_eq!(farm::crops::list(&farm), vec!["Plot 0: empty", "Plot 1: empty", "Plot 2: empty"]);
+    }
+
+    #[test]
+    fn test_animals_are_fed_once_a_day() {
+        let mut farm = farm();
+        assert_eq!(
+            farm::animals::add(&mut farm, "Daisy", Species::Sheep),
+            Err(FarmError::DuplicateAnimal("Daisy".to_string()))
+        );
+
+        assert_eq!(farm::animals::care(&mut farm, 1), vec!["Daisy", "Clucky"]);
+        assert!(farm::animals::care(&mut farm, 1).is_empty());
+        assert_eq!(farm::animals::hungry(&farm, 2).len(), 2);
+        assert_eq!(farm::animals::list(&farm), vec!["Daisy (Cow)", "Clucky (Chicken)"]);
+    }
+
+    #[test]
+    fn test_garden_tending_waters_dry_beds() {
+        let mut garden = Garden::new();
+        garden::plant(&mut garden, "Tulips", 0);
+        garden::plant(&mut garden, "Roses", 1);
+
+        assert!(!garden::needs_tending(&garden, 1));
+        assert_eq!(garden::tend(&mut garden, 2), 1);
+        assert_eq!(garden::tend(&mut garden, 3), 1);
+        assert_eq!(garden.beds()[0].last_watered(), 2);
+    }
+
+    #[test]
+    fn test_enrollment_respects_capacity() {
+        let mut school = School::new();
+        school::courses::add(&mut school, "AG101", "Soil Basics", "Sam", 2, Time::hm(8, 0), 60).unwrap();
+
+        assert_eq!(school::students::enroll(&mut school, "Ana", "AG101"), Ok(1));
+        assert_eq!(school::students::enroll(&mut school, "Ana", "AG101"), Err(SchoolError::AlreadyEnrolled));
+        assert_eq!(school::students::enroll(&mut school, "Ben", "AG101"), Ok(0));
+        assert_eq!(
+            school::students::enroll(&mut school, "Cy", "AG101"),
+            Err(SchoolError::CourseFull {
+                code: "AG101".to_string(),
+                capacity: 2
+            })
+        );
+
+        school::students::withdraw(&mut school, "Ana", "AG101").unwrap();
+        assert_eq!(school::students::enroll(&mut school, "Cy", "AG101"), Ok(0));
+        assert_eq!(school::students::courses_for(&school, "Cy"), vec!["AG101"]);
+        assert_eq!(school::courses::list(&school), vec!["AG101 Soil Basics (2/2)"]);
+        assert_eq!(
+            school::students::enroll(&mut school, "Cy", "XX999"),
+            Err(SchoolError::UnknownCourse("XX999".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_time_arithmetic_saturates() {
+        assert_eq!(Time::hm(6, 30).plus(45).to_string(), "07:15");
+        assert_eq!(Time::hm(2000, 0), Time::hm(u16::MAX, 0));
+        assert_eq!(Time::hm(1000, 0).plus(u16::MAX).minutes(), u16::MAX);
+    }
+
+    #[test]
+    fn test_plan_orders_by_dependencies_then_time() {
+        let mut schedule = Schedule::new();
+        let milk = schedule.add("Milk the cow", "Sam", Time::hm(7, 0), 30);
+        let feed = schedule.add("Feed the cow", "Sam", Time::hm(6, 0), 30);
+        let sell = schedule.add("Sell the milk", "Ana", Time::hm(6, 30), 60);
+        schedule.after(milk, feed);
+        schedule.after(sell, milk);
+
+        let plan = schedule.plan();
+        assert_eq!(plan.ids(), vec![feed, milk, sell]);
+        // Selling starts at 6:30 but the milking only ends at 7:30
+        assert_eq!(plan.conflicts, vec![Conflict::StartsTooEarly { task: sell, prerequisite: milk }]);
+    }
+
+    #[test]
+    fn test_plan_reports_overlaps_and_cycles() {
+        let mut schedule = Schedule::new();
+        let a = schedule.add("Plough", "Sam", Time::hm(8, 0), 60);
+        let b = schedule.add("Teach", "Sam", Time::hm(8, 30), 60);
+        let c = schedule.add("Fence", "Ana", Time::hm(8, 30), 60);
+        let d = schedule.add("Gate", "Ana", Time::hm(9, 30), 30);
+        schedule.after(c, d);
+        schedule.after(d, c);
+
+        let plan = schedule.plan();
+        assert_eq!(plan.ids(), vec![a, b]);
+        assert_eq!(
+            plan.conflicts,
+            vec![
+                Conflict::Cycle(vec![c, d]),
+                Conflict::Overlap {
+                    worker: "Sam".to_string(),
+                    first: a,
+                    second: b
+                },
+            ]
+        );
+        // Back-to-back tasks don't overlap
+        assert!(!plan.conflicts.iter().any(|conflict| matches!(conflict, Conflict::Overlap { worker, .. } if worker == "Ana")));
+    }
+
+    #[test]
+    fn test_daily_schedule_from_state() {
+        let mut farm = farm();
+        farm::crops::plant(&mut farm, 1, Crop::Corn, 0).unwrap();
+        let mut garden = Garden::new();
+        garden::plant(&mut garden, "Tulips", 0);
+        let mut school = School::new();
+        school::courses::add(&mut school, "AG101", "Soil Basics", "Sam", 2, Time::hm(7, 0), 60).unwrap();
+
+        // Day 4: both animals hungry, both plots thirsty, the wheat is ripe
+        let plan = scheduler::daily_schedule(&farm, &garden, &school, 4, "Sam").plan();
+        let titles: Vec<&str> = plan.order.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Feed Daisy, Clucky",
+                "Water plot 0",
+                "Water plot 1",
+                "Teach AG101 Soil Basics",
+                "Harvest plot 0",
+                "Tend the garden"
+            ]
+        );
+        // Sam's chores run from 6:00 to 8:40, right through the class
+        assert_eq!(plan.order[4].start, Time::hm(7, 10));
+        let overlaps = plan.conflicts.iter().filter(|c| matches!(c, Conflict::Overlap { .. })).count();
+        assert_eq!(overlaps, 2);
+
+        // Once the chores are done, nothing is left for day 4 but the class
+        farm::animals::care(&mut farm, 4);
+        farm::crops::water(&mut farm, 0, 4).unwrap();
+        farm::crops::water(&mut farm, 1, 4).unwrap();
+        farm::crops::harvest(&mut farm, 0, 4).unwrap();
+        garden::tend(&mut garden, 4);
+        let plan = scheduler::daily_schedule(&farm, &garden, &school, 4, "Sam").plan();
+        assert_eq!(plan.order.len(), 1);
+        assert!(!plan.has_conflicts());
+    }
+
+    #[test]
+    fn test_show_farm_status_renders_state() {
+        let mut farm = farm();
+        farm::animals::care(&mut farm, 3);
+        farm::animals::add(&mut farm, "Woolly", Species::Sheep).unwrap();
+
+        assert_eq!(
+            farm::show_farm_status(&farm, 3),
+            "Green Acres has 3 animal(s) and 3 plot(s), 1 planted on day 3\n\
+             \x20 Plot 0: Wheat, 1 day(s) to harvest, needs water\n\
+             \x20 Plot 1: empty\n\
+             \x20 Plot 2: empty\n\
+             \x20 Daisy (Cow): fed\n\
+             \x20 Clucky (Chicken): fed\n\
+             \x20 Woolly (Sheep): hungry"
+        );
+    }
+}
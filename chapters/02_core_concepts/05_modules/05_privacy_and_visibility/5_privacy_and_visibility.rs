@@ -187,6 +187,9 @@ fn main() {
     // This would cause an error - can't use private enum
     // let growth = plant_types::GrowthRate::Fast;
     
+    // garden_simulation.rs keeps `plants` and `plant_types` (with a private
+    // GrowthRate) but lets the plants dry out, grow and wilt day by day
+
     //------------------------------------------------------
     // MODULE PRIVACY
     //------------------------------------------------------
@@ -222,6 +225,9 @@ fn main() {
     
     // This would cause an error - can't access private module
     // garden_center::inventory::add_to_inventory();
+
+    // garden_center_checkout.rs keeps this module layout but gives the
+    // garden center real stock, carts and a checkout
 }
 
 //------------------------------------------------------
@@ -235,6 +241,9 @@ fn main() {
 // - Books should have public title and author, but private ISBN
 // - Library should be able to add and find books
 // - Users should be able to check out books but not modify the library's records directly
+//
+// Once you're done, challenge_1_library_system.rs shows a complete library
+// with members, loans, hold queues, fines and ISBN validation.
 
 mod challenge_1_library_system {
     // TODO: Implement the library system with appropriate privacy settings
@@ -254,6 +263,9 @@ mod challenge_1_library_system {
 //
 // Fix the privacy modifiers in this module hierarchy to make the
 // code in the test function work correctly.
+//
+// Once you're done, challenge_2_bank_ledger.rs shows the same bank with
+// generated account ids, a double-entry ledger and real balances.
 
 mod challenge_2_privacy {
     // This module simulates a simple banking system
@@ -367,6 +379,9 @@ mod challenge_2_privacy {
 // - The content of a document can be read but not directly modified
 // - The version can be read but not modified at all
 // - Documents can be created and updated only through controlled methods
+//
+// Once you're done, challenge_3_document_history.rs shows a Document that
+// keeps every revision, with diffs, undo/redo and revert.
 
 mod challenge_3_document {
     struct Document {
@@ -0,0 +1,984 @@
+// Library System: A Working Version of Challenge 1
+//
+// Challenge 1 in 5_privacy_and_visibility.rs only hints at a `Book` and a
+// `Library`. This file builds a complete library, using privacy so members
+// can borrow books without ever touching the library's records directly:
+//
+// - `isbn`: ISBN-10 and ISBN-13 parsing with checksum validation. Both forms
+//   of the same book compare equal.
+// - `Book`: public title and author, private ISBN and copy count.
+// - `Library`: the catalog, members, loans with due dates, hold queues,
+//   overdue fines and search by title, author or ISBN.
+//
+// The library keeps its own calendar: days are counted from the day it
+// opened, and `advance_days` moves time forward. That keeps due dates and
+// fines easy to follow and the tests deterministic.
+//
+// Fines use the shared fixed-point `Money` type (chapters/shared/money.rs).
+//
+// Try challenge 1 yourself before reading this file!
+
+// The library only uses part of the money module
+#[allow(dead_code)]
+#[path = "../../../shared/money.rs"]
+mod money;
+
+fn main() {
+    println!("A working library system built with Rust's privacy rules!");
+
+    use library::{Library, SearchQuery};
+
+    let mut library = Library::new();
+    library.add_book("The Rust Programming Language", "Steve Klabnik", "978-1-7185-0310-6", 1).unwrap();
+    library.add_book("Programming Rust", "Jim Blandy", "978-1-4920-5254-8", 2).unwrap();
+    library.add_book("A Book on C", "Al Kelley", "0-8053-1677-9", 1).unwrap();
+
+    let ana = library.register_member("Ana");
+    let ben = library.register_member("Ben");
+
+    let due = library.checkout(ana, "9781718503106").unwrap();
+    println!("Ana borrowed The Rust Programming Language, due on day {}", due);
+
+    // The only copy is out, so Ben joins the hold queue
+    if let Err(e) = library.checkout(ben, "9781718503106") {
+        println!("Ben can't borrow it: {}", e);
+    }
+    let position = library.place_hold(ben, "9781718503106").unwrap();
+    println!("Ben is number {} in the queue", position);
+
+    // Ana returns the book three days late
+    library.advance_days(17).unwrap();
+    let fine = library.return_book(ana, "9781718503106").unwrap();
+    println!("Ana returned it on day {} and was fined {}", library.today(), fine);
+
+    for (isbn, ready_until) in library.holds_ready_for(ben) {
+        println!("Ben's hold on {} is ready until day {}", isbn, ready_until);
+    }
+    library.checkout(ben, "9781718503106").unwrap();
+
+    println!("\nSearching for \"programming\":");
+    for book in library.search(&SearchQuery::Title("programming")) {
+        println!("  {}", book);
+    }
+
+    // An ISBN-10 finds the same book as its ISBN-13
+    if let Some(book) = library.search(&SearchQuery::Isbn("0805316779")).first() {
+        println!("ISBN 0-8053-1677-9 is {}", book.title);
+    }
+}
+
+pub mod library {
+    use crate::money::{Currency, Money};
+    use std::collections::{BTreeMap, VecDeque};
+    use std::fmt;
+
+    use self::isbn::{Isbn, IsbnError};
+
+    /// Days since the library opened
+    pub type Day = u32;
+
+    /// Member ids are generated by `Library::register_member`
+    pub type MemberId = u32;
+
+    /// How long a loan lasts
+    pub const LOAN_DAYS: Day = 14;
+    /// How many books a member can have at once
+    pub const MAX_LOANS: usize = 5;
+    /// How many times a loan can be renewed
+    pub const MAX_RENEWALS: u32 = 1;
+    /// How long a returned copy waits for the member whose hold it fills
+    pub const HOLD_PICKUP_DAYS: Day = 7;
+    /// Overdue fines: 25 cents a day, at most $10 per loan
+    pub const FINE_PER_DAY_CENTS: i64 = 25;
+    pub const FINE_CAP_CENTS: i64 = 1_000;
+    /// Members owing this much can't borrow until they pay
+    pub const BLOCKING_FINE_CENTS: i64 = 500;
+    /// The calendar stops here, so due dates and hold deadlines still fit in a `Day`
+    pub const LAST_DAY: Day = Day::MAX - if LOAN_DAYS > HOLD_PICKUP_DAYS { LOAN_DAYS } else { HOLD_PICKUP_DAYS };
+
+    //------------------------------------------------------
+    // ISBN
+    //------------------------------------------------------
+
+    pub mod isbn {
+        use std::fmt;
+
+        /// Why a string is not a valid ISBN
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum IsbnError {
+            WrongLength(usize),
+            InvalidCharacter(char),
+            BadChecksum,
+            /// ISBN-13s start with 978 or 979
+            UnknownPrefix,
+        }
+
+        impl fmt::Display for IsbnError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    IsbnError::WrongLength(length) => {
+                        write!(f, "an ISBN has 10 or 13 digits, not {}", length)
+                    }
+                    IsbnError::InvalidCharacter(c) => write!(f, "'{}' is not allowed in an ISBN", c),
+                    IsbnError::BadChecksum => write!(f, "the ISBN check digit is wrong"),
+                    IsbnError::UnknownPrefix => write!(f, "an ISBN-13 starts with 978 or 979"),
+                }
+            }
+        }
+
+        impl std::error::Error for IsbnError {}
+
+        /// A validated ISBN, always stored in its 13-digit form
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct Isbn {
+            digits: String,
+        }
+
+        impl Isbn {
+            /// Parses an ISBN-10 or ISBN-13. Hyphens and spaces are ignored.
+            pub fn parse(text: &str) -> Result<Isbn, IsbnError> {
+                let compact: Vec<char> = text.chars().filter(|c| *c != '-' && *c != ' ').collect();
+                match compact.len() {
+                    10 => Isbn::from_isbn10(&compact),
+                    13 => Isbn::from_isbn13(&compact),
+                    length => Err(IsbnError::WrongLength(length)),
+                }
+            }
+
+            // ISBN-10: weights 10 down to 1, the sum must be divisible by 11.
+            // The check digit can be 'X', meaning 10.
+            fn from_isbn10(chars: &[char]) -> Result<Isbn, IsbnError> {
+                let mut sum = 0;
+                for (index, &c) in chars.iter().enumerate() {
+                    let value = match c {
+                        '0'..='9' => c as u32 - '0' as u32,
+                        'X' | 'x' if index == 9 => 10,
+                        _ => return Err(IsbnError::InvalidCharacter(c)),
+                    };
+                    sum += value * (10 - index as u32);
+                }
+                if sum % 11 != 0 {
+                    return Err(IsbnError::BadChecksum);
+                }
+
+                let mut digits: String = "978".chars().chain(chars[..9].iter().copied()).collect();
+                digits.push(isbn13_check_digit(&digits));
+                Ok(Isbn { digits })
+            }
+
+            // ISBN-13: weights alternate 1 and 3, the sum must be divisible by 10
+            fn from_isbn13(chars: &[char]) -> Result<Isbn, IsbnError> {
+                if let Some(&c) = chars.iter().find(|c| !c.is_ascii_digit()) {
+                    return Err(IsbnError::InvalidCharacter(c));
+                }
+                let digits: String = chars.iter().collect();
+                if !digits.starts_with("978") && !digits.starts_with("979") {
+                    return Err(IsbnError::UnknownPrefix);
+                }
+                if isbn13_check_digit(&digits[..12]) != chars[12] {
+                    return Err(IsbnError::BadChecksum);
+                }
+                Ok(Isbn { digits })
+            }
+
+            pub fn as_isbn13(&self) -> &str {
+                &self.digits
+            }
+
+            /// The ISBN-10 form, which only exists for 978 ISBNs
+            pub fn to_isbn10(&self) -> Option<String> {
+                let body = self.digits.strip_prefix("978")?.get(..9)?;
+                let sum: u32 = body
+                    .chars()
+                    .enumerate()
+                    .map(|(index, c)| (c as u32 - '0' as u32) * (10 - index as u32))
+                    .sum();
+                let check = match (11 - sum % 11) % 11 {
+                    10 => 'X',
+                    digit => char::from_digit(digit, 10)?,
+                };
+                Some(format!("{}{}", body, check))
+            }
+        }
+
+        // The check digit for the first 12 digits of an ISBN-13
+        fn isbn13_check_digit(first_twelve: &str) -> char {
+            let sum: u32 = first_twelve
+                .chars()
+                .enumerate()
+                .map(|(index, c)| (c as u32 - '0' as u32) * if index % 2 == 0 { 1 } else { 3 })
+                .sum();
+            char::from_digit((10 - sum % 10) % 10, 10).unwrap_or('0')
+        }
+
+        impl fmt::Display for Isbn {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.digits)
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // ERRORS
+    //------------------------------------------------------
+
+    /// Why a library operation was refused
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum LibraryError {
+        Isbn(IsbnError),
+        UnknownBook(Isbn),
+        UnknownMember(MemberId),
+        /// The same ISBN was added with a different title
+        ConflictingRecord(Isbn),
+        NoCopiesAvailable,
+        LoanLimitReached,
+        FinesOwed(Money),
+        AlreadyBorrowed,
+        NotBorrowed,
+        AlreadyOnHold,
+        NoHold,
+        RenewalLimitReached,
+        /// Someone is waiting for the book, so it can't be renewed
+        HoldsWaiting,
+        Overdue,
+        InvalidPayment(Money),
+        /// Adding the copies would overflow the book's copy count
+        TooManyCopies(Isbn),
+        /// The calendar can't move past `LAST_DAY`
+        CalendarFull,
+    }
+
+    impl fmt::Display for LibraryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                LibraryError::Isbn(e) => write!(f, "invalid ISBN: {}", e),
+                LibraryError::UnknownBook(isbn) => write!(f, "no book with ISBN {}", isbn),
+                LibraryError::UnknownMember(id) => write!(f, "no member #{}", id),
+                LibraryError::ConflictingRecord(isbn) => {
+                    write!(f, "ISBN {} is already in the catalog under another title", isbn)
+                }
+                LibraryError::NoCopiesAvailable => write!(f, "no copies are available"),
+                LibraryError::LoanLimitReached => write!(f, "members can borrow at most {} books", MAX_LOANS),
+                LibraryError::FinesOwed(amount) => write!(f, "{} in fines must be paid first", amount),
+                LibraryError::AlreadyBorrowed => write!(f, "the member already has this book"),
+                LibraryError::NotBorrowed => write!(f, "the member doesn't have this book"),
+                LibraryError::AlreadyOnHold => write!(f, "the member already has a hold on this book"),
+                LibraryError::NoHold => write!(f, "the member has no hold on this book"),
+                LibraryError::RenewalLimitReached => {
+                    write!(f, "a loan can be renewed at most {} time(s)", MAX_RENEWALS)
+                }
+                LibraryError::HoldsWaiting => write!(f, "other members are waiting for this book"),
+                LibraryError::Overdue => write!(f, "overdue books can't be renewed"),
+                LibraryError::InvalidPayment(amount) => write!(f, "can't pay {}", amount),
+                LibraryError::TooManyCopies(isbn) => write!(f, "too many copies of ISBN {}", isbn),
+                LibraryError::CalendarFull => write!(f, "the calendar ends on day {}", LAST_DAY),
+            }
+        }
+    }
+
+    impl std::error::Error for LibraryError {}
+
+    impl From<IsbnError> for LibraryError {
+        fn from(e: IsbnError) -> Self {
+            LibraryError::Isbn(e)
+        }
+    }
+
+    //------------------------------------------------------
+    // RECORDS
+    //------------------------------------------------------
+
+    /// A title in the catalog. Anyone can read the title and author;
+    /// the ISBN and copy count are managed by the library.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Book {
+        pub title: String,
+        pub author: String,
+        isbn: Isbn,
+        copies: u32,
+    }
+
+    impl Book {
+        pub fn isbn(&self) -> &Isbn {
+            &self.isbn
+        }
+
+        pub fn copies(&self) -> u32 {
+            self.copies
+        }
+    }
+
+    impl fmt::Display for Book {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} by {} (ISBN {})", self.title, self.author, self.isbn)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Member {
+        id: MemberId,
+        name: String,
+        fines_owed: Money,
+    }
+
+    impl Member {
+        pub fn id(&self) -> MemberId {
+            self.id
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn fines_owed(&self) -> Money {
+            self.fines_owed
+        }
+    }
+
+    /// A book a member has borrowed
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Loan {
+        member: MemberId,
+        isbn: Isbn,
+        borrowed_on: Day,
+        due_on: Day,
+        renewals: u32,
+    }
+
+    impl Loan {
+        pub fn member(&self) -> MemberId {
+            self.member
+        }
+
+        pub fn isbn(&self) -> &Isbn {
+            &self.isbn
+        }
+
+        pub fn borrowed_on(&self) -> Day {
+            self.borrowed_on
+        }
+
+        pub fn due_on(&self) -> Day {
+            self.due_on
+        }
+
+        pub fn renewals(&self) -> u32 {
+            self.renewals
+        }
+
+        /// The fine for returning this loan on `day`
+        pub fn fine_on(&self, day: Day) -> Money {
+            let days_late = day.saturating_sub(self.due_on) as i64;
+            Money::usd((days_late * FINE_PER_DAY_CENTS).min(FINE_CAP_CENTS))
+        }
+    }
+
+    // A member waiting for a book. Once a copy is set aside for them,
+    // `ready_until` says how long it stays on the hold shelf.
+    #[derive(Debug, Clone)]
+    struct Hold {
+        member: MemberId,
+        ready_until: Option<Day>,
+    }
+
+    /// What to search the catalog for
+    pub enum SearchQuery<'a> {
+        /// Every word must appear in the title, ignoring case
+        Title(&'a str),
+        /// Every word must appear in the author's name, ignoring case
+        Author(&'a str),
+        /// ISBN-10 or ISBN-13
+        Isbn(&'a str),
+    }
+
+    fn matches_words(text: &str, query: &str) -> bool {
+        let text = text.to_lowercase();
+        query.split_whitespace().all(|word| text.contains(&word.to_lowercase()))
+    }
+
+    //------------------------------------------------------
+    // LIBRARY
+    //------------------------------------------------------
+
+    /// The library and all of its records. Members act through its
+    /// methods; none of the records can be changed from outside.
+    pub struct Library {
+        today: Day,
+        books: BTreeMap<Isbn, Book>,
+        members: BTreeMap<MemberId, Member>,
+        next_member_id: MemberId,
+        loans: Vec<Loan>,
+        // Hold queues per book, first come first served
+        holds: BTreeMap<Isbn, VecDeque<Hold>>,
+    }
+
+    impl Default for Library {
+        fn default() -> Self {
+            Library::new()
+        }
+    }
+
+    impl Library {
+        /// A library on its opening day (day 0)
+        pub fn new() -> Library {
+            Library {
+                today: 0,
+                books: BTreeMap::new(),
+                members: BTreeMap::new(),
+                next_member_id: 1,
+                loans: Vec::new(),
+                holds: BTreeMap::new(),
+            }
+        }
+
+        pub fn today(&self) -> Day {
+            self.today
+        }
+
+        /// Moves the calendar forward. Holds nobody picked up in time
+        /// expire and their copies go to the next member in the queue.
+        /// Returns the expired holds, or an error if that would go past `LAST_DAY`.
+        pub fn advance_days(&mut self, days: Day) -> Result<Vec<(MemberId, Isbn)>, LibraryError> {
+            self.today = self
+                .today
+                .checked_add(days)
+                .filter(|&day| day <= LAST_DAY)
+                .ok_or(LibraryError::CalendarFull)?;
+
+            let mut expired = Vec::new();
+            for (isbn, queue) in self.holds.iter_mut() {
+                queue.retain(|hold| match hold.ready_until {
+                    Some(until) if until < self.today => {
+                        expired.push((hold.member, isbn.clone()));
+                        false
+                    }
+                    _ => true,
+                });
+            }
+            for (_, isbn) in &expired {
+                self.fill_holds(isbn);
+            }
+            Ok(expired)
+        }
+
+        //--------------------------------------------------
+        // Catalog
+        //--------------------------------------------------
+
+        /// Adds copies of a book. Adding an ISBN that is already in the
+        /// catalog adds to its copies.
+        pub fn add_book(&mut self, title: &str, author: &str, isbn: &str, copies: u32) -> Result<(), LibraryError> {
+            let isbn = Isbn::parse(isbn)?;
+            match self.books.get_mut(&isbn) {
+                Some(book) if book.title != title => return Err(LibraryError::ConflictingRecord(isbn)),
+                Some(book) => {
+                    book.copies = book.copies.checked_add(copies).ok_or(LibraryError::TooManyCopies(isbn.clone()))?;
+                }
+                None => {
+                    let book = Book {
+                        title: title.to_string(),
+                        author: author.to_string(),
+                        isbn: isbn.clone(),
+                        copies,
+                    };
+                    self.books.insert(isbn.clone(), book);
+                }
+            }
+            self.fill_holds(&isbn);
+            Ok(())
+        }
+
+        pub fn book(&self, isbn: &str) -> Result<&Book, LibraryError> {
+            let isbn = Isbn::parse(isbn)?;
+            self.books.get(&isbn).ok_or(LibraryError::UnknownBook(isbn))
+        }
+
+        /// Books matching the query, sorted by title
+        pub fn search(&self, query: &SearchQuery) -> Vec<&Book> {
+            let mut found: Vec<&Book> = match query {
+                SearchQuery::Title(words) => {
+                    self.books.values().filter(|book| matches_words(&book.title, words)).collect()
+                }
+                SearchQuery::Author(words) => {
+                    self.books.values().filter(|book| matches_words(&book.author, words)).collect()
+                }
+                SearchQuery::Isbn(text) => match Isbn::parse(text) {
+                    Ok(isbn) => self.books.get(&isbn).into_iter().collect(),
+                    Err(_) => Vec::new(),
+                },
+            };
+            found.sort_by(|a, b| a.title.cmp(&b.title));
+            found
+        }
+
+        /// Copies on the shelf: not on loan and not set aside for a hold
+        pub fn available_copies(&self, isbn: &str) -> Result<u32, LibraryError> {
+            let isbn = self.book(isbn)?.isbn.clone();
+            Ok(self.free_copies(&isbn))
+        }
+
+        fn free_copies(&self, isbn: &Isbn) -> u32 {
+            let copies = self.books.get(isbn).map_or(0, |book| book.copies);
+            let on_loan = self.loans.iter().filter(|loan| &loan.isbn == isbn).count() as u32;
+            let set_aside = self
+                .holds
+                .get(isbn)
+                .map_or(0, |queue| queue.iter().filter(|hold| hold.ready_until.is_some()).count() as u32);
+            copies.saturating_sub(on_loan + set_aside)
+        }
+
+        //--------------------------------------------------
+        // Members
+        //--------------------------------------------------
+
+        pub fn register_member(&mut self, name: &str) -> MemberId {
+            let id = self.next_member_id;
+            self.next_member_id += 1;
+            let member = Member {
+                id,
+                name: name.to_string(),
+                fines_owed: Money::usd(0),
+            };
+            self.members.insert(id, member);
+            id
+        }
+
+        pub fn member(&self, id: MemberId) -> Result<&Member, LibraryError> {
+            self.members.get(&id).ok_or(LibraryError::UnknownMember(id))
+        }
+
+        /// Pays off fines; returns what is still owed
+        pub fn pay_fine(&mut self, id: MemberId, amount: Money) -> Result<Money, LibraryError> {
+            let member = self.members.get_mut(&id).ok_or(LibraryError::UnknownMember(id))?;
+            // Fines are in dollars; other currencies don't compare with them
+            if amount.currency() != Currency::USD || !amount.is_positive() || amount > member.fines_owed {
+                return Err(LibraryError::InvalidPayment(amount));
+            }
+            member.fines_owed = member
+                .fines_owed
+                .checked_sub(amount)
+                .map_err(|_| LibraryError::InvalidPayment(amount))?;
+            Ok(member.fines_owed)
+        }
+
+        //--------------------------------------------------
+        // Loans
+        //--------------------------------------------------
+
+        /// Lends a copy to a member; returns the due date.
+        /// A member whose hold is ready picks up the copy set aside for them.
+        pub fn checkout(&mut self, member_id: MemberId, isbn: &str) -> Result<Day, LibraryError> {
+            let member = self.member(member_id)?;
+            if member.fines_owed.minor_units() >= BLOCKING_FINE_CENTS {
+                return Err(LibraryError::FinesOwed(member.fines_owed));
+            }
+            if self.loans_for(member_id).len() >= MAX_LOANS {
+                return Err(LibraryError::LoanLimitReached);
+            }
+            let isbn = self.book(isbn)?.isbn.clone();
+            if self.find_loan(member_id, &isbn).is_some() {
+                return Err(LibraryError::AlreadyBorrowed);
+            }
+
+            let queue = self.holds.entry(isbn.clone()).or_default();
+            let ready_hold = queue
+                .iter()
+                .position(|hold| hold.member == member_id && hold.ready_until.is_some());
+            match ready_hold {
+                Some(position) => {
+                    queue.remove(position);
+                }
+                None if self.free_copies(&isbn) == 0 => return Err(LibraryError::NoCopiesAvailable),
+                None => {}
+            }
+
+            let due_on = self.today + LOAN_DAYS;
+            self.loans.push(Loan {
+                member: member_id,
+                isbn,
+                borrowed_on: self.today,
+                due_on,
+                renewals: 0,
+            });
+            Ok(due_on)
+        }
+
+        /// Takes a book back and charges any overdue fine, which is returned.
+        /// The copy goes to the next member waiting for it, if any.
+        pub fn return_book(&mut self, member_id: MemberId, isbn: &str) -> Result<Money, LibraryError> {
+            self.member(member_id)?;
+            let isbn = self.book(isbn)?.isbn.clone();
+            let index = self.find_loan(member_id, &isbn).ok_or(LibraryError::NotBorrowed)?;
+
+            let loan = self.loans.remove(index);
+            let fine = loan.fine_on(self.today);
+            if let Some(member) = self.members.get_mut(&member_id) {
+                member.fines_owed = Money::usd(member.fines_owed.minor_units() + fine.minor_units());
+            }
+            self.fill_holds(&isbn);
+            Ok(fine)
+        }
+
+        /// Extends a loan by another `LOAN_DAYS` from today; returns the new due date
+        pub fn renew(&mut self, member_id: MemberId, isbn: &str) -> Result<Day, LibraryError> {
+            let isbn = self.book(isbn)?.isbn.clone();
+            let index = self.find_loan(member_id, &isbn).ok_or(LibraryError::NotBorrowed)?;
+            if self.holds.get(&isbn).is_some_and(|queue| !queue.is_empty()) {
+                return Err(LibraryError::HoldsWaiting);
+            }
+
+            let today = self.today;
+            let loan = &mut self.loans[index];
+            if loan.due_on < today {
+                return Err(LibraryError::Overdue);
+            }
+            if loan.renewals >= MAX_RENEWALS {
+                return Err(LibraryError::RenewalLimitReached);
+            }
+            loan.renewals += 1;
+            loan.due_on = today + LOAN_DAYS;
+            Ok(loan.due_on)
+        }
+
+        pub fn loans_for(&self, member_id: MemberId) -> Vec<&Loan> {
+            self.loans.iter().filter(|loan| loan.member == member_id).collect()
+        }
+
+        pub fn overdue_loans(&self) -> Vec<&Loan> {
+            self.loans.iter().filter(|loan| loan.due_on < self.today).collect()
+        }
+
+        fn find_loan(&self, member_id: MemberId, isbn: &Isbn) -> Option<usize> {
+            self.loans
+                .iter()
+                .position(|loan| loan.member == member_id && &loan.isbn == isbn)
+        }
+
+        //--------------------------------------------------
+        // Holds
+        //--------------------------------------------------
+
+        /// Joins the queue for a book; returns the member's place in it.
+        /// If a copy is on the shelf it is set aside right away.
+        pub fn place_hold(&mut self, member_id: MemberId, isbn: &str) -> Result<usize, LibraryError> {
+            self.member(member_id)?;
+            let isbn = self.book(isbn)?.isbn.clone();
+            if self.find_loan(member_id, &isbn).is_some() {
+                return Err(LibraryError::AlreadyBorrowed);
+            }
+            let queue = self.holds.entry(isbn.clone()).or_default();
+            if queue.iter().any(|hold| hold.member == member_id) {
+                return Err(LibraryError::AlreadyOnHold);
+            }
+            queue.push_back(Hold {
+                member: member_id,
+                ready_until: None,
+            });
+            let position = queue.len();
+            self.fill_holds(&isbn);
+            Ok(position)
+        }
+
+        pub fn cancel_hold(&mut self, member_id: MemberId, isbn: &str) -> Result<(), LibraryError> {
+            let isbn = self.book(isbn)?.isbn.clone();
+            let queue = self.holds.get_mut(&isbn).ok_or(LibraryError::NoHold)?;
+            let position = queue
+                .iter()
+                .position(|hold| hold.member == member_id)
+                .ok_or(LibraryError::NoHold)?;
+            queue.remove(position);
+            // A copy that was set aside goes to the next in line
+            self.fill_holds(&isbn);
+            Ok(())
+        }
+
+        /// The member's holds that can be picked up, with the last pickup day
+        pub fn holds_ready_for(&self, member_id: MemberId) -> Vec<(&Isbn, Day)> {
+            self.holds
+                .iter()
+                .flat_map(|(isbn, queue)| {
+                    queue
+                        .iter()
+                        .filter(move |hold| hold.member == member_id)
+                        .filter_map(move |hold| hold.ready_until.map(|until| (isbn, until)))
+                })
+                .collect()
+        }
+
+        /// The member's place in the queue for a book (1 is next)
+        pub fn hold_position(&self, member_id: MemberId, isbn: &str) -> Result<usize, LibraryError> {
+            let isbn = Isbn::parse(isbn)?;
+            self.holds
+                .get(&isbn)
+                .and_then(|queue| queue.iter().position(|hold| hold.member == member_id))
+                .map(|index| index + 1)
+                .ok_or(LibraryError::NoHold)
+        }
+
+        // Sets free copies aside for the members at the front of the queue
+        fn fill_holds(&mut self, isbn: &Isbn) {
+            let mut free = self.free_copies(isbn);
+            let ready_until = self.today + HOLD_PICKUP_DAYS;
+            if let Some(queue) = self.holds.get_mut(isbn) {
+                for hold in queue.iter_mut().filter(|hold| hold.ready_until.is_none()) {
+                    if free == 0 {
+                        break;
+                    }
+                    hold.ready_until = Some(ready_until);
+                    free -= 1;
+                }
+            }
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::library::isbn::{Isbn, IsbnError};
+    use super::library::*;
+    use super::money::{Currency, Money};
+
+    const RUST_BOOK: &str = "978-1-7185-0310-6";
+    const C_BOOK_10: &str = "0-8053-1677-9";
+
+    fn library() -> Library {
+        let mut library = Library::new();
+        library.add_book("The Rust Programming Language", "Steve Klabnik", RUST_BOOK, 1).unwrap();
+        library.add_book("Programming Rust", "Jim Blandy", "978-1-4920-5254-8", 2).unwrap();
+        library.add_book("A Book on C", "Al Kelley", C_BOOK_10, 1).unwrap();
+        library
+    }
+
+    #[test]
+    fn test_isbn_validation() {
+        // Test cases: (input, expected)
+        let cases = vec![
+            ("0-306-40615-2", Ok("9780306406157")),
+            ("978-0-306-40615-7", Ok("9780306406157")),
+            ("0-8044-2957-X", Ok("9780804429573")),
+            ("080442957x", Ok("9780804429573")),
+            ("979-10-90636-07-1", Ok("9791090636071")),
+            ("0-306-40615-3", Err(IsbnError::BadChecksum)),
+            ("978-0-306-40615-8", Err(IsbnError::BadChecksum)),
+            ("123-0-306-40615-7", Err(IsbnError::UnknownPrefix)),
+            ("X-306-40615-2", Err(IsbnError::InvalidCharacter('X'))),
+            ("978-0-306-4O615-7", Err(IsbnError::InvalidCharacter('O'))),
+            ("12345", Err(IsbnError::WrongLength(5))),
+        ];
+
+        for (input, expected) in cases {
+            let parsed = Isbn::parse(input);
+            let digits = parsed.as_ref().map(|isbn| isbn.as_isbn13());
+            assert_eq!(digits, expected.as_ref().map(|s| *s), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_isbn10_round_trip() {
+        assert_eq!(Isbn::parse("9780804429573").unwrap().to_isbn10(), Some("080442957X".to_string()));
+        assert_eq!(Isbn::parse(C_BOOK_10).unwrap().to_isbn10(), Some("0805316779".to_string()));
+        assert_eq!(Isbn::parse("9791090636071").unwrap().to_isbn10(), None);
+    }
+
+    #[test]
+    fn test_catalog_and_search() {
+        let mut library = library();
+
+        // Adding the same ISBN again adds copies, in either form
+        library.add_book("A Book on C", "Al Kelley", "9780805316773", 2).unwrap();
+        assert_eq!(library.book(C_BOOK_10).unwrap().copies(), 3);
+        assert!(matches!(
+            library.add_book("Another Title", "Someone", C_BOOK_10, 1),
+            Err(LibraryError::ConflictingRecord(_))
+        ));
+        assert!(matches!(library.add_book("Bad", "Bad", "0-8053-1677-0", 1), Err(LibraryError::Isbn(_))));
+
+        let titles = |books: Vec<&Book>| books.iter().map(|b| b.title.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            titles(library.search(&SearchQuery::Title("PROGRAMMING"))),
+            vec!["Programming Rust", "The Rust Programming Language"]
+        );
+        assert_eq!(titles(library.search(&SearchQuery::Title("rust language"))), vec!["The Rust Programming Language"]);
+        assert_eq!(titles(library.search(&SearchQuery::Author("kelley"))), vec!["A Book on C"]);
+        assert_eq!(titles(library.search(&SearchQuery::Isbn("9781718503106"))), vec!["The Rust Programming Language"]);
+        assert!(library.search(&SearchQuery::Isbn("not an isbn")).is_empty());
+    }
+
+    #[test]
+    fn test_checkout_and_return() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+
+        assert_eq!(library.checkout(ana, RUST_BOOK), Ok(LOAN_DAYS));
+        assert_eq!(library.available_copies(RUST_BOOK), Ok(0));
+        assert_eq!(library.checkout(ana, RUST_BOOK), Err(LibraryError::AlreadyBorrowed));
+        assert_eq!(library.loans_for(ana)[0].due_on(), LOAN_DAYS);
+
+        library.advance_days(10).unwrap();
+        assert_eq!(library.return_book(ana, RUST_BOOK), Ok(Money::usd(0)));
+        assert_eq!(library.available_copies(RUST_BOOK), Ok(1));
+        assert_eq!(library.return_book(ana, RUST_BOOK), Err(LibraryError::NotBorrowed));
+        assert_eq!(library.checkout(99, RUST_BOOK), Err(LibraryError::UnknownMember(99)));
+    }
+
+    #[test]
+    fn test_no_copies_and_loan_limit() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        let ben = library.register_member("Ben");
+
+        library.checkout(ana, RUST_BOOK).unwrap();
+        assert_eq!(library.checkout(ben, RUST_BOOK), Err(LibraryError::NoCopiesAvailable));
+
+        library.add_book("Test Book", "Anon", "0-306-40615-2", 1).unwrap();
+        library.add_book("Another Test Book", "Anon", "0-8044-2957-X", 1).unwrap();
+        library.add_book("A French Book", "Anon", "979-10-90636-07-1", 1).unwrap();
+        for isbn in ["0-306-40615-2", "0-8044-2957-X", "978-1-4920-5254-8", C_BOOK_10] {
+            library.checkout(ana, isbn).unwrap();
+        }
+        assert_eq!(library.loans_for(ana).len(), MAX_LOANS);
+        assert_eq!(library.checkout(ana, "979-10-90636-07-1"), Err(LibraryError::LoanLimitReached));
+    }
+
+    #[test]
+    fn test_overdue_fines() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        library.checkout(ana, RUST_BOOK).unwrap();
+        library.checkout(ana, C_BOOK_10).unwrap();
+
+        library.advance_days(LOAN_DAYS + 3).unwrap();
+        assert_eq!(library.overdue_loans().len(), 2);
+        assert_eq!(library.return_book(ana, RUST_BOOK), Ok(Money::usd(75)));
+
+        // The fine is capped per loan
+        library.advance_days(100).unwrap();
+        assert_eq!(library.return_book(ana, C_BOOK_10), Ok(Money::usd(FINE_CAP_CENTS)));
+        assert_eq!(library.member(ana).unwrap().fines_owed(), Money::usd(1_075));
+
+        // Members owing too much can't borrow until they pay
+        assert_eq!(library.checkout(ana, RUST_BOOK), Err(LibraryError::FinesOwed(Money::usd(1_075))));
+        assert_eq!(library.pay_fine(ana, Money::usd(2_000)), Err(LibraryError::InvalidPayment(Money::usd(2_000))));
+        assert_eq!(library.pay_fine(ana, Money::usd(1_000)), Ok(Money::usd(75)));
+        assert!(library.checkout(ana, RUST_BOOK).is_ok());
+    }
+
+    #[test]
+    fn test_fines_are_paid_in_dollars() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        library.checkout(ana, RUST_BOOK).unwrap();
+        library.advance_days(LOAN_DAYS + 16).unwrap();
+        assert_eq!(library.return_book(ana, RUST_BOOK), Ok(Money::usd(400)));
+
+        // 400 yen is not $4.00, and 5000 yen must not push the balance negative
+        for amount in [Money::new(400, Currency::JPY), Money::new(5_000, Currency::JPY)] {
+            assert_eq!(library.pay_fine(ana, amount), Err(LibraryError::InvalidPayment(amount)));
+        }
+        assert_eq!(library.member(ana).unwrap().fines_owed(), Money::usd(400));
+        assert_eq!(library.pay_fine(ana, Money::usd(400)), Ok(Money::usd(0)));
+    }
+
+    #[test]
+    fn test_renewals() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        let ben = library.register_member("Ben");
+        library.checkout(ana, RUST_BOOK).unwrap();
+
+        library.advance_days(10).unwrap();
+        assert_eq!(library.renew(ana, RUST_BOOK), Ok(10 + LOAN_DAYS));
+        assert_eq!(library.renew(ana, RUST_BOOK), Err(LibraryError::RenewalLimitReached));
+
+        library.checkout(ana, C_BOOK_10).unwrap();
+        library.place_hold(ben, C_BOOK_10).unwrap();
+        assert_eq!(library.renew(ana, C_BOOK_10), Err(LibraryError::HoldsWaiting));
+
+        library.advance_days(LOAN_DAYS + 1).unwrap();
+        library.cancel_hold(ben, C_BOOK_10).unwrap();
+        assert_eq!(library.renew(ana, C_BOOK_10), Err(LibraryError::Overdue));
+    }
+
+    #[test]
+    fn test_hold_queue() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        let ben = library.register_member("Ben");
+        let cy = library.register_member("Cy");
+        library.checkout(ana, RUST_BOOK).unwrap();
+
+        assert_eq!(library.place_hold(ben, RUST_BOOK), Ok(1));
+        assert_eq!(library.place_hold(cy, RUST_BOOK), Ok(2));
+        assert_eq!(library.place_hold(cy, RUST_BOOK), Err(LibraryError::AlreadyOnHold));
+        assert_eq!(library.place_hold(ana, RUST_BOOK), Err(LibraryError::AlreadyBorrowed));
+        assert!(library.holds_ready_for(ben).is_empty());
+
+        // The returned copy is set aside for Ben, not put back on the shelf
+        library.advance_days(5).unwrap();
+        library.return_book(ana, RUST_BOOK).unwrap();
+        assert_eq!(library.available_copies(RUST_BOOK), Ok(0));
+        let rust_book = Isbn::parse(RUST_BOOK).unwrap();
+        assert_eq!(library.holds_ready_for(ben), vec![(&rust_book, 5 + HOLD_PICKUP_DAYS)]);
+        assert_eq!(library.checkout(cy, RUST_BOOK), Err(LibraryError::NoCopiesAvailable));
+
+        library.checkout(ben, RUST_BOOK).unwrap();
+        assert_eq!(library.hold_position(cy, RUST_BOOK), Ok(1));
+    }
+
+    #[test]
+    fn test_expired_hold_passes_to_next_member() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+        let ben = library.register_member("Ben");
+        let cy = library.register_member("Cy");
+        library.checkout(ana, RUST_BOOK).unwrap();
+        library.place_hold(ben, RUST_BOOK).unwrap();
+        library.place_hold(cy, RUST_BOOK).unwrap();
+        library.return_book(ana, RUST_BOOK).unwrap();
+
+        // Ben never comes to pick it up
+        assert!(library.advance_days(HOLD_PICKUP_DAYS).unwrap().is_empty());
+        let expired = library.advance_days(1).unwrap();
+        assert_eq!(expired, vec![(ben, Isbn::parse(RUST_BOOK).unwrap())]);
+        assert_eq!(library.hold_position(ben, RUST_BOOK), Err(LibraryError::NoHold));
+        assert_eq!(library.holds_ready_for(cy).len(), 1);
+        assert!(library.checkout(cy, RUST_BOOK).is_ok());
+    }
+
+    #[test]
+    fn test_hold_on_available_book_is_ready_at_once() {
+        let mut library = library();
+        let ana = library.register_member("Ana");
+
+        assert_eq!(library.place_hold(ana, C_BOOK_10), Ok(1));
+        assert_eq!(library.holds_ready_for(ana).len(), 1);
+        assert_eq!(library.available_copies(C_BOOK_10), Ok(0));
+
+        // Cancelling puts the copy back on the shelf
+        library.cancel_hold(ana, C_BOOK_10).unwrap();
+        assert_eq!(library.available_copies(C_BOOK_10), Ok(1));
+        assert_eq!(library.cancel_hold(ana, C_BOOK_10), Err(LibraryError::NoHold));
+    }
+
+    #[test]
+    fn test_overflows_are_refused() {
+        let mut library = library();
+
+        let too_many = library.add_book("A Book on C", "Al Kelley", C_BOOK_10, u32::MAX);
+        assert_eq!(too_many, Err(LibraryError::TooManyCopies(Isbn::parse(C_BOOK_10).unwrap())));
+        assert_eq!(library.book(C_BOOK_10).unwrap().copies(), 1);
+
+        assert_eq!(library.advance_days(Day::MAX), Err(LibraryError::CalendarFull));
+        assert_eq!(library.today(), 0);
+
+        // The last day still leaves room for a loan's due date
+        let ana = library.register_member("Ana");
+        library.advance_days(LAST_DAY).unwrap();
+        assert_eq!(library.advance_days(1), Err(LibraryError::CalendarFull));
+        assert_eq!(library.checkout(ana, C_BOOK_10), Ok(LAST_DAY + LOAN_DAYS));
+    }
+}
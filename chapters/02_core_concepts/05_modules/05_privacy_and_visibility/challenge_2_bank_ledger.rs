@@ -0,0 +1,1122 @@
+// Bank Ledger: A Working Version of Challenge 2
+//
+// Challenge 2 in 5_privacy_and_visibility.rs sketches a bank whose customer
+// API only pretends: account ids are hardcoded, balances are fixed, and
+// transactions are only printed. This file builds the real thing, using
+// privacy to keep the books consistent:
+//
+// - `accounts`: accounts and a registry that generates account ids.
+//   Balances can only be changed from inside the `bank` module.
+// - `ledger`: a double-entry journal. Every entry moves money between
+//   accounts and its postings always add up to zero.
+// - `storage`: saving and loading accounts and the journal (private module).
+// - `customer_api`: the `Bank` that customers use: open accounts, deposit,
+//   withdraw, transfer, with insufficient-funds and overdraft rules.
+//
+// Amounts use the shared fixed-point `Money` type (chapters/shared/money.rs),
+// so balances are exact to the cent.
+//
+// Try challenge 2 yourself before reading this file!
+
+// The bank only uses part of the money module
+#[allow(dead_code)]
+#[path = "../../../shared/money.rs"]
+mod money;
+
+fn main() {
+    println!("A working bank ledger built with Rust's privacy rules!");
+
+    use bank::customer_api::Bank;
+    use money::{Currency, Money};
+
+    let dollars = |text: &str| Money::parse(text, Currency::USD).unwrap();
+    let mut bank = Bank::new();
+
+    let john = bank.open_account("John Doe", dollars("1000")).unwrap();
+    let jane = bank
+        .open_account_with_overdraft("Jane Roe", dollars("50"), dollars("200"))
+        .unwrap();
+    println!("Opened accounts #{} and #{}", john, jane);
+
+    bank.deposit(john, dollars("500")).unwrap();
+    bank.withdraw(john, dollars("200")).unwrap();
+    bank.transfer(john, jane, dollars("300")).unwrap();
+
+    // Jane may go up to $200 into overdraft, but no further
+    bank.withdraw(jane, dollars("500")).unwrap();
+    if let Err(e) = bank.withdraw(jane, dollars("100")) {
+        println!("Withdrawal refused: {}", e);
+    }
+
+    for id in [john, jane] {
+        println!("Balance of #{}: {}", id, bank.get_balance(id).unwrap());
+    }
+
+    println!("\nJournal:");
+    for entry in bank.ledger().entries() {
+        println!("  {}", entry);
+    }
+
+    // The balances can always be rebuilt from the journal alone
+    println!("\nBooks balanced: {}", bank.verify_books());
+}
+
+pub mod bank {
+    /// Account ids are generated by the `AccountRegistry`
+    pub type AccountId = u32;
+
+    /// The bank's own account on the other side of every deposit and
+    /// withdrawal. It represents cash entering or leaving the bank.
+    pub const CASH_ACCOUNT: AccountId = 0;
+
+    //------------------------------------------------------
+    // ACCOUNTS
+    //------------------------------------------------------
+
+    pub mod accounts {
+        use super::AccountId;
+        use crate::money::Money;
+        use std::collections::BTreeMap;
+
+        /// A customer account.
+        /// Everything can be read, but only the `bank` module can change the balance.
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Account {
+            id: AccountId,
+            owner: String,
+            balance: Money,
+            overdraft_limit: Money,
+        }
+
+        impl Account {
+            pub fn id(&self) -> AccountId {
+                self.id
+            }
+
+            pub fn owner(&self) -> &str {
+                &self.owner
+            }
+
+            pub fn balance(&self) -> Money {
+                self.balance
+            }
+
+            /// How far below zero the balance may go
+            pub fn overdraft_limit(&self) -> Money {
+                self.overdraft_limit
+            }
+
+            /// The most that can be withdrawn right now
+            pub fn available(&self) -> Money {
+                // Saturates instead of failing: a huge limit just means "no limit"
+                let minor = self
+                    .balance
+                    .minor_units()
+                    .saturating_add(self.overdraft_limit.minor_units());
+                Money::new(minor, self.balance.currency())
+            }
+
+            // Visible to the whole `bank` module, but not to customers
+            pub(in crate::bank) fn set_balance(&mut self, balance: Money) {
+                self.balance = balance;
+            }
+        }
+
+        /// Hands out account ids and owns every account
+        #[derive(Debug)]
+        pub struct AccountRegistry {
+            next_id: AccountId,
+            accounts: BTreeMap<AccountId, Account>,
+        }
+
+        impl AccountRegistry {
+            /// Customer account ids start here; lower ids are reserved for the bank
+            pub const FIRST_ID: AccountId = 10_001;
+
+            pub fn new() -> Self {
+                AccountRegistry {
+                    next_id: AccountRegistry::FIRST_ID,
+                    accounts: BTreeMap::new(),
+                }
+            }
+
+            /// Creates an account with a fresh id and a zero balance
+            pub(in crate::bank) fn register(&mut self, owner: &str, overdraft_limit: Money) -> Result<AccountId, String> {
+                let id = self.next_id;
+                self.insert(id, owner, overdraft_limit)?;
+                Ok(id)
+            }
+
+            /// Re-creates an account with a known id (used when loading).
+            /// Refuses reserved ids, ids already in use, and the last possible
+            /// id, which would leave no id for the next account.
+            pub(in crate::bank) fn insert(&mut self, id: AccountId, owner: &str, overdraft_limit: Money) -> Result<(), String> {
+                if id < AccountRegistry::FIRST_ID {
+                    return Err(format!("account id {} is reserved for the bank", id));
+                }
+                if self.accounts.contains_key(&id) {
+                    return Err(format!("duplicate account id {}", id));
+                }
+                if overdraft_limit.is_negative() {
+                    return Err(format!("account id {} has a negative overdraft limit {}", id, overdraft_limit));
+                }
+                let after = id.checked_add(1).ok_or_else(|| format!("account id {} is too large", id))?;
+                self.next_id = self.next_id.max(after);
+                self.accounts.insert(
+                    id,
+                    Account {
+                        id,
+                        owner: owner.to_string(),
+                        balance: Money::zero(overdraft_limit.currency()),
+                        overdraft_limit,
+                    },
+                );
+                Ok(())
+            }
+
+            pub fn get(&self, id: AccountId) -> Option<&Account> {
+                self.accounts.get(&id)
+            }
+
+            pub(in crate::bank) fn get_mut(&mut self, id: AccountId) -> Option<&mut Account> {
+                self.accounts.get_mut(&id)
+            }
+
+            pub fn contains(&self, id: AccountId) -> bool {
+                self.accounts.contains_key(&id)
+            }
+
+            /// All accounts, ordered by id
+            pub fn iter(&self) -> impl Iterator<Item = &Account> {
+                self.accounts.values()
+            }
+
+            pub fn len(&self) -> usize {
+                self.accounts.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.accounts.is_empty()
+            }
+        }
+
+        impl Default for AccountRegistry {
+            fn default() -> Self {
+                AccountRegistry::new()
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // DOUBLE-ENTRY LEDGER
+    //------------------------------------------------------
+
+    pub mod ledger {
+        use super::AccountId;
+        use crate::money::{Currency, Money, MoneyError};
+        use std::collections::BTreeMap;
+        use std::fmt;
+
+        /// The kind of movement a journal entry records
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum EntryKind {
+            Deposit,
+            Withdrawal,
+            Transfer,
+        }
+
+        impl EntryKind {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    EntryKind::Deposit => "deposit",
+                    EntryKind::Withdrawal => "withdrawal",
+                    EntryKind::Transfer => "transfer",
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<EntryKind> {
+                match name {
+                    "deposit" => Some(EntryKind::Deposit),
+                    "withdrawal" => Some(EntryKind::Withdrawal),
+                    "transfer" => Some(EntryKind::Transfer),
+                    _ => None,
+                }
+            }
+        }
+
+        /// One side of a journal entry.
+        /// A positive amount adds money to the account, a negative one takes it away.
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Posting {
+            pub account: AccountId,
+            pub amount: Money,
+        }
+
+        /// A balanced set of postings recorded together
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct JournalEntry {
+            id: u64,
+            kind: EntryKind,
+            postings: Vec<Posting>,
+        }
+
+        impl JournalEntry {
+            pub fn id(&self) -> u64 {
+                self.id
+            }
+
+            pub fn kind(&self) -> EntryKind {
+                self.kind
+            }
+
+            pub fn postings(&self) -> &[Posting] {
+                &self.postings
+            }
+
+            /// The amount this entry moved into (or out of) an account
+            pub fn amount_for(&self, account: AccountId) -> Money {
+                let currency = self.postings[0].amount.currency();
+                let amounts = self.postings.iter().filter(|p| p.account == account).map(|p| p.amount);
+                // Recording checked that all postings add up, so a subset cannot overflow
+                Money::sum(amounts, currency).unwrap_or(Money::zero(currency))
+            }
+        }
+
+        impl fmt::Display for JournalEntry {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "#{} {}:", self.id, self.kind.name())?;
+                for posting in &self.postings {
+                    let sign = if posting.amount.is_negative() { "" } else { "+" };
+                    write!(f, " [{} {}{}]", posting.account, sign, posting.amount)?;
+                }
+                Ok(())
+            }
+        }
+
+        /// An append-only journal in a single currency. Entries can be added, never changed.
+        #[derive(Debug)]
+        pub struct Ledger {
+            currency: Currency,
+            entries: Vec<JournalEntry>,
+        }
+
+        impl Ledger {
+            pub fn new(currency: Currency) -> Self {
+                Ledger { currency, entries: Vec::new() }
+            }
+
+            pub fn currency(&self) -> Currency {
+                self.currency
+            }
+
+            /// Appends an entry. The postings must be in the ledger's currency
+            /// and add up to exactly zero: money is only ever moved, never
+            /// created or destroyed.
+            pub(in crate::bank) fn record(
+                &mut self,
+                kind: EntryKind,
+                postings: Vec<Posting>,
+            ) -> Result<&JournalEntry, String> {
+                if postings.len() < 2 {
+                    return Err("an entry needs at least two postings".to_string());
+                }
+                let total = Money::sum(postings.iter().map(|p| p.amount), self.currency)
+                    .map_err(|e| e.to_string())?;
+                if !total.is_zero() {
+                    return Err(format!("postings do not balance (off by {})", total));
+                }
+
+                let id = self.entries.len() as u64 + 1;
+                self.entries.push(JournalEntry { id, kind, postings });
+                Ok(&self.entries[self.entries.len() - 1])
+            }
+
+            pub fn entries(&self) -> &[JournalEntry] {
+                &self.entries
+            }
+
+            /// Every entry that touched an account, oldest first
+            pub fn history(&self, account: AccountId) -> Vec<&JournalEntry> {
+                self.entries
+                    .iter()
+                    .filter(|e| e.postings.iter().any(|p| p.account == account))
+                    .collect()
+            }
+
+            /// Rebuilds every account balance from scratch by replaying the journal
+            pub fn replay(&self) -> Result<BTreeMap<AccountId, Money>, MoneyError> {
+                let mut balances = BTreeMap::new();
+                for entry in &self.entries {
+                    for posting in &entry.postings {
+                        let balance = balances
+                            .entry(posting.account)
+                            .or_insert(Money::zero(self.currency));
+                        *balance = balance.checked_add(posting.amount)?;
+                    }
+                }
+                Ok(balances)
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // STORAGE
+    //------------------------------------------------------
+
+    // Private module: only the customer API decides when to save or load.
+    //
+    // File format, one record per line, fields separated by tabs. Amounts
+    // are whole minor units (cents for USD):
+    //
+    //     bank 2 USD
+    //     account	10001	John Doe	0
+    //     entry	deposit	0:-100000	10001:100000
+    //
+    // Version 1 files have no currency and store amounts as decimal dollars
+    // ("1000", "12.25"); they are read as USD.
+    mod storage {
+        use super::accounts::AccountRegistry;
+        use super::ledger::{EntryKind, Ledger, Posting};
+        use super::{AccountId, CASH_ACCOUNT};
+        use crate::money::{Currency, Money};
+        use std::fmt::Write as _;
+
+        const FORMAT_VERSION: u32 = 2;
+
+        pub fn serialize(registry: &AccountRegistry, ledger: &Ledger) -> String {
+            let mut out = String::new();
+            let _ = writeln!(out, "bank {} {}", FORMAT_VERSION, ledger.currency());
+            for account in registry.iter() {
+                let _ = writeln!(
+                    out,
+                    "account\t{}\t{}\t{}",
+                    account.id(),
+                    account.owner(),
+                    account.overdraft_limit().minor_units()
+                );
+            }
+            for entry in ledger.entries() {
+                let _ = write!(out, "entry\t{}", entry.kind().name());
+                for posting in entry.postings() {
+                    let _ = write!(out, "\t{}:{}", posting.account, posting.amount.minor_units());
+                }
+                out.push('\n');
+            }
+            out
+        }
+
+        fn parse_number<T: std::str::FromStr>(text: &str, what: &str) -> Result<T, String> {
+            text.parse().map_err(|_| format!("invalid {} '{}'", what, text))
+        }
+
+        // Reads an amount in the given format version
+        fn parse_amount(text: &str, version: u32, currency: Currency) -> Result<Money, String> {
+            if version == 1 {
+                let dollars: f64 = parse_number(text, "amount")?;
+                Money::from_f64(dollars, currency).map_err(|e| e.to_string())
+            } else {
+                Ok(Money::new(parse_number(text, "amount")?, currency))
+            }
+        }
+
+        fn parse_header(line: Option<&str>) -> Result<(u32, Currency), String> {
+            let fields: Vec<&str> = line.unwrap_or("").split(' ').collect();
+            match fields.as_slice() {
+                ["bank", "1"] => Ok((1, Currency::USD)),
+                ["bank", "2", code] => Currency::from_code(code)
+                    .map(|currency| (2, currency))
+                    .ok_or_else(|| format!("unknown currency '{}'", code)),
+                ["bank", version, ..] => Err(format!("unsupported format version '{}'", version)),
+                _ => Err("missing 'bank' header".to_string()),
+            }
+        }
+
+        // Reads one line of the file into the registry or the ledger
+        fn parse_record(
+            line: &str,
+            version: u32,
+            registry: &mut AccountRegistry,
+            ledger: &mut Ledger,
+        ) -> Result<(), String> {
+            let currency = ledger.currency();
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                [] | [""] => Ok(()),
+                ["account", id, owner, limit] => {
+                    let id = parse_number(id, "account id")?;
+                    let limit = parse_amount(limit, version, currency)?;
+                    registry.insert(id, owner, limit)
+                }
+                ["entry", kind, postings @ ..] => {
+                    let kind = EntryKind::from_name(kind)
+                        .ok_or_else(|| format!("unknown entry kind '{}'", kind))?;
+                    let postings = postings
+                        .iter()
+                        .map(|p| {
+                            let (account, amount) = p
+                                .split_once(':')
+                                .ok_or_else(|| format!("invalid posting '{}'", p))?;
+                            let account: AccountId = parse_number(account, "account id")?;
+                            if account != CASH_ACCOUNT && !registry.contains(account) {
+                                return Err(format!("posting to unknown account {}", account));
+                            }
+                            Ok(Posting { account, amount: parse_amount(amount, version, currency)? })
+                        })
+                        .collect::<Result<Vec<_>, String>>()?;
+                    ledger.record(kind, postings).map(|_| ())
+                }
+                _ => Err("unrecognized record".to_string()),
+            }
+        }
+
+        pub fn deserialize(text: &str) -> Result<(AccountRegistry, Ledger), String> {
+            let mut lines = text.lines().enumerate();
+            let (version, currency) = parse_header(lines.next().map(|(_, line)| line))?;
+
+            let mut registry = AccountRegistry::new();
+            let mut ledger = Ledger::new(currency);
+            for (index, line) in lines {
+                parse_record(line, version, &mut registry, &mut ledger)
+                    .map_err(|e| format!("line {}: {}", index + 1, e))?;
+            }
+
+            // Every entry balances on its own, but together they must also
+            // respect each account's overdraft limit
+            let balances = ledger.replay().map_err(|e| e.to_string())?;
+            for account in registry.iter() {
+                let balance = balances.get(&account.id()).copied().unwrap_or(Money::zero(currency));
+                if balance.checked_add(account.overdraft_limit()).map_err(|e| e.to_string())?.is_negative() {
+                    return Err(format!(
+                        "account #{} has a balance of {}, past its {} overdraft limit",
+                        account.id(),
+                        balance,
+                        account.overdraft_limit()
+                    ));
+                }
+            }
+            Ok((registry, ledger))
+        }
+    }
+
+    //------------------------------------------------------
+    // CUSTOMER API
+    //------------------------------------------------------
+
+    pub mod customer_api {
+        use super::accounts::{Account, AccountRegistry};
+        use super::ledger::{EntryKind, JournalEntry, Ledger, Posting};
+        use super::{storage, AccountId, CASH_ACCOUNT};
+        use crate::money::{Currency, Money, MoneyError};
+        use std::fmt;
+        use std::path::Path;
+
+        /// Why a banking operation was refused
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum BankError {
+            AccountNotFound(AccountId),
+            InvalidAmount(Money),
+            InvalidOwner(String),
+            SameAccount(AccountId),
+            /// Every account id is taken
+            NoAccountIdsLeft,
+            /// The account has no overdraft and not enough money
+            InsufficientFunds { account: AccountId, available: Money, requested: Money },
+            /// The withdrawal would go past the account's overdraft limit
+            OverdraftLimitExceeded { account: AccountId, limit: Money, requested: Money },
+            /// Wrong currency, or an amount too large to represent
+            Money(MoneyError),
+            Storage(String),
+        }
+
+        impl fmt::Display for BankError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    BankError::AccountNotFound(id) => write!(f, "account #{} not found", id),
+                    BankError::InvalidAmount(amount) => {
+                        write!(f, "amount must be positive, got {}", amount)
+                    }
+                    BankError::InvalidOwner(owner) => write!(f, "invalid owner name {:?}", owner),
+                    BankError::SameAccount(id) => {
+                        write!(f, "cannot transfer from account #{} to itself", id)
+                    }
+                    BankError::NoAccountIdsLeft => write!(f, "no account ids left"),
+                    BankError::InsufficientFunds { account, available, requested } => write!(
+                        f,
+                        "insufficient funds in account #{}: {} available, {} requested",
+                        account, available, requested
+                    ),
+                    BankError::OverdraftLimitExceeded { account, limit, requested } => write!(
+                        f,
+                        "withdrawing {} would exceed the {} overdraft limit of account #{}",
+                        requested, limit, account
+                    ),
+                    BankError::Money(e) => write!(f, "{}", e),
+                    BankError::Storage(message) => write!(f, "storage error: {}", message),
+                }
+            }
+        }
+
+        impl std::error::Error for BankError {}
+
+        impl From<MoneyError> for BankError {
+            fn from(e: MoneyError) -> Self {
+                BankError::Money(e)
+            }
+        }
+
+        /// The bank: an account registry plus the journal that explains every balance
+        #[derive(Debug)]
+        pub struct Bank {
+            registry: AccountRegistry,
+            ledger: Ledger,
+        }
+
+        impl Bank {
+            /// A bank that keeps its books in US dollars
+            pub fn new() -> Self {
+                Bank::with_currency(Currency::USD)
+            }
+
+            pub fn with_currency(currency: Currency) -> Self {
+                Bank {
+                    registry: AccountRegistry::new(),
+                    ledger: Ledger::new(currency),
+                }
+            }
+
+            pub fn currency(&self) -> Currency {
+                self.ledger.currency()
+            }
+
+            /// Opens an account without overdraft
+            pub fn open_account(&mut self, owner: &str, initial_deposit: Money) -> Result<AccountId, BankError> {
+                let no_overdraft = Money::zero(self.currency());
+                self.open_account_with_overdraft(owner, initial_deposit, no_overdraft)
+            }
+
+            /// Opens an account whose balance may go down to `-overdraft_limit`
+            pub fn open_account_with_overdraft(
+                &mut self,
+                owner: &str,
+                initial_deposit: Money,
+                overdraft_limit: Money,
+            ) -> Result<AccountId, BankError> {
+                if owner.trim().is_empty() || owner.contains(|c: char| c.is_control()) {
+                    return Err(BankError::InvalidOwner(owner.to_string()));
+                }
+                self.check_currency(initial_deposit)?;
+                self.check_currency(overdraft_limit)?;
+                if initial_deposit.is_negative() {
+                    return Err(BankError::InvalidAmount(initial_deposit));
+                }
+                if overdraft_limit.is_negative() {
+                    return Err(BankError::InvalidAmount(overdraft_limit));
+                }
+
+                let id = self.registry.register(owner, overdraft_limit).map_err(|_| BankError::NoAccountIdsLeft)?;
+                if initial_deposit.is_positive() {
+                    self.deposit(id, initial_deposit)?;
+                }
+                Ok(id)
+            }
+
+            fn check_currency(&self, amount: Money) -> Result<(), BankError> {
+                if amount.currency() == self.currency() {
+                    Ok(())
+                } else {
+                    Err(MoneyError::CurrencyMismatch(self.currency(), amount.currency()).into())
+                }
+            }
+
+            fn check_amount(&self, amount: Money) -> Result<(), BankError> {
+                self.check_currency(amount)?;
+                if amount.is_positive() {
+                    Ok(())
+                } else {
+                    Err(BankError::InvalidAmount(amount))
+                }
+            }
+
+            fn account(&self, id: AccountId) -> Result<&Account, BankError> {
+                self.registry.get(id).ok_or(BankError::AccountNotFound(id))
+            }
+
+            // Checks that `amount` can leave the account under its overdraft rules
+            fn check_funds(&self, id: AccountId, amount: Money) -> Result<(), BankError> {
+                let account = self.account(id)?;
+                if amount <= account.available() {
+                    return Ok(());
+                }
+                if account.overdraft_limit().is_zero() {
+                    Err(BankError::InsufficientFunds {
+                        account: id,
+                        available: account.available(),
+                        requested: amount,
+                    })
+                } else {
+                    Err(BankError::OverdraftLimitExceeded {
+                        account: id,
+                        limit: account.overdraft_limit(),
+                        requested: amount,
+                    })
+                }
+            }
+
+            // Records a balanced entry and applies it to the cached balances.
+            // New balances are computed first so an overflow changes nothing.
+            fn post(&mut self, kind: EntryKind, postings: Vec<Posting>) -> Result<u64, BankError> {
+                let mut updates = Vec::new();
+                for posting in &postings {
+                    if let Some(account) = self.registry.get(posting.account) {
+                        updates.push((posting.account, account.balance().checked_add(posting.amount)?));
+                    }
+                }
+
+                let id = self.ledger.record(kind, postings).map_err(BankError::Storage)?.id();
+                for (account_id, balance) in updates {
+                    if let Some(account) = self.registry.get_mut(account_id) {
+                        account.set_balance(balance);
+                    }
+                }
+                Ok(id)
+            }
+
+            /// Puts cash into an account; returns the journal entry id
+            pub fn deposit(&mut self, id: AccountId, amount: Money) -> Result<u64, BankError> {
+                self.check_amount(amount)?;
+                self.account(id)?;
+                self.post(
+                    EntryKind::Deposit,
+                    vec![
+                        Posting { account: CASH_ACCOUNT, amount: amount.checked_neg()? },
+                        Posting { account: id, amount },
+                    ],
+                )
+            }
+
+            /// Takes cash out of an account; returns the journal entry id
+            pub fn withdraw(&mut self, id: AccountId, amount: Money) -> Result<u64, BankError> {
+                self.check_amount(amount)?;
+                self.check_funds(id, amount)?;
+                self.post(
+                    EntryKind::Withdrawal,
+                    vec![
+                        Posting { account: id, amount: amount.checked_neg()? },
+                        Posting { account: CASH_ACCOUNT, amount },
+                    ],
+                )
+            }
+
+            /// Moves money between two accounts; returns the journal entry id
+            pub fn transfer(&mut self, from: AccountId, to: AccountId, amount: Money) -> Result<u64, BankError> {
+                self.check_amount(amount)?;
+                if from == to {
+                    return Err(BankError::SameAccount(from));
+                }
+                self.account(to)?;
+                self.check_funds(from, amount)?;
+                self.post(
+                    EntryKind::Transfer,
+                    vec![
+                        Posting { account: from, amount: amount.checked_neg()? },
+                        Posting { account: to, amount },
+                    ],
+                )
+            }
+
+            pub fn get_balance(&self, id: AccountId) -> Result<Money, BankError> {
+                self.account(id).map(|a| a.balance())
+            }
+
+            pub fn accounts(&self) -> &AccountRegistry {
+                &self.registry
+            }
+
+            pub fn ledger(&self) -> &Ledger {
+                &self.ledger
+            }
+
+            /// Every journal entry that touched the account, oldest first
+            pub fn statement(&self, id: AccountId) -> Result<Vec<&JournalEntry>, BankError> {
+                self.account(id)?;
+                Ok(self.ledger.history(id))
+            }
+
+            /// Throws away the cached balances and rebuilds them from the journal
+            pub fn rebuild_balances(&mut self) -> Result<(), BankError> {
+                let balances = self.ledger.replay()?;
+                let zero = Money::zero(self.currency());
+                let ids: Vec<AccountId> = self.registry.iter().map(|a| a.id()).collect();
+                for id in ids {
+                    let balance = balances.get(&id).copied().unwrap_or(zero);
+                    if let Some(account) = self.registry.get_mut(id) {
+                        account.set_balance(balance);
+                    }
+                }
+                Ok(())
+            }
+
+            /// True when every cached balance matches a replay of the journal
+            /// and all money in customer accounts came through the cash account
+            pub fn verify_books(&self) -> bool {
+                let Ok(balances) = self.ledger.replay() else {
+                    return false;
+                };
+                let zero = Money::zero(self.currency());
+                let accounts_match = self.registry.iter().all(|account| {
+                    balances.get(&account.id()).copied().unwrap_or(zero) == account.balance()
+                });
+                let total = Money::sum(balances.values().copied(), self.currency());
+                accounts_match && total == Ok(zero)
+            }
+
+            /// Saves accounts and the journal to a file
+            pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BankError> {
+                let text = storage::serialize(&self.registry, &self.ledger);
+                std::fs::write(path, text).map_err(|e| BankError::Storage(e.to_string()))
+            }
+
+            /// Loads accounts and the journal from a file.
+            /// Balances are not stored; they are rebuilt by replaying the journal.
+            pub fn load<P: AsRef<Path>>(path: P) -> Result<Bank, BankError> {
+                let text = std::fs::read_to_string(path).map_err(|e| BankError::Storage(e.to_string()))?;
+                let (registry, ledger) = storage::deserialize(&text).map_err(BankError::Storage)?;
+
+                let mut bank = Bank { registry, ledger };
+                bank.rebuild_balances()?;
+                Ok(bank)
+            }
+        }
+
+        impl Default for Bank {
+            fn default() -> Self {
+                Bank::new()
+            }
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::bank::accounts::AccountRegistry;
+    use super::bank::customer_api::{Bank, BankError};
+    use super::bank::ledger::EntryKind;
+    use super::bank::CASH_ACCOUNT;
+    use super::money::{Currency, Money, MoneyError};
+
+    fn usd(text: &str) -> Money {
+        Money::parse(text, Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn test_challenge_scenario() {
+        // The same steps as `test_bank` in challenge 2
+        let mut bank = Bank::new();
+        let id = bank.open_account("John Doe", usd("1000")).unwrap();
+        bank.deposit(id, usd("500")).unwrap();
+        bank.withdraw(id, usd("200")).unwrap();
+
+        assert_eq!(bank.get_balance(id), Ok(usd("1300")));
+    }
+
+    #[test]
+    fn test_account_ids_are_generated() {
+        let mut bank = Bank::new();
+        let first = bank.open_account("Alice", usd("0")).unwrap();
+        let second = bank.open_account("Bob", usd("0")).unwrap();
+
+        assert_eq!(first, AccountRegistry::FIRST_ID);
+        assert_eq!(second, first + 1);
+        assert_eq!(bank.accounts().get(second).unwrap().owner(), "Bob");
+    }
+
+    #[test]
+    fn test_transfer_moves_money() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("100")).unwrap();
+        let bob = bank.open_account("Bob", usd("20")).unwrap();
+
+        bank.transfer(alice, bob, usd("30")).unwrap();
+
+        assert_eq!(bank.get_balance(alice), Ok(usd("70")));
+        assert_eq!(bank.get_balance(bob), Ok(usd("50")));
+        let entry = bank.ledger().entries().last().unwrap();
+        assert_eq!(entry.kind(), EntryKind::Transfer);
+        assert_eq!(entry.amount_for(alice), usd("-30"));
+        assert_eq!(entry.amount_for(bob), usd("30"));
+        assert_eq!(entry.to_string(), format!("#3 transfer: [{} -$30.00] [{} +$30.00]", alice, bob));
+    }
+
+    #[test]
+    fn test_cent_balances_are_exact() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("0")).unwrap();
+        let bob = bank.open_account("Bob", usd("0")).unwrap();
+
+        // With f64 these would add up to 0.30000000000000004 and 0.9999999999999999
+        bank.deposit(alice, usd("0.10")).unwrap();
+        bank.deposit(alice, usd("0.20")).unwrap();
+        assert_eq!(bank.get_balance(alice), Ok(usd("0.30")));
+
+        for _ in 0..10 {
+            bank.transfer(alice, bob, usd("0.03")).unwrap();
+            bank.deposit(bob, usd("0.07")).unwrap();
+        }
+        assert_eq!(bank.get_balance(alice), Ok(usd("0")));
+        assert_eq!(bank.get_balance(bob), Ok(usd("1.00")));
+        assert!(bank.verify_books());
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("100")).unwrap();
+        let bob = bank.open_account("Bob", usd("0")).unwrap();
+
+        let expected = BankError::InsufficientFunds {
+            account: alice,
+            available: usd("100"),
+            requested: usd("150"),
+        };
+        assert_eq!(bank.withdraw(alice, usd("150")), Err(expected.clone()));
+        assert_eq!(bank.transfer(alice, bob, usd("150")), Err(expected));
+
+        // Nothing was recorded for the refused operations
+        assert_eq!(bank.get_balance(alice), Ok(usd("100")));
+        assert_eq!(bank.ledger().entries().len(), 1);
+
+        // Emptying the account exactly is fine, but not one cent more
+        assert!(bank.withdraw(alice, usd("99.99")).is_ok());
+        assert!(bank.withdraw(alice, usd("0.02")).is_err());
+        assert!(bank.withdraw(alice, usd("0.01")).is_ok());
+        assert_eq!(bank.get_balance(alice), Ok(usd("0")));
+    }
+
+    #[test]
+    fn test_overdraft_rules() {
+        let mut bank = Bank::new();
+        let id = bank.open_account_with_overdraft("Jane", usd("50"), usd("200")).unwrap();
+
+        bank.withdraw(id, usd("250")).unwrap();
+        assert_eq!(bank.get_balance(id), Ok(usd("-200")));
+
+        assert_eq!(
+            bank.withdraw(id, usd("0.01")),
+            Err(BankError::OverdraftLimitExceeded { account: id, limit: usd("200"), requested: usd("0.01") })
+        );
+
+        bank.deposit(id, usd("300")).unwrap();
+        assert_eq!(bank.get_balance(id), Ok(usd("100")));
+    }
+
+    #[test]
+    fn test_invalid_operations() {
+        let mut bank = Bank::new();
+        let id = bank.open_account("Alice", usd("10")).unwrap();
+
+        assert_eq!(bank.deposit(id, usd("0")), Err(BankError::InvalidAmount(usd("0"))));
+        assert_eq!(bank.deposit(id, usd("-5")), Err(BankError::InvalidAmount(usd("-5"))));
+        assert_eq!(bank.deposit(99, usd("5")), Err(BankError::AccountNotFound(99)));
+        assert_eq!(bank.transfer(id, id, usd("5")), Err(BankError::SameAccount(id)));
+        assert_eq!(bank.transfer(id, 99, usd("5")), Err(BankError::AccountNotFound(99)));
+        assert!(matches!(bank.open_account("", usd("0")), Err(BankError::InvalidOwner(_))));
+        assert!(matches!(bank.open_account("Tab\tName", usd("0")), Err(BankError::InvalidOwner(_))));
+
+        let euros = Money::new(500, Currency::EUR);
+        assert_eq!(
+            bank.deposit(id, euros),
+            Err(BankError::Money(MoneyError::CurrencyMismatch(Currency::USD, Currency::EUR)))
+        );
+    }
+
+    #[test]
+    fn test_overflow_is_refused() {
+        let mut bank = Bank::new();
+        let id = bank.open_account("Alice", Money::usd(i64::MAX)).unwrap();
+
+        assert_eq!(bank.deposit(id, usd("0.01")), Err(BankError::Money(MoneyError::Overflow)));
+        assert_eq!(bank.get_balance(id), Ok(Money::usd(i64::MAX)));
+        assert_eq!(bank.ledger().entries().len(), 1);
+    }
+
+    #[test]
+    fn test_every_entry_balances() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("100")).unwrap();
+        let bob = bank.open_account("Bob", usd("50")).unwrap();
+        bank.transfer(alice, bob, usd("25")).unwrap();
+        bank.withdraw(bob, usd("10")).unwrap();
+
+        for entry in bank.ledger().entries() {
+            let total = Money::sum(entry.postings().iter().map(|p| p.amount), Currency::USD);
+            assert_eq!(total, Ok(usd("0")), "entry {} does not balance", entry);
+        }
+
+        // The cash account mirrors all money held by customers
+        let balances = bank.ledger().replay().unwrap();
+        assert_eq!(balances[&CASH_ACCOUNT], usd("-140"));
+        assert!(bank.verify_books());
+    }
+
+    #[test]
+    fn test_statement_lists_account_history() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("100")).unwrap();
+        let bob = bank.open_account("Bob", usd("0")).unwrap();
+        bank.deposit(bob, usd("5")).unwrap();
+        bank.transfer(alice, bob, usd("25")).unwrap();
+
+        let kinds: Vec<EntryKind> = bank.statement(alice).unwrap().iter().map(|e| e.kind()).collect();
+        assert_eq!(kinds, vec![EntryKind::Deposit, EntryKind::Transfer]);
+        assert_eq!(bank.statement(bob).unwrap().len(), 2);
+        assert!(bank.statement(99).is_err());
+    }
+
+    #[test]
+    fn test_replay_rebuilds_balances() {
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice", usd("100")).unwrap();
+        let bob = bank.open_account_with_overdraft("Bob", usd("0"), usd("50")).unwrap();
+        bank.transfer(alice, bob, usd("40")).unwrap();
+        bank.withdraw(bob, usd("80")).unwrap();
+
+        let replayed = bank.ledger().replay().unwrap();
+        assert_eq!(replayed[&alice], usd("60"));
+        assert_eq!(replayed[&bob], usd("-40"));
+
+        bank.rebuild_balances().unwrap();
+        assert_eq!(bank.get_balance(alice), Ok(usd("60")));
+        assert_eq!(bank.get_balance(bob), Ok(usd("-40")));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("bank_ledger_{}.txt", std::process::id()));
+
+        let mut bank = Bank::new();
+        let alice = bank.open_account("Alice Smith", usd("100")).unwrap();
+        let bob = bank.open_account_with_overdraft("Bob", usd("0"), usd("75.50")).unwrap();
+        bank.transfer(alice, bob, usd("12.25")).unwrap();
+        bank.withdraw(bob, usd("60")).unwrap();
+        bank.save(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let loaded = Bank::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(text.starts_with("bank 2 USD\n"));
+        assert!(text.contains(&format!("entry\ttransfer\t{}:-1225\t{}:1225\n", alice, bob)));
+        assert_eq!(loaded.get_balance(alice), Ok(usd("87.75")));
+        assert_eq!(loaded.get_balance(bob), Ok(usd("-47.75")));
+        assert_eq!(loaded.accounts().get(bob).unwrap().overdraft_limit(), usd("75.50"));
+        assert_eq!(loaded.ledger().entries(), bank.ledger().entries());
+        assert!(loaded.verify_books());
+
+        // New accounts continue after the loaded ids
+        let mut loaded = loaded;
+        assert_eq!(loaded.open_account("Carol", usd("0")), Ok(bob + 1));
+    }
+
+    #[test]
+    fn test_load_version_1_file() {
+        let path = std::env::temp_dir().join(format!("bank_v1_{}.txt", std::process::id()));
+        let v1 = "bank 1\naccount\t10001\tAlice\t0\naccount\t10002\tBob\t75.5\n\
+                  entry\tdeposit\t0:-20.1\t10001:20.1\nentry\tdeposit\t0:-0.2\t10001:0.2\n\
+                  entry\ttransfer\t10001:-12.25\t10002:12.25\n";
+        std::fs::write(&path, v1).unwrap();
+
+        let result = Bank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let bank = result.unwrap();
+        assert_eq!(bank.get_balance(10001), Ok(usd("8.05")));
+        assert_eq!(bank.get_balance(10002), Ok(usd("12.25")));
+        assert_eq!(bank.accounts().get(10002).unwrap().overdraft_limit(), usd("75.50"));
+        assert!(bank.verify_books());
+    }
+
+    #[test]
+    fn test_load_rejects_unbalanced_journal() {
+        let path = std::env::temp_dir().join(format!("bank_bad_{}.txt", std::process::id()));
+        std::fs::write(&path, "bank 2 USD\naccount\t10001\tAlice\t0\nentry\tdeposit\t0:-1000\t10001:2000\n").unwrap();
+
+        let result = Bank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(BankError::Storage(message)) => assert!(message.starts_with("line 3:"), "{}", message),
+            other => panic!("expected a storage error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_header() {
+        let path = std::env::temp_dir().join(format!("bank_header_{}.txt", std::process::id()));
+        std::fs::write(&path, "bank 9 USD\n").unwrap();
+
+        let result = Bank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.err(), Some(BankError::Storage("unsupported format version '9'".to_string())));
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_accounts() {
+        // Test cases: (file contents, expected error)
+        let cases = vec![
+            ("bank 2 USD\naccount\t0\tMallory\t0\n", "line 2: account id 0 is reserved for the bank"),
+            (
+                "bank 2 USD\naccount\t10001\tAlice\t0\naccount\t10001\tMallory\t100000\n",
+                "line 3: duplicate account id 10001",
+            ),
+            ("bank 2 USD\naccount\t4294967295\tAlice\t0\n", "line 2: account id 4294967295 is too large"),
+            (
+                "bank 2 USD\naccount\t10001\tAlice\t1000\nentry\twithdrawal\t10001:-5000\t0:5000\n",
+                "account #10001 has a balance of -$50.00, past its $10.00 overdraft limit",
+            ),
+            (
+                "bank 2 USD\naccount\t10001\tMallory\t-1000\n",
+                "line 2: account id 10001 has a negative overdraft limit -$10.00",
+            ),
+            (
+                "bank 2 USD\naccount\t10001\tAlice\t0\nentry\tdeposit\t0:-5000\t55555:5000\n",
+                "line 3: posting to unknown account 55555",
+            ),
+        ];
+        for (index, (text, expected)) in cases.into_iter().enumerate() {
+            let path = std::env::temp_dir().join(format!("bank_invalid_{}_{}.txt", std::process::id(), index));
+            std::fs::write(&path, text).unwrap();
+
+            let result = Bank::load(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(result.err(), Some(BankError::Storage(expected.to_string())), "{:?}", text);
+        }
+
+        // Within the limit is fine
+        let path = std::env::temp_dir().join(format!("bank_overdrawn_{}.txt", std::process::id()));
+        std::fs::write(&path, "bank 2 USD\naccount\t10001\tAlice\t5000\nentry\twithdrawal\t10001:-5000\t0:5000\n").unwrap();
+        let result = Bank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap().get_balance(10001), Ok(usd("-50")));
+    }
+
+    #[test]
+    fn test_open_account_after_the_last_id() {
+        let path = std::env::temp_dir().join(format!("bank_last_id_{}.txt", std::process::id()));
+        std::fs::write(&path, "bank 2 USD\naccount\t4294967294\tAlice\t0\n").unwrap();
+
+        let result = Bank::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // No id is left for a new account
+        let mut bank = result.unwrap();
+        assert_eq!(bank.open_account("Bob", usd("10")), Err(BankError::NoAccountIdsLeft));
+        assert_eq!(bank.accounts().len(), 1);
+        assert!(bank.ledger().entries().is_empty());
+    }
+}
@@ -0,0 +1,587 @@
+// Document History: A Working Version of Challenge 3
+//
+// Challenge 3 in 5_privacy_and_visibility.rs has a `Document` whose
+// `update_content` bumps a version counter and throws the old content away.
+// This file keeps every revision instead, and uses privacy so the history
+// cannot be rewritten from outside:
+//
+// - Every update appends a `Revision` with its author and timestamp.
+//   Revisions can be read, never changed or removed.
+// - Any two revisions can be compared with a line-by-line diff.
+// - Undo and redo move between revisions. Editing after an undo starts a
+//   new branch, but the undone revisions stay in the history.
+// - Reverting to an old version records a new revision with its content.
+//
+// Try challenge 3 yourself before reading this file!
+
+fn main() {
+    println!("A document that remembers every revision!");
+
+    use document::Document;
+
+    let mut doc = Document::new(
+        "Privacy in Rust",
+        "Rust has a sophisticated privacy system...\nItems are private by default.",
+        "alice",
+    )
+    .unwrap();
+
+    doc.update_content(
+        "Rust's privacy system is based on modules.\nItems are private by default.\nUse `pub` to expose them.",
+        "bob",
+    )
+    .unwrap();
+    doc.update_content("Rust's privacy system is based on modules.\nUse `pub` to expose items.", "alice")
+        .unwrap();
+
+    println!("Current version: v{}", doc.version());
+    println!("\nChanges from v1 to v3:\n{}", doc.diff(1, 3).unwrap());
+
+    doc.undo().unwrap();
+    println!("\nAfter undo (v{}):\n{}", doc.version(), doc.content());
+    doc.redo().unwrap();
+
+    doc.revert_to(1, "carol").unwrap();
+    println!("\nHistory:");
+    for line in doc.log() {
+        println!("  {}", line);
+    }
+}
+
+pub mod document {
+    use std::fmt;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    //------------------------------------------------------
+    // CLOCK
+    //------------------------------------------------------
+
+    /// Where revision timestamps come from; tests use a fixed clock
+    pub trait Clock {
+        /// Seconds since the Unix epoch
+        fn now_secs(&self) -> u64;
+    }
+
+    pub struct SystemClock;
+
+    impl Clock for SystemClock {
+        fn now_secs(&self) -> u64 {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        }
+    }
+
+    /// Formats a Unix timestamp as "YYYY-MM-DD HH:MM:SS" (UTC)
+    pub fn format_timestamp(secs: u64) -> String {
+        let days = (secs / 86_400) as i64;
+        let rest = secs % 86_400;
+
+        // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            rest / 3_600,
+            rest % 3_600 / 60,
+            rest % 60
+        )
+    }
+
+    //------------------------------------------------------
+    // REVISIONS
+    //------------------------------------------------------
+
+    /// Why a history operation failed
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DocumentError {
+        UnknownRevision(u32),
+        NothingToUndo,
+        NothingToRedo,
+        EmptyAuthor,
+    }
+
+    impl fmt::Display for DocumentError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                DocumentError::UnknownRevision(number) => write!(f, "there is no revision v{}", number),
+                DocumentError::NothingToUndo => write!(f, "nothing to undo"),
+                DocumentError::NothingToRedo => write!(f, "nothing to redo"),
+                DocumentError::EmptyAuthor => write!(f, "a revision needs an author"),
+            }
+        }
+    }
+
+    impl std::error::Error for DocumentError {}
+
+    /// One saved version of the document. Read-only outside this module.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Revision {
+        number: u32,
+        /// The revision this one was edited from (None for the first)
+        parent: Option<u32>,
+        content: String,
+        author: String,
+        timestamp: u64,
+        summary: String,
+    }
+
+    impl Revision {
+        pub fn number(&self) -> u32 {
+            self.number
+        }
+
+        pub fn parent(&self) -> Option<u32> {
+            self.parent
+        }
+
+        pub fn content(&self) -> &str {
+            &self.content
+        }
+
+        pub fn author(&self) -> &str {
+            &self.author
+        }
+
+        /// Seconds since the Unix epoch
+        pub fn timestamp(&self) -> u64 {
+            self.timestamp
+        }
+
+        /// "Created", "Edited" or "Reverted to v2"
+        pub fn summary(&self) -> &str {
+            &self.summary
+        }
+    }
+
+    //------------------------------------------------------
+    // DIFF
+    //------------------------------------------------------
+
+    /// One line of a diff
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DiffLine {
+        Same(String),
+        Added(String),
+        Removed(String),
+    }
+
+    /// The line-by-line changes between two texts
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Diff {
+        lines: Vec<DiffLine>,
+    }
+
+    impl Diff {
+        /// Compares two texts line by line, keeping the longest common
+        /// subsequence of lines unchanged
+        pub fn between(old: &str, new: &str) -> Diff {
+            let old: Vec<&str> = old.lines().collect();
+            let new: Vec<&str> = new.lines().collect();
+
+            // common[i][j] = length of the LCS of old[i..] and new[j..]
+            let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+            for i in (0..old.len()).rev() {
+                for j in (0..new.len()).rev() {
+                    common[i][j] = if old[i] == new[j] {
+                        common[i + 1][j + 1] + 1
+                    } else {
+                        common[i + 1][j].max(common[i][j + 1])
+                    };
+                }
+            }
+
+            let mut lines = Vec::new();
+            let (mut i, mut j) = (0, 0);
+            while i < old.len() && j < new.len() {
+                if old[i] == new[j] {
+                    lines.push(DiffLine::Same(old[i].to_string()));
+                    i += 1;
+                    j += 1;
+                } else if common[i + 1][j] >= common[i][j + 1] {
+                    lines.push(DiffLine::Removed(old[i].to_string()));
+                    i += 1;
+                } else {
+                    lines.push(DiffLine::Added(new[j].to_string()));
+                    j += 1;
+                }
+            }
+            lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+            lines.extend(new[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+            Diff { lines }
+        }
+
+        pub fn lines(&self) -> &[DiffLine] {
+            &self.lines
+        }
+
+        pub fn added(&self) -> usize {
+            self.lines.iter().filter(|line| matches!(line, DiffLine::Added(_))).count()
+        }
+
+        pub fn removed(&self) -> usize {
+            self.lines.iter().filter(|line| matches!(line, DiffLine::Removed(_))).count()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.added() == 0 && self.removed() == 0
+        }
+    }
+
+    impl fmt::Display for Diff {
+        /// Unchanged lines start with "  ", added ones with "+ ", removed ones with "- "
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for (index, line) in self.lines.iter().enumerate() {
+                if index > 0 {
+                    writeln!(f)?;
+                }
+                match line {
+                    DiffLine::Same(text) => write!(f, "  {}", text)?,
+                    DiffLine::Added(text) => write!(f, "+ {}", text)?,
+                    DiffLine::Removed(text) => write!(f, "- {}", text)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    //------------------------------------------------------
+    // DOCUMENT
+    //------------------------------------------------------
+
+    /// A document with its complete revision history.
+    /// The title can be changed freely; content only through the methods below.
+    pub struct Document {
+        pub title: String,
+        // Append-only; revision n is at index n - 1
+        revisions: Vec<Revision>,
+        // The revision currently shown
+        head: u32,
+        // Revisions undone since the last edit, most recent last
+        redo_stack: Vec<u32>,
+        clock: Box<dyn Clock>,
+    }
+
+    impl Document {
+        /// Creates a document whose revisions are stamped with the system time
+        pub fn new(title: &str, content: &str, author: &str) -> Result<Document, DocumentError> {
+            Document::with_clock(title, content, author, Box::new(SystemClock))
+        }
+
+        pub fn with_clock(
+            title: &str,
+            content: &str,
+            author: &str,
+            clock: Box<dyn Clock>,
+        ) -> Result<Document, DocumentError> {
+            let mut document = Document {
+                title: title.to_string(),
+                revisions: Vec::new(),
+                head: 0,
+                redo_stack: Vec::new(),
+                clock,
+            };
+            document.record(content, author, "Created".to_string())?;
+            Ok(document)
+        }
+
+        // Appends a revision on top of the current one and makes it current
+        fn record(&mut self, content: &str, author: &str, summary: String) -> Result<u32, DocumentError> {
+            let author = author.trim();
+            if author.is_empty() {
+                return Err(DocumentError::EmptyAuthor);
+            }
+            let number = self.revisions.len() as u32 + 1;
+            self.revisions.push(Revision {
+                number,
+                parent: if self.head == 0 { None } else { Some(self.head) },
+                content: content.to_string(),
+                author: author.to_string(),
+                timestamp: self.clock.now_secs(),
+                summary,
+            });
+            self.head = number;
+            self.redo_stack.clear();
+            Ok(number)
+        }
+
+        fn revision_or_err(&self, number: u32) -> Result<&Revision, DocumentError> {
+            self.revision(number).ok_or(DocumentError::UnknownRevision(number))
+        }
+
+        /// Saves new content as a new revision; returns its version number
+        pub fn update_content(&mut self, new_content: &str, author: &str) -> Result<u32, DocumentError> {
+            self.record(new_content, author, "Edited".to_string())
+        }
+
+        /// The content of the current revision
+        pub fn content(&self) -> &str {
+            self.current().content()
+        }
+
+        /// The number of the current revision
+        pub fn version(&self) -> u32 {
+            self.head
+        }
+
+        pub fn current(&self) -> &Revision {
+            &self.revisions[self.head as usize - 1]
+        }
+
+        pub fn revision(&self, number: u32) -> Option<&Revision> {
+            let index = (number as usize).checked_sub(1)?;
+            self.revisions.get(index)
+        }
+
+        /// Every revision ever made, oldest first, including undone ones
+        pub fn history(&self) -> &[Revision] {
+            &self.revisions
+        }
+
+        /// Line changes from revision `from` to revision `to`
+        pub fn diff(&self, from: u32, to: u32) -> Result<Diff, DocumentError> {
+            let old = self.revision_or_err(from)?;
+            let new = self.revision_or_err(to)?;
+            Ok(Diff::between(old.content(), new.content()))
+        }
+
+        /// Goes back to the revision the current one was edited from
+        pub fn undo(&mut self) -> Result<u32, DocumentError> {
+            let parent = self.current().parent().ok_or(DocumentError::NothingToUndo)?;
+            self.redo_stack.push(self.head);
+            self.head = parent;
+            Ok(self.head)
+        }
+
+        /// Re-applies the last undone revision
+        pub fn redo(&mut self) -> Result<u32, DocumentError> {
+            self.head = self.redo_stack.pop().ok_or(DocumentError::NothingToRedo)?;
+            Ok(self.head)
+        }
+
+        pub fn can_undo(&self) -> bool {
+            self.current().parent().is_some()
+        }
+
+        pub fn can_redo(&self) -> bool {
+            !self.redo_stack.is_empty()
+        }
+
+        /// Records a new revision with the content of an older one.
+        /// Unlike undo, this shows up in the history with its own author.
+        pub fn revert_to(&mut self, number: u32, author: &str) -> Result<u32, DocumentError> {
+            let content = self.revision_or_err(number)?.content().to_string();
+            self.record(&content, author, format!("Reverted to v{}", number))
+        }
+
+        /// One line per revision: "v2 2024-05-01 12:00:00 bob: Edited (+1 -0)",
+        /// with "*" marking the current one
+        pub fn log(&self) -> Vec<String> {
+            self.revisions
+                .iter()
+                .map(|revision| {
+                    let marker = if revision.number == self.head { "*" } else { " " };
+                    let changes = match revision.parent {
+                        Some(parent) => {
+                            let diff = Diff::between(self.revisions[parent as usize - 1].content(), revision.content());
+                            format!(" (+{} -{})", diff.added(), diff.removed())
+                        }
+                        None => String::new(),
+                    };
+                    format!(
+                        "{}v{} {} {}: {}{}",
+                        marker,
+                        revision.number,
+                        format_timestamp(revision.timestamp),
+                        revision.author,
+                        revision.summary,
+                        changes
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::document::{format_timestamp, Clock, Diff, DiffLine, Document, DocumentError};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // A clock that moves one minute every time it is read
+    struct FakeClock {
+        now: Rc<Cell<u64>>,
+    }
+
+    impl Clock for FakeClock {
+        fn now_secs(&self) -> u64 {
+            let now = self.now.get();
+            self.now.set(now + 60);
+            now
+        }
+    }
+
+    // 2024-05-01 12:00:00 UTC
+    const START: u64 = 1_714_564_800;
+
+    fn document(content: &str) -> Document {
+        let clock = FakeClock { now: Rc::new(Cell::new(START)) };
+        Document::with_clock("Notes", content, "alice", Box::new(clock)).unwrap()
+    }
+
+    #[test]
+    fn test_challenge_scenario() {
+        // The same steps as `test_document` in challenge 3
+        let mut doc = document("Rust has a sophisticated privacy system...");
+        assert_eq!(doc.version(), 1);
+
+        doc.update_content("Rust's privacy system is based on modules...", "alice").unwrap();
+        assert_eq!(doc.version(), 2);
+        assert_eq!(doc.content(), "Rust's privacy system is based on modules...");
+    }
+
+    #[test]
+    fn test_every_revision_is_kept() {
+        let mut doc = document("one");
+        doc.update_content("two", "bob").unwrap();
+        doc.update_content("three", "carol").unwrap();
+
+        let contents: Vec<&str> = doc.history().iter().map(|r| r.content()).collect();
+        assert_eq!(contents, vec!["one", "two", "three"]);
+        let authors: Vec<&str> = doc.history().iter().map(|r| r.author()).collect();
+        assert_eq!(authors, vec!["alice", "bob", "carol"]);
+        let times: Vec<u64> = doc.history().iter().map(|r| r.timestamp()).collect();
+        assert_eq!(times, vec![START, START + 60, START + 120]);
+
+        assert_eq!(doc.revision(2).unwrap().parent(), Some(1));
+        assert!(doc.revision(0).is_none());
+        assert!(doc.revision(4).is_none());
+        assert_eq!(doc.update_content("four", "  "), Err(DocumentError::EmptyAuthor));
+        assert_eq!(doc.version(), 3);
+    }
+
+    #[test]
+    fn test_diff_between_revisions() {
+        let mut doc = document("a\nb\nc\nd");
+        doc.update_content("a\nc\nd\ne", "bob").unwrap();
+        doc.update_content("a\nX\nd\ne", "bob").unwrap();
+
+        let diff = doc.diff(1, 2).unwrap();
+        assert_eq!(
+            diff.lines(),
+            &[
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Same("d".to_string()),
+                DiffLine::Added("e".to_string()),
+            ]
+        );
+        assert_eq!((diff.added(), diff.removed()), (1, 1));
+        assert_eq!(doc.diff(1, 3).unwrap().to_string(), "  a\n- b\n- c\n+ X\n  d\n+ e");
+
+        // Diffs work in both directions, and a revision equals itself
+        assert_eq!(doc.diff(3, 1).unwrap().added(), 2);
+        assert!(doc.diff(2, 2).unwrap().is_empty());
+        assert_eq!(doc.diff(1, 9), Err(DocumentError::UnknownRevision(9)));
+    }
+
+    #[test]
+    fn test_diff_edge_cases() {
+        assert!(Diff::between("", "").is_empty());
+        assert_eq!(Diff::between("", "new").to_string(), "+ new");
+        assert_eq!(Diff::between("old", "").to_string(), "- old");
+        assert_eq!(Diff::between("x\nx\nx", "x\nx").removed(), 1);
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut doc = document("one");
+        doc.update_content("two", "alice").unwrap();
+        doc.update_content("three", "alice").unwrap();
+
+        assert_eq!(doc.undo(), Ok(2));
+        assert_eq!(doc.undo(), Ok(1));
+        assert_eq!(doc.content(), "one");
+        assert_eq!(doc.undo(), Err(DocumentError::NothingToUndo));
+        assert!(!doc.can_undo());
+
+        assert_eq!(doc.redo(), Ok(2));
+        assert_eq!(doc.redo(), Ok(3));
+        assert_eq!(doc.redo(), Err(DocumentError::NothingToRedo));
+        assert_eq!(doc.content(), "three");
+    }
+
+    #[test]
+    fn test_edit_after_undo_branches_but_keeps_history() {
+        let mut doc = document("one");
+        doc.update_content("two", "alice").unwrap();
+        doc.undo().unwrap();
+
+        assert_eq!(doc.update_content("two, take two", "bob"), Ok(3));
+        assert!(!doc.can_redo());
+        assert_eq!(doc.revision(3).unwrap().parent(), Some(1));
+        // The undone revision is still there
+        assert_eq!(doc.revision(2).unwrap().content(), "two");
+
+        // Undoing the new branch goes back to its parent, not to v2
+        assert_eq!(doc.undo(), Ok(1));
+    }
+
+    #[test]
+    fn test_revert_records_a_new_revision() {
+        let mut doc = document("first draft");
+        doc.update_content("second draft", "bob").unwrap();
+        doc.update_content("bad edit", "mallory").unwrap();
+
+        assert_eq!(doc.revert_to(1, "alice"), Ok(4));
+        assert_eq!(doc.content(), "first draft");
+        assert_eq!(doc.current().author(), "alice");
+        assert_eq!(doc.current().summary(), "Reverted to v1");
+        assert_eq!(doc.history().len(), 4);
+
+        // A revert can be undone like any edit
+        assert_eq!(doc.undo(), Ok(3));
+        assert_eq!(doc.revert_to(7, "alice"), Err(DocumentError::UnknownRevision(7)));
+    }
+
+    #[test]
+    fn test_log() {
+        let mut doc = document("a");
+        doc.update_content("a\nb", "bob").unwrap();
+        doc.undo().unwrap();
+
+        assert_eq!(
+            doc.log(),
+            vec![
+                "*v1 2024-05-01 12:00:00 alice: Created".to_string(),
+                " v2 2024-05-01 12:01:00 bob: Edited (+1 -0)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(START + 3_661), "2024-05-01 13:01:01");
+        assert_eq!(format_timestamp(4_102_444_799), "2099-12-31 23:59:59");
+    }
+}
@@ -0,0 +1,732 @@
+// Garden Center Checkout: Stock, Carts and Orders
+//
+// The `garden_center` module in 5_privacy_and_visibility.rs has a public
+// `plants::sell_plant`, a private `inventory::add_to_inventory` and a public
+// `restock` that only print. This file keeps the same module layout but
+// gives it real stock, and connects it to the shared `ShoppingCart`:
+//
+// - `inventory` (private): stock levels, reservations and reorder rules.
+//   Nothing outside `garden_center` can change stock directly.
+// - `plants::sell_plant`: a counter sale that takes stock right away.
+// - `restock`: tops up every plant that fell below its reorder point.
+// - `checkout`: a `CheckoutService` that reserves stock as items are added
+//   to a cart, prices the cart with the shared `PricingEngine`, and turns it
+//   into an `Order` record when the customer pays. Checkout fails if the
+//   stock is no longer there, and restocks automatically afterwards.
+//
+// Carts and prices come from chapters/shared/shopping_cart.rs.
+
+#[allow(dead_code)]
+#[path = "../../../shared/shopping_cart.rs"]
+mod shopping_cart;
+
+fn main() {
+    println!("A garden center with real stock and a checkout!");
+
+    use garden_center::checkout::CheckoutService;
+    use garden_center::{plants, restock, GardenCenter};
+    use shopping_cart::{Currency, Money, PricingEngine, PricingRule, TaxCategory};
+
+    let dollars = |text: &str| Money::parse(text, Currency::USD).unwrap();
+
+    let mut center = GardenCenter::new();
+    center.add_plant("Fern", dollars("12.50"), TaxCategory::Standard, 6).unwrap();
+    center.add_plant("Basil", dollars("3.99"), TaxCategory::Reduced, 20).unwrap();
+    center.set_reorder_rule("Fern", 3, 10).unwrap();
+
+    // A counter sale, as in the original `sell_plant`
+    plants::sell_plant(&mut center, "Basil", 2).unwrap();
+    println!("Basil left after a counter sale: {}", center.available("Basil").unwrap());
+
+    let mut engine = PricingEngine::new(Currency::USD);
+    engine.set_tax_rate(TaxCategory::Standard, 825).unwrap();
+    engine
+        .add_rule(PricingRule::BuyNGetM { item: "Basil".to_string(), buy: 3, free: 1 })
+        .unwrap();
+
+    let mut shop = CheckoutService::new(center, engine);
+    let orders = shop.subscribe();
+
+    let cart = shop.open_cart();
+    shop.add_to_cart(cart, "Fern", 4).unwrap();
+    shop.add_to_cart(cart, "Basil", 4).unwrap();
+    println!("Ferns still available while the cart is open: {}", shop.center().available("Fern").unwrap());
+
+    // Nobody else can take the reserved ferns
+    let other = shop.open_cart();
+    if let Err(e) = shop.add_to_cart(other, "Fern", 3) {
+        println!("Second cart refused: {}", e);
+    }
+    shop.abandon_cart(other).unwrap();
+
+    let order = shop.checkout(cart).unwrap();
+    println!("\n{}", order);
+
+    for record in orders.try_iter() {
+        println!("Order #{} emitted with total {}", record.id, record.receipt.total);
+    }
+
+    // Nothing below its reorder point is left
+    println!("Restocked afterwards: {}", restock(shop.center_mut()).len());
+}
+
+pub mod garden_center {
+    use crate::shopping_cart::{Money, TaxCategory};
+    use std::fmt;
+
+    /// Why a stock operation was refused
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum StockError {
+        UnknownPlant(String),
+        InsufficientStock { plant: String, requested: u32, available: u32 },
+        InvalidQuantity(u32),
+        AlreadyStocked(String),
+    }
+
+    impl fmt::Display for StockError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                StockError::UnknownPlant(name) => write!(f, "'{}' is not sold here", name),
+                StockError::InsufficientStock { plant, requested, available } => write!(
+                    f,
+                    "not enough '{}' in stock: {} requested, {} available",
+                    plant, requested, available
+                ),
+                StockError::InvalidQuantity(quantity) => write!(f, "invalid quantity {}", quantity),
+                StockError::AlreadyStocked(name) => write!(f, "'{}' is already in the catalog", name),
+            }
+        }
+    }
+
+    impl std::error::Error for StockError {}
+
+    /// A reorder that topped up a plant's stock
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Restock {
+        pub plant: String,
+        pub quantity: u32,
+        /// Stock on hand after the delivery
+        pub on_hand: u32,
+    }
+
+    //------------------------------------------------------
+    // INVENTORY (PRIVATE)
+    //------------------------------------------------------
+
+    // Private module: customers see availability through `GardenCenter`,
+    // but only code inside `garden_center` can move stock.
+    mod inventory {
+        use super::{Restock, StockError};
+        use crate::shopping_cart::{Money, TaxCategory};
+        use std::collections::BTreeMap;
+
+        #[derive(Debug, Clone)]
+        pub struct StockItem {
+            pub price: Money,
+            pub tax_category: TaxCategory,
+            pub on_hand: u32,
+            /// Held by open carts; not available to anyone else
+            pub reserved: u32,
+            /// Restock when the available stock drops below this...
+            pub reorder_point: u32,
+            /// ...by ordering this many
+            pub reorder_quantity: u32,
+        }
+
+        impl StockItem {
+            pub fn available(&self) -> u32 {
+                self.on_hand.saturating_sub(self.reserved)
+            }
+        }
+
+        #[derive(Debug, Default)]
+        pub struct Inventory {
+            items: BTreeMap<String, StockItem>,
+        }
+
+        impl Inventory {
+            pub fn get(&self, name: &str) -> Result<&StockItem, StockError> {
+                self.items.get(name).ok_or_else(|| StockError::UnknownPlant(name.to_string()))
+            }
+
+            fn get_mut(&mut self, name: &str) -> Result<&mut StockItem, StockError> {
+                self.items.get_mut(name).ok_or_else(|| StockError::UnknownPlant(name.to_string()))
+            }
+
+            pub fn insert(&mut self, name: &str, item: StockItem) -> Result<(), StockError> {
+                if self.items.contains_key(name) {
+                    return Err(StockError::AlreadyStocked(name.to_string()));
+                }
+                self.items.insert(name.to_string(), item);
+                Ok(())
+            }
+
+            pub fn set_reorder_rule(&mut self, name: &str, point: u32, quantity: u32) -> Result<(), StockError> {
+                let item = self.get_mut(name)?;
+                item.reorder_point = point;
+                item.reorder_quantity = quantity;
+                Ok(())
+            }
+
+            pub fn add_to_inventory(&mut self, name: &str, quantity: u32) -> Result<u32, StockError> {
+                let item = self.get_mut(name)?;
+                item.on_hand = item.on_hand.checked_add(quantity).ok_or(StockError::InvalidQuantity(quantity))?;
+                Ok(item.on_hand)
+            }
+
+            // Fails unless `quantity` is available to this caller
+            fn check_available(item: &StockItem, name: &str, quantity: u32) -> Result<(), StockError> {
+                if quantity == 0 {
+                    return Err(StockError::InvalidQuantity(quantity));
+                }
+                if quantity > item.available() {
+                    return Err(StockError::InsufficientStock {
+                        plant: name.to_string(),
+                        requested: quantity,
+                        available: item.available(),
+                    });
+                }
+                Ok(())
+            }
+
+            pub fn reserve(&mut self, name: &str, quantity: u32) -> Result<(), StockError> {
+                let item = self.get_mut(name)?;
+                Inventory::check_available(item, name, quantity)?;
+                item.reserved += quantity;
+                Ok(())
+            }
+
+            pub fn release(&mut self, name: &str, quantity: u32) {
+                if let Ok(item) = self.get_mut(name) {
+                    item.reserved = item.reserved.saturating_sub(quantity);
+                }
+            }
+
+            /// Checks that every reserved line is still covered by the stock on hand
+            pub fn check_reserved(&self, lines: &[(String, u32)]) -> Result<(), StockError> {
+                for (name, quantity) in lines {
+                    let item = self.get(name)?;
+                    if *quantity > item.on_hand {
+                        return Err(StockError::InsufficientStock {
+                            plant: name.clone(),
+                            requested: *quantity,
+                            available: item.on_hand,
+                        });
+                    }
+                }
+                Ok(())
+            }
+
+            /// Turns a reservation into a sale
+            pub fn commit_reserved(&mut self, name: &str, quantity: u32) {
+                if let Ok(item) = self.get_mut(name) {
+                    item.reserved = item.reserved.saturating_sub(quantity);
+                    item.on_hand = item.on_hand.saturating_sub(quantity);
+                }
+            }
+
+            /// Sells stock that was not reserved
+            pub fn take(&mut self, name: &str, quantity: u32) -> Result<(), StockError> {
+                let item = self.get_mut(name)?;
+                Inventory::check_available(item, name, quantity)?;
+                item.on_hand -= quantity;
+                Ok(())
+            }
+
+            /// Removes damaged or dead plants; this may leave reservations uncovered
+            pub fn write_off(&mut self, name: &str, quantity: u32) -> Result<(), StockError> {
+                let item = self.get_mut(name)?;
+                item.on_hand = item.on_hand.saturating_sub(quantity);
+                Ok(())
+            }
+
+            /// Reorders one plant if its available stock is below the reorder point
+            pub fn restock_if_low(&mut self, name: &str) -> Option<Restock> {
+                let item = self.items.get(name)?;
+                if item.reorder_quantity == 0 || item.available() >= item.reorder_point {
+                    return None;
+                }
+                let quantity = item.reorder_quantity;
+                let on_hand = self.add_to_inventory(name, quantity).ok()?;
+                Some(Restock { plant: name.to_string(), quantity, on_hand })
+            }
+
+            pub fn names(&self) -> Vec<String> {
+                self.items.keys().cloned().collect()
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // GARDEN CENTER
+    //------------------------------------------------------
+
+    /// The shop's catalog and stock. Stock only changes through sales,
+    /// carts, restocking and write-offs.
+    #[derive(Debug, Default)]
+    pub struct GardenCenter {
+        inventory: inventory::Inventory,
+    }
+
+    impl GardenCenter {
+        pub fn new() -> Self {
+            GardenCenter::default()
+        }
+
+        /// Adds a plant to the catalog with its starting stock
+        pub fn add_plant(
+            &mut self,
+            name: &str,
+            price: Money,
+            tax_category: TaxCategory,
+            quantity: u32,
+        ) -> Result<(), StockError> {
+            self.inventory.insert(
+                name,
+                inventory::StockItem {
+                    price,
+                    tax_category,
+                    on_hand: quantity,
+                    reserved: 0,
+                    reorder_point: 0,
+                    reorder_quantity: 0,
+                },
+            )
+        }
+
+        /// Restock `quantity` plants whenever fewer than `point` are available
+        pub fn set_reorder_rule(&mut self, name: &str, point: u32, quantity: u32) -> Result<(), StockError> {
+            self.inventory.set_reorder_rule(name, point, quantity)
+        }
+
+        /// Plants that can still be sold or added to a cart
+        pub fn available(&self, name: &str) -> Result<u32, StockError> {
+            self.inventory.get(name).map(|item| item.available())
+        }
+
+        pub fn on_hand(&self, name: &str) -> Result<u32, StockError> {
+            self.inventory.get(name).map(|item| item.on_hand)
+        }
+
+        pub fn price(&self, name: &str) -> Result<Money, StockError> {
+            self.inventory.get(name).map(|item| item.price)
+        }
+
+        /// Removes plants that died or were damaged
+        pub fn write_off(&mut self, name: &str, quantity: u32) -> Result<(), StockError> {
+            self.inventory.write_off(name, quantity)
+        }
+    }
+
+    //------------------------------------------------------
+    // COUNTER SALES AND RESTOCKING
+    //------------------------------------------------------
+
+    pub mod plants {
+        use super::{GardenCenter, Restock, StockError};
+
+        /// Sells plants over the counter. Returns the restock it triggered, if any.
+        pub fn sell_plant(center: &mut GardenCenter, name: &str, quantity: u32) -> Result<Option<Restock>, StockError> {
+            center.inventory.take(name, quantity)?;
+            Ok(center.inventory.restock_if_low(name))
+        }
+    }
+
+    /// Tops up every plant whose available stock is below its reorder point
+    pub fn restock(center: &mut GardenCenter) -> Vec<Restock> {
+        center
+            .inventory
+            .names()
+            .iter()
+            .filter_map(|name| center.inventory.restock_if_low(name))
+            .collect()
+    }
+
+    //------------------------------------------------------
+    // CHECKOUT
+    //------------------------------------------------------
+
+    pub mod checkout {
+        use super::{GardenCenter, Restock, StockError};
+        use crate::shopping_cart::{CartError, PricingEngine, PricingError, Receipt, ShoppingCart};
+        use std::collections::BTreeMap;
+        use std::fmt;
+        use std::sync::mpsc::{channel, Receiver, Sender};
+
+        pub type CartId = u32;
+
+        /// Why a cart operation or checkout failed
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum CheckoutError {
+            UnknownCart(CartId),
+            EmptyCart(CartId),
+            Stock(StockError),
+            Cart(CartError),
+            Pricing(PricingError),
+        }
+
+        impl fmt::Display for CheckoutError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    CheckoutError::UnknownCart(id) => write!(f, "cart #{} not found", id),
+                    CheckoutError::EmptyCart(id) => write!(f, "cart #{} is empty", id),
+                    CheckoutError::Stock(e) => write!(f, "{}", e),
+                    CheckoutError::Cart(e) => write!(f, "{}", e),
+                    CheckoutError::Pricing(e) => write!(f, "{}", e),
+                }
+            }
+        }
+
+        impl std::error::Error for CheckoutError {}
+
+        impl From<StockError> for CheckoutError {
+            fn from(e: StockError) -> Self {
+                CheckoutError::Stock(e)
+            }
+        }
+
+        impl From<CartError> for CheckoutError {
+            fn from(e: CartError) -> Self {
+                CheckoutError::Cart(e)
+            }
+        }
+
+        impl From<PricingError> for CheckoutError {
+            fn from(e: PricingError) -> Self {
+                CheckoutError::Pricing(e)
+            }
+        }
+
+        /// The record of a paid order
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Order {
+            pub id: u64,
+            pub cart: CartId,
+            pub receipt: Receipt,
+            /// Reorders triggered by this sale
+            pub restocked: Vec<Restock>,
+        }
+
+        impl fmt::Display for Order {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                writeln!(f, "Order #{} (cart #{})", self.id, self.cart)?;
+                write!(f, "{}", self.receipt)?;
+                for restock in &self.restocked {
+                    write!(f, "\nRestocked {} x {} ({} on hand)", restock.quantity, restock.plant, restock.on_hand)?;
+                }
+                Ok(())
+            }
+        }
+
+        /// Carts whose items are backed by reserved stock
+        pub struct CheckoutService {
+            center: GardenCenter,
+            engine: PricingEngine,
+            carts: BTreeMap<CartId, ShoppingCart>,
+            next_cart: CartId,
+            orders: Vec<Order>,
+            subscribers: Vec<Sender<Order>>,
+        }
+
+        impl CheckoutService {
+            pub fn new(center: GardenCenter, engine: PricingEngine) -> Self {
+                CheckoutService {
+                    center,
+                    engine,
+                    carts: BTreeMap::new(),
+                    next_cart: 1,
+                    orders: Vec::new(),
+                    subscribers: Vec::new(),
+                }
+            }
+
+            pub fn center(&self) -> &GardenCenter {
+                &self.center
+            }
+
+            pub fn center_mut(&mut self) -> &mut GardenCenter {
+                &mut self.center
+            }
+
+            /// Every order placed so far, oldest first
+            pub fn orders(&self) -> &[Order] {
+                &self.orders
+            }
+
+            /// Receives a copy of every order as it is placed
+            pub fn subscribe(&mut self) -> Receiver<Order> {
+                let (sender, receiver) = channel();
+                self.subscribers.push(sender);
+                receiver
+            }
+
+            pub fn open_cart(&mut self) -> CartId {
+                let id = self.next_cart;
+                self.next_cart += 1;
+                self.carts.insert(id, ShoppingCart::new());
+                id
+            }
+
+            /// Carts are read-only from outside; items go through `add_to_cart`
+            pub fn cart(&self, id: CartId) -> Option<&ShoppingCart> {
+                self.carts.get(&id)
+            }
+
+            fn cart_mut(&mut self, id: CartId) -> Result<&mut ShoppingCart, CheckoutError> {
+                self.carts.get_mut(&id).ok_or(CheckoutError::UnknownCart(id))
+            }
+
+            /// Reserves stock and adds the plants to the cart at the catalog price
+            pub fn add_to_cart(&mut self, id: CartId, name: &str, quantity: u32) -> Result<(), CheckoutError> {
+                if !self.carts.contains_key(&id) {
+                    return Err(CheckoutError::UnknownCart(id));
+                }
+                let item = self.center.inventory.get(name)?.clone();
+                self.center.inventory.reserve(name, quantity)?;
+
+                let added = self.cart_mut(id)?.add_taxed_item(name, item.price, quantity, item.tax_category);
+                if let Err(e) = added {
+                    self.center.inventory.release(name, quantity);
+                    return Err(e.into());
+                }
+                Ok(())
+            }
+
+            /// Takes a plant out of the cart and releases its stock
+            pub fn remove_from_cart(&mut self, id: CartId, name: &str) -> Result<bool, CheckoutError> {
+                let cart = self.cart_mut(id)?;
+                let quantity = match cart.get_item(name) {
+                    Some(item) => item.quantity,
+                    None => return Ok(false),
+                };
+                cart.remove_item(name);
+                self.center.inventory.release(name, quantity);
+                Ok(true)
+            }
+
+            pub fn apply_coupon(&mut self, id: CartId, code: &str) -> Result<bool, CheckoutError> {
+                Ok(self.cart_mut(id)?.apply_coupon(code))
+            }
+
+            /// Closes a cart without buying and releases all of its stock
+            pub fn abandon_cart(&mut self, id: CartId) -> Result<(), CheckoutError> {
+                let cart = self.carts.remove(&id).ok_or(CheckoutError::UnknownCart(id))?;
+                for item in cart.get_items() {
+                    self.center.inventory.release(&item.name, item.quantity);
+                }
+                Ok(())
+            }
+
+            /// Prices the cart, takes the reserved stock and records the order.
+            /// On error nothing changes and the cart stays open.
+            pub fn checkout(&mut self, id: CartId) -> Result<Order, CheckoutError> {
+                let cart = self.carts.get(&id).ok_or(CheckoutError::UnknownCart(id))?;
+                if cart.is_empty() {
+                    return Err(CheckoutError::EmptyCart(id));
+                }
+
+                // Stock may have been written off since the items were reserved
+                let lines: Vec<(String, u32)> =
+                    cart.get_items().iter().map(|item| (item.name.clone(), item.quantity)).collect();
+                self.center.inventory.check_reserved(&lines)?;
+                let receipt = self.engine.price(cart)?;
+
+                self.carts.remove(&id);
+                let mut restocked = Vec::new();
+                for (name, quantity) in &lines {
+                    self.center.inventory.commit_reserved(name, *quantity);
+                    restocked.extend(self.center.inventory.restock_if_low(name));
+                }
+
+                let order = Order { id: self.orders.len() as u64 + 1, cart: id, receipt, restocked };
+                self.orders.push(order.clone());
+                // Drop subscribers that stopped listening
+                self.subscribers.retain(|s| s.send(order.clone()).is_ok());
+                Ok(order)
+            }
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::garden_center::checkout::{CheckoutError, CheckoutService};
+    use super::garden_center::{plants, restock, GardenCenter, Restock, StockError};
+    use super::shopping_cart::{Currency, Money, PricingEngine, TaxCategory};
+
+    fn usd(text: &str) -> Money {
+        Money::parse(text, Currency::USD).unwrap()
+    }
+
+    fn nursery() -> GardenCenter {
+        let mut center = GardenCenter::new();
+        center.add_plant("Fern", usd("12.50"), TaxCategory::Standard, 5).unwrap();
+        center.add_plant("Cactus", usd("8.00"), TaxCategory::Standard, 2).unwrap();
+        center
+    }
+
+    fn shop() -> CheckoutService {
+        CheckoutService::new(nursery(), PricingEngine::new(Currency::USD))
+    }
+
+    #[test]
+    fn test_sell_plant_takes_stock() {
+        let mut center = nursery();
+        assert_eq!(plants::sell_plant(&mut center, "Fern", 2), Ok(None));
+        assert_eq!(center.on_hand("Fern"), Ok(3));
+
+        assert_eq!(
+            plants::sell_plant(&mut center, "Fern", 4),
+            Err(StockError::InsufficientStock { plant: "Fern".to_string(), requested: 4, available: 3 })
+        );
+        assert_eq!(plants::sell_plant(&mut center, "Rose", 1), Err(StockError::UnknownPlant("Rose".to_string())));
+        assert_eq!(plants::sell_plant(&mut center, "Fern", 0), Err(StockError::InvalidQuantity(0)));
+        assert!(center.add_plant("Fern", usd("1.00"), TaxCategory::Standard, 1).is_err());
+    }
+
+    #[test]
+    fn test_adding_to_cart_reserves_stock() {
+        let mut shop = shop();
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 3).unwrap();
+        shop.add_to_cart(cart, "Fern", 1).unwrap();
+
+        assert_eq!(shop.cart(cart).unwrap().get_item("Fern").unwrap().quantity, 4);
+        assert_eq!(shop.center().available("Fern"), Ok(1));
+        assert_eq!(shop.center().on_hand("Fern"), Ok(5));
+
+        // Another cart cannot take reserved plants, and neither can the counter
+        let other = shop.open_cart();
+        assert_eq!(
+            shop.add_to_cart(other, "Fern", 2),
+            Err(CheckoutError::Stock(StockError::InsufficientStock {
+                plant: "Fern".to_string(),
+                requested: 2,
+                available: 1,
+            }))
+        );
+        assert!(shop.cart(other).unwrap().is_empty());
+        assert!(plants::sell_plant(shop.center_mut(), "Fern", 2).is_err());
+    }
+
+    #[test]
+    fn test_removing_and_abandoning_release_stock() {
+        let mut shop = shop();
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 2).unwrap();
+        shop.add_to_cart(cart, "Cactus", 2).unwrap();
+
+        assert_eq!(shop.remove_from_cart(cart, "Fern"), Ok(true));
+        assert_eq!(shop.remove_from_cart(cart, "Fern"), Ok(false));
+        assert_eq!(shop.center().available("Fern"), Ok(5));
+
+        shop.abandon_cart(cart).unwrap();
+        assert_eq!(shop.center().available("Cactus"), Ok(2));
+        assert!(shop.cart(cart).is_none());
+        assert_eq!(shop.checkout(cart), Err(CheckoutError::UnknownCart(cart)));
+    }
+
+    #[test]
+    fn test_checkout_records_order() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine.set_tax_rate(TaxCategory::Standard, 1000).unwrap();
+        let mut shop = CheckoutService::new(nursery(), engine);
+        let orders = shop.subscribe();
+
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 2).unwrap();
+        shop.add_to_cart(cart, "Cactus", 1).unwrap();
+        let order = shop.checkout(cart).unwrap();
+
+        assert_eq!(order.id, 1);
+        assert_eq!(order.receipt.subtotal, usd("33.00"));
+        assert_eq!(order.receipt.total, usd("36.30"));
+        assert_eq!(shop.center().on_hand("Fern"), Ok(3));
+        assert_eq!(shop.center().available("Fern"), Ok(3));
+        assert_eq!(shop.center().on_hand("Cactus"), Ok(1));
+        assert!(shop.cart(cart).is_none());
+
+        assert_eq!(shop.orders(), std::slice::from_ref(&order));
+        assert_eq!(orders.try_recv(), Ok(order));
+    }
+
+    #[test]
+    fn test_checkout_fails_when_stock_is_gone() {
+        let mut shop = shop();
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 4).unwrap();
+
+        // Frost got three of the reserved ferns
+        shop.center_mut().write_off("Fern", 3).unwrap();
+        assert_eq!(
+            shop.checkout(cart),
+            Err(CheckoutError::Stock(StockError::InsufficientStock {
+                plant: "Fern".to_string(),
+                requested: 4,
+                available: 2,
+            }))
+        );
+
+        // Nothing was sold and the cart is still open
+        assert_eq!(shop.center().on_hand("Fern"), Ok(2));
+        assert!(shop.cart(cart).is_some());
+        assert!(shop.orders().is_empty());
+
+        // The customer settles for two
+        shop.remove_from_cart(cart, "Fern").unwrap();
+        shop.add_to_cart(cart, "Fern", 2).unwrap();
+        assert!(shop.checkout(cart).is_ok());
+    }
+
+    #[test]
+    fn test_failed_pricing_keeps_reservations() {
+        let mut shop = shop();
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Cactus", 1).unwrap();
+        shop.apply_coupon(cart, "NOPE").unwrap();
+
+        assert!(matches!(shop.checkout(cart), Err(CheckoutError::Pricing(_))));
+        assert_eq!(shop.center().available("Cactus"), Ok(1));
+        assert_eq!(shop.center().on_hand("Cactus"), Ok(2));
+
+        let empty = shop.open_cart();
+        assert_eq!(shop.checkout(empty), Err(CheckoutError::EmptyCart(empty)));
+    }
+
+    #[test]
+    fn test_checkout_restocks_below_threshold() {
+        let mut shop = shop();
+        shop.center_mut().set_reorder_rule("Fern", 2, 10).unwrap();
+
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 2).unwrap();
+        let order = shop.checkout(cart).unwrap();
+        assert!(order.restocked.is_empty());
+
+        let cart = shop.open_cart();
+        shop.add_to_cart(cart, "Fern", 2).unwrap();
+        let order = shop.checkout(cart).unwrap();
+        assert_eq!(order.restocked, vec![Restock { plant: "Fern".to_string(), quantity: 10, on_hand: 11 }]);
+        assert_eq!(shop.center().available("Fern"), Ok(11));
+        assert!(order.to_string().ends_with("Restocked 10 x Fern (11 on hand)"));
+    }
+
+    #[test]
+    fn test_restock_tops_up_low_plants() {
+        let mut center = nursery();
+        center.set_reorder_rule("Cactus", 3, 5).unwrap();
+        center.set_reorder_rule("Fern", 3, 5).unwrap();
+
+        let restocked = restock(&mut center);
+        assert_eq!(restocked, vec![Restock { plant: "Cactus".to_string(), quantity: 5, on_hand: 7 }]);
+        assert!(restock(&mut center).is_empty());
+
+        // Counter sales restock too
+        let triggered = plants::sell_plant(&mut center, "Fern", 3).unwrap();
+        assert_eq!(triggered, Some(Restock { plant: "Fern".to_string(), quantity: 5, on_hand: 7 }));
+    }
+}
@@ -0,0 +1,698 @@
+// Garden Simulation: The `plants` and `plant_types` Modules Brought to Life
+//
+// In 5_privacy_and_visibility.rs, `Plant::needs_water` always says yes, and
+// the private `GrowthRate` only picks a description. This file keeps the same
+// modules and privacy rules but gives the plants real state:
+//
+// - `plant_types`: `PlantType` is public, `GrowthRate` is still private.
+//   Each type reports how fast it grows and how moist it likes its soil,
+//   without ever exposing the `GrowthRate` itself.
+// - `plants`: a `Plant` with soil moisture, health and a growth stage.
+//   Anyone can read them, but only the simulation can move a plant through
+//   a day (`pub(super)`), and only watering changes its moisture.
+// - `simulation`: a `Garden` that runs day by day. The weather comes from a
+//   seeded random number generator, so the same seed always grows the same
+//   garden, and tests can check its state after N days.
+//
+// Try the privacy exercises yourself before reading this file!
+
+fn main() {
+    println!("A garden simulation built on Rust's privacy rules!");
+
+    use garden::plant_types::PlantType;
+    use garden::plants::Plant;
+    use garden::simulation::{CarePolicy, Climate, Garden};
+
+    let mut garden = Garden::new(Climate::Seeded(42), CarePolicy::WhenNeeded);
+    garden.plant(Plant::new("Rose", "Rosa rubiginosa", PlantType::Flowering, 3));
+    garden.plant(Plant::new("Boston Fern", "Nephrolepis exaltata", PlantType::Fern, 2));
+    garden.plant(Plant::new("Cactus", "Opuntia", PlantType::NonFlowering, 14));
+    garden.plant(Plant::new("Cushion Moss", "Leucobryum glaucum", PlantType::Moss, 1));
+
+    for _ in 0..7 {
+        let report = garden.simulate_day();
+        println!("{}", report);
+    }
+
+    let reports = garden.simulate(23);
+    let rainy_days = reports.iter().filter(|r| r.weather.is_rainy()).count();
+    println!("\nAfter {} days ({} of the last 23 rainy):", garden.day(), rainy_days);
+    for plant in garden.plants() {
+        println!("  {}", plant.status());
+    }
+
+    // The same seed always grows the same garden
+    let mut neglected = Garden::new(Climate::Seeded(42), CarePolicy::Neglect);
+    neglected.plant(Plant::new("Rose", "Rosa rubiginosa", PlantType::Flowering, 3));
+    neglected.simulate(30);
+    println!("\nWithout watering: {}", neglected.plants().next().unwrap().status());
+}
+
+pub mod garden {
+    //------------------------------------------------------
+    // PLANT TYPES
+    //------------------------------------------------------
+
+    pub mod plant_types {
+        use std::fmt;
+
+        /// Public enum - all variants are automatically public
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum PlantType {
+            Flowering,
+            NonFlowering,
+            Fern,
+            Moss,
+        }
+
+        // Still private: callers get numbers and descriptions, never the rate
+        enum GrowthRate {
+            Slow,
+            Medium,
+            Fast,
+        }
+
+        impl PlantType {
+            fn growth_rate(&self) -> GrowthRate {
+                match self {
+                    PlantType::Flowering => GrowthRate::Medium,
+                    PlantType::NonFlowering => GrowthRate::Slow,
+                    PlantType::Fern => GrowthRate::Fast,
+                    PlantType::Moss => GrowthRate::Slow,
+                }
+            }
+
+            /// Growth points a fully healthy plant gains in a day
+            pub fn daily_growth(&self) -> u32 {
+                match self.growth_rate() {
+                    GrowthRate::Slow => 3,
+                    GrowthRate::Medium => 5,
+                    GrowthRate::Fast => 8,
+                }
+            }
+
+            /// Soil moisture (in %) below which the plant wants water
+            pub fn min_moisture(&self) -> u32 {
+                match self {
+                    PlantType::Flowering => 40,
+                    PlantType::NonFlowering => 20,
+                    PlantType::Fern => 50,
+                    PlantType::Moss => 60,
+                }
+            }
+
+            /// Moisture (in %) the plant itself uses up each day
+            pub fn daily_water_use(&self) -> u32 {
+                match self {
+                    PlantType::Flowering => 5,
+                    PlantType::NonFlowering => 2,
+                    PlantType::Fern => 6,
+                    PlantType::Moss => 3,
+                }
+            }
+
+            /// Moss thrives in soaked soil; everything else rots
+            pub fn tolerates_waterlogging(&self) -> bool {
+                matches!(self, PlantType::Moss)
+            }
+        }
+
+        /// Same descriptions as the original example
+        pub fn describe_growth(plant_type: PlantType) -> &'static str {
+            match plant_type.growth_rate() {
+                GrowthRate::Slow => "This plant grows slowly",
+                GrowthRate::Medium => "This plant grows at a medium rate",
+                GrowthRate::Fast => "This plant grows quickly",
+            }
+        }
+
+        impl fmt::Display for PlantType {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let name = match self {
+                    PlantType::Flowering => "flowering",
+                    PlantType::NonFlowering => "non-flowering",
+                    PlantType::Fern => "fern",
+                    PlantType::Moss => "moss",
+                };
+                write!(f, "{}", name)
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // PLANTS
+    //------------------------------------------------------
+
+    pub mod plants {
+        use super::plant_types::PlantType;
+        use super::simulation::Weather;
+        use std::fmt;
+
+        /// Growth points needed to reach each stage
+        pub const SPROUT_AT: u32 = 20;
+        pub const JUVENILE_AT: u32 = 60;
+        pub const MATURE_AT: u32 = 120;
+
+        /// Soil above this moisture (in %) is waterlogged
+        pub const WATERLOGGED_ABOVE: u32 = 90;
+
+        /// How much one watering raises the soil moisture (in %)
+        pub const WATERING_AMOUNT: u32 = 50;
+
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub enum GrowthStage {
+            Seed,
+            Sprout,
+            Juvenile,
+            Mature,
+            Dead,
+        }
+
+        impl GrowthStage {
+            fn for_growth(growth: u32) -> GrowthStage {
+                match growth {
+                    g if g >= MATURE_AT => GrowthStage::Mature,
+                    g if g >= JUVENILE_AT => GrowthStage::Juvenile,
+                    g if g >= SPROUT_AT => GrowthStage::Sprout,
+                    _ => GrowthStage::Seed,
+                }
+            }
+        }
+
+        impl fmt::Display for GrowthStage {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:?}", self)
+            }
+        }
+
+        /// What happened to a plant during one day
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum PlantEvent {
+            Grew(GrowthStage),
+            Wilting,
+            Waterlogged,
+            Died,
+        }
+
+        /// Public struct with mixed field privacy: the name and species can
+        /// be changed by anyone, the living state only by this module
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Plant {
+            pub name: String,
+            pub species: String,
+            plant_type: PlantType,
+            // The gardener's schedule when following `CarePolicy::Schedule`
+            water_frequency: u32,
+            moisture: u32,
+            health: u32,
+            growth: u32,
+            stage: GrowthStage,
+        }
+
+        impl Plant {
+            /// A freshly sown seed in moist soil
+            pub fn new(name: &str, species: &str, plant_type: PlantType, water_frequency: u32) -> Plant {
+                Plant {
+                    name: name.to_string(),
+                    species: species.to_string(),
+                    plant_type,
+                    water_frequency: water_frequency.max(1),
+                    moisture: 70,
+                    health: 100,
+                    growth: 0,
+                    stage: GrowthStage::Seed,
+                }
+            }
+
+            pub fn description(&self) -> String {
+                format!("{} ({}), Water every {} days", self.name, self.species, self.water_frequency)
+            }
+
+            pub fn plant_type(&self) -> PlantType {
+                self.plant_type
+            }
+
+            pub fn water_frequency(&self) -> u32 {
+                self.water_frequency
+            }
+
+            /// Soil moisture in % (0-100)
+            pub fn moisture(&self) -> u32 {
+                self.moisture
+            }
+
+            /// Health in % (0-100); the plant dies at 0
+            pub fn health(&self) -> u32 {
+                self.health
+            }
+
+            /// Growth points collected so far
+            pub fn growth(&self) -> u32 {
+                self.growth
+            }
+
+            pub fn stage(&self) -> GrowthStage {
+                self.stage
+            }
+
+            pub fn is_alive(&self) -> bool {
+                self.stage != GrowthStage::Dead
+            }
+
+            /// True when the soil is drier than this type of plant likes
+            pub fn needs_water(&self) -> bool {
+                self.is_alive() && self.moisture < self.plant_type.min_moisture()
+            }
+
+            /// Waters the plant only if it needs it; returns whether it did
+            pub fn water_if_needed(&mut self) -> bool {
+                if !self.needs_water() {
+                    return false;
+                }
+                self.water();
+                true
+            }
+
+            /// Waters the plant whether it needs it or not
+            pub fn water(&mut self) {
+                if self.is_alive() {
+                    self.moisture = (self.moisture + WATERING_AMOUNT).min(100);
+                }
+            }
+
+            /// One line summary: "Rose (flowering): Juvenile, growth 74, health 100%, moisture 55%"
+            pub fn status(&self) -> String {
+                format!(
+                    "{} ({}): {}, growth {}, health {}%, moisture {}%",
+                    self.name, self.plant_type, self.stage, self.growth, self.health, self.moisture
+                )
+            }
+
+            // Moves the plant through one day. Only the `garden` module and
+            // its children (the simulation) can call this.
+            pub(super) fn tick(&mut self, weather: Weather, evaporation_jitter: u32) -> Vec<PlantEvent> {
+                let mut events = Vec::new();
+                if !self.is_alive() {
+                    return events;
+                }
+
+                // Soil moisture: the weather dries or soaks the soil, and the
+                // plant drinks its share
+                let drying = self.plant_type.daily_water_use() + evaporation_jitter;
+                self.moisture = match weather {
+                    Weather::Rainy => (self.moisture + 35).min(100).saturating_sub(drying),
+                    Weather::Cloudy => self.moisture.saturating_sub(drying + 4),
+                    Weather::Sunny => self.moisture.saturating_sub(drying + 10),
+                };
+
+                // Health: too dry hurts a lot, waterlogged roots rot slowly,
+                // anything in between lets the plant recover
+                let min_moisture = self.plant_type.min_moisture();
+                if self.moisture < min_moisture / 2 {
+                    self.health = self.health.saturating_sub(15);
+                    events.push(PlantEvent::Wilting);
+                } else if self.moisture > WATERLOGGED_ABOVE && !self.plant_type.tolerates_waterlogging() {
+                    self.health = self.health.saturating_sub(5);
+                    events.push(PlantEvent::Waterlogged);
+                } else if self.moisture >= min_moisture {
+                    self.health = (self.health + 5).min(100);
+                }
+
+                if self.health == 0 {
+                    self.stage = GrowthStage::Dead;
+                    events.push(PlantEvent::Died);
+                    return events;
+                }
+
+                // Growth: a healthy plant grows at its type's rate, a weak
+                // one more slowly, a very weak one not at all
+                if self.health >= 50 {
+                    self.growth += self.plant_type.daily_growth() * self.health / 100;
+                }
+                let stage = GrowthStage::for_growth(self.growth);
+                if stage != self.stage {
+                    self.stage = stage;
+                    events.push(PlantEvent::Grew(stage));
+                }
+                events
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // SIMULATION
+    //------------------------------------------------------
+
+    pub mod simulation {
+        use super::plants::{Plant, PlantEvent};
+        use std::fmt;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Weather {
+            Sunny,
+            Cloudy,
+            Rainy,
+        }
+
+        impl Weather {
+            pub fn is_rainy(&self) -> bool {
+                *self == Weather::Rainy
+            }
+        }
+
+        /// Where the weather comes from
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Climate {
+            /// Random weather: 50% sunny, 30% cloudy, 20% rain.
+            /// The same seed always gives the same sequence.
+            Seeded(u64),
+            /// The same weather every day
+            Always(Weather),
+        }
+
+        /// How the gardener looks after the plants each morning
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum CarePolicy {
+            /// Nobody waters anything
+            Neglect,
+            /// Water every plant every `water_frequency` days, needed or not
+            Schedule,
+            /// Water each plant only when its soil is too dry
+            WhenNeeded,
+        }
+
+        // SplitMix64: tiny, fast and fully deterministic. Private, because
+        // nothing outside the simulation should depend on how it works.
+        struct SimRng {
+            state: u64,
+        }
+
+        impl SimRng {
+            fn new(seed: u64) -> SimRng {
+                SimRng { state: seed }
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = self.state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                z ^ (z >> 31)
+            }
+
+            /// A number in 0..bound
+            fn below(&mut self, bound: u64) -> u64 {
+                self.next_u64() % bound
+            }
+        }
+
+        // A plant and its care record. Private struct, like `PlantCare`
+        // in the original example.
+        struct PlantCare {
+            plant: Plant,
+            last_watered: u32,
+        }
+
+        /// Everything that happened on one simulated day
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct DayReport {
+            pub day: u32,
+            pub weather: Weather,
+            /// Names of the plants the gardener watered
+            pub watered: Vec<String>,
+            pub events: Vec<(String, PlantEvent)>,
+        }
+
+        impl fmt::Display for DayReport {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "Day {:>3} {:?}", self.day, self.weather)?;
+                if !self.watered.is_empty() {
+                    write!(f, ", watered {}", self.watered.join(", "))?;
+                }
+                for (name, event) in &self.events {
+                    match event {
+                        PlantEvent::Grew(stage) => write!(f, ", {} is now a {}", name, stage)?,
+                        PlantEvent::Wilting => write!(f, ", {} is wilting", name)?,
+                        PlantEvent::Waterlogged => write!(f, ", {} is waterlogged", name)?,
+                        PlantEvent::Died => write!(f, ", {} died", name)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        pub struct Garden {
+            day: u32,
+            climate: Climate,
+            policy: CarePolicy,
+            rng: SimRng,
+            plants: Vec<PlantCare>,
+        }
+
+        impl Garden {
+            pub fn new(climate: Climate, policy: CarePolicy) -> Garden {
+                let seed = match climate {
+                    Climate::Seeded(seed) => seed,
+                    Climate::Always(_) => 0,
+                };
+                Garden {
+                    day: 0,
+                    climate,
+                    policy,
+                    rng: SimRng::new(seed),
+                    plants: Vec::new(),
+                }
+            }
+
+            pub fn plant(&mut self, plant: Plant) {
+                self.plants.push(PlantCare {
+                    plant,
+                    last_watered: self.day,
+                });
+            }
+
+            /// Days simulated so far
+            pub fn day(&self) -> u32 {
+                self.day
+            }
+
+            pub fn plants(&self) -> impl Iterator<Item = &Plant> {
+                self.plants.iter().map(|care| &care.plant)
+            }
+
+            pub fn find(&self, name: &str) -> Option<&Plant> {
+                self.plants().find(|plant| plant.name == name)
+            }
+
+            /// Waters one plant by hand, outside the gardener's routine.
+            /// Returns false if there is no plant with that name.
+            pub fn water(&mut self, name: &str) -> bool {
+                let day = self.day;
+                match self.plants.iter_mut().find(|care| care.plant.name == name) {
+                    Some(care) => {
+                        care.plant.water();
+                        care.last_watered = day;
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            fn next_weather(&mut self) -> Weather {
+                match self.climate {
+                    Climate::Always(weather) => weather,
+                    Climate::Seeded(_) => match self.rng.below(10) {
+                        0..=4 => Weather::Sunny,
+                        5..=7 => Weather::Cloudy,
+                        _ => Weather::Rainy,
+                    },
+                }
+            }
+
+            /// Runs one day: the gardener waters in the morning, then the
+            /// weather and the plants do the rest
+            pub fn simulate_day(&mut self) -> DayReport {
+                self.day += 1;
+                let day = self.day;
+                let weather = self.next_weather();
+
+                let mut watered = Vec::new();
+                for care in self.plants.iter_mut() {
+                    let did_water = match self.policy {
+                        CarePolicy::Neglect => false,
+                        CarePolicy::WhenNeeded => care.plant.water_if_needed(),
+                        CarePolicy::Schedule => {
+                            let due = day - care.last_watered >= care.plant.water_frequency();
+                            if due && care.plant.is_alive() {
+                                care.plant.water();
+                            }
+                            due && care.plant.is_alive()
+                        }
+                    };
+                    if did_water {
+                        care.last_watered = day;
+                        watered.push(care.plant.name.clone());
+                    }
+                }
+
+                let mut events = Vec::new();
+                for care in self.plants.iter_mut() {
+                    let jitter = self.rng.below(3) as u32;
+                    for event in care.plant.tick(weather, jitter) {
+                        events.push((care.plant.name.clone(), event));
+                    }
+                }
+
+                DayReport {
+                    day,
+                    weather,
+                    watered,
+                    events,
+                }
+            }
+
+            /// Runs several days and returns their reports
+            pub fn simulate(&mut self, days: u32) -> Vec<DayReport> {
+                (0..days).map(|_| self.simulate_day()).collect()
+            }
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::garden::plant_types::{describe_growth, PlantType};
+    use super::garden::plants::{GrowthStage, Plant, PlantEvent};
+    use super::garden::simulation::{CarePolicy, Climate, Garden, Weather};
+
+    fn garden(climate: Climate, policy: CarePolicy) -> Garden {
+        let mut garden = Garden::new(climate, policy);
+        garden.plant(Plant::new("Rose", "Rosa rubiginosa", PlantType::Flowering, 3));
+        garden.plant(Plant::new("Fern", "Nephrolepis exaltata", PlantType::Fern, 2));
+        garden.plant(Plant::new("Cactus", "Opuntia", PlantType::NonFlowering, 14));
+        garden.plant(Plant::new("Moss", "Leucobryum glaucum", PlantType::Moss, 1));
+        garden
+    }
+
+    fn stages(garden: &Garden) -> Vec<GrowthStage> {
+        garden.plants().map(|plant| plant.stage()).collect()
+    }
+
+    #[test]
+    fn test_growth_rates_follow_plant_type() {
+        assert_eq!(describe_growth(PlantType::Fern), "This plant grows quickly");
+        assert_eq!(describe_growth(PlantType::Moss), "This plant grows slowly");
+        assert!(PlantType::Fern.daily_growth() > PlantType::Flowering.daily_growth());
+        assert!(PlantType::Flowering.daily_growth() > PlantType::NonFlowering.daily_growth());
+        assert_eq!(PlantType::Moss.daily_growth(), PlantType::NonFlowering.daily_growth());
+    }
+
+    #[test]
+    fn test_water_if_needed_acts_on_moisture() {
+        let mut plant = Plant::new("Rose", "Rosa", PlantType::Flowering, 3);
+        assert_eq!(plant.moisture(), 70);
+        assert!(!plant.needs_water());
+        assert!(!plant.water_if_needed());
+        assert_eq!(plant.moisture(), 70);
+
+        // Dry it out in a sunny garden that nobody tends
+        let mut garden = Garden::new(Climate::Always(Weather::Sunny), CarePolicy::Neglect);
+        garden.plant(plant);
+        garden.simulate(2);
+        let dry = garden.find("Rose").unwrap().clone();
+        assert!(dry.moisture() < PlantType::Flowering.min_moisture());
+        assert!(dry.needs_water());
+
+        let mut plant = dry;
+        assert!(plant.water_if_needed());
+        assert!(!plant.needs_water());
+    }
+
+    #[test]
+    fn test_same_seed_grows_the_same_garden() {
+        let mut first = garden(Climate::Seeded(7), CarePolicy::WhenNeeded);
+        let mut second = garden(Climate::Seeded(7), CarePolicy::WhenNeeded);
+
+        assert_eq!(first.simulate(60), second.simulate(60));
+        let first_plants: Vec<&Plant> = first.plants().collect();
+        let second_plants: Vec<&Plant> = second.plants().collect();
+        assert_eq!(first_plants, second_plants);
+
+        // A different seed gives different weather
+        let weather = |seed| {
+            let mut garden = garden(Climate::Seeded(seed), CarePolicy::WhenNeeded);
+            garden.simulate(30).iter().map(|report| report.weather).collect::<Vec<_>>()
+        };
+        assert_ne!(weather(7), weather(8));
+    }
+
+    #[test]
+    fn test_cared_for_garden_after_thirty_days() {
+        let mut garden = garden(Climate::Seeded(42), CarePolicy::WhenNeeded);
+        garden.simulate(30);
+
+        assert_eq!(garden.day(), 30);
+        assert!(garden.plants().all(|plant| plant.is_alive()));
+        // Fast growers are fully grown, slow ones are still catching up
+        assert_eq!(
+            stages(&garden),
+            vec![GrowthStage::Mature, GrowthStage::Mature, GrowthStage::Juvenile, GrowthStage::Juvenile]
+        );
+        let fern = garden.find("Fern").unwrap();
+        let cactus = garden.find("Cactus").unwrap();
+        assert!(fern.growth() > cactus.growth());
+    }
+
+    #[test]
+    fn test_neglect_in_a_drought_kills_plants() {
+        let mut garden = garden(Climate::Always(Weather::Sunny), CarePolicy::Neglect);
+        let reports = garden.simulate(30);
+
+        assert!(garden.plants().all(|plant| !plant.is_alive()));
+        let deaths: Vec<&String> = reports
+            .iter()
+            .flat_map(|report| report.events.iter())
+            .filter(|(_, event)| *event == PlantEvent::Died)
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(deaths.len(), 4);
+        // The thirstiest plant goes first
+        assert_eq!(deaths[0], "Fern");
+
+        // Dead plants can't be revived by watering
+        assert!(garden.water("Fern"));
+        assert_eq!(garden.find("Fern").unwrap().moisture(), 0);
+    }
+
+    #[test]
+    fn test_rain_waterlogs_all_but_moss() {
+        let mut garden = garden(Climate::Always(Weather::Rainy), CarePolicy::Neglect);
+        garden.simulate(10);
+
+        let moss = garden.find("Moss").unwrap();
+        assert_eq!(moss.health(), 100);
+        assert!(garden.find("Rose").unwrap().health() < 100);
+    }
+
+    #[test]
+    fn test_schedule_follows_water_frequency() {
+        let mut garden = Garden::new(Climate::Always(Weather::Cloudy), CarePolicy::Schedule);
+        garden.plant(Plant::new("Rose", "Rosa", PlantType::Flowering, 3));
+        garden.plant(Plant::new("Cactus", "Opuntia", PlantType::NonFlowering, 14));
+
+        let watered_on = |reports: &[super::garden::simulation::DayReport], name: &str| {
+            reports
+                .iter()
+                .filter(|report| report.watered.iter().any(|n| n == name))
+                .map(|report| report.day)
+                .collect::<Vec<u32>>()
+        };
+        let reports = garden.simulate(15);
+        assert_eq!(watered_on(&reports, "Rose"), vec![3, 6, 9, 12, 15]);
+        assert_eq!(watered_on(&reports, "Cactus"), vec![14]);
+    }
+}
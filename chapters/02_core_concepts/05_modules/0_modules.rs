@@ -226,6 +226,9 @@ fn main() {
 
 // In a real project, these would be in separate files.
 // We're including them here for demonstration purposes.
+//
+// restaurant_orders.rs keeps this restaurant's module layout but runs real
+// orders through it: a waitlist, a kitchen queue and bill splitting.
 
 mod restaurant {
     // A module that models a restaurant
@@ -466,6 +469,11 @@ mod challenges {
     }
     
     // Challenge 4: Fix nested module access
+    //
+    // Once you're done, authentication.rs keeps these modules but stores
+    // salted password hashes instead of plain text, enforces a password
+    // policy, locks accounts after repeated failed logins and hands out
+    // expiring session tokens.
     pub fn challenge_nested_modules() -> Result<(), String> {
         mod authentication {
             mod credentials {
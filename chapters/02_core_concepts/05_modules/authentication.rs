@@ -0,0 +1,1006 @@
+// Authentication: Challenge 4's Module with Real Credential Storage
+//
+// Challenge 4 in 0_modules.rs has an `authentication` module whose
+// `credentials::User` keeps the password in plain text and checks it
+// with `==`. This file keeps the same modules but stores credentials the
+// way a real service must:
+//
+// - `validation`: username rules, and the password policy checked when a
+//   user registers: the rules from exercise 4 in
+//   05_testing/01_unit_testing/0_unit_testing.rs plus a few banned words.
+// - `credentials`: a `CredentialStore` that keeps only salted PBKDF2 hashes
+//   of passwords, compares them in constant time, and locks an account
+//   after too many failed attempts.
+// - `sessions`: a `SessionRegistry` of tokens that expire, can be refreshed
+//   and logged out, with an injectable clock so expiry can be tested.
+// - `login`: checks a username and password against the store and starts
+//   a session.
+//
+// The hashing itself is in the shared std-only crypto module
+// (chapters/shared/crypto.rs), which is tested against published vectors,
+// and the password rules are a `PasswordPolicy` from
+// chapters/shared/password_policy.rs.
+//
+// Try challenge 4 yourself before reading this file!
+
+// Authentication only uses part of the crypto module
+#[allow(dead_code)]
+#[path = "../../shared/crypto.rs"]
+mod crypto;
+
+#[allow(dead_code)]
+#[path = "../../shared/password_policy.rs"]
+mod password_policy;
+
+fn main() {
+    println!("Storing credentials safely with Rust modules!");
+
+    use authentication::credentials::{CredentialStore, SystemSalt};
+    use authentication::sessions::SessionRegistry;
+    use authentication::login;
+
+    // `CredentialStore::new()` uses DEFAULT_ITERATIONS; fewer keep this
+    // demo quick without `-O`
+    let mut store = CredentialStore::with_settings(Box::new(SystemSalt::default()), 10_000, 3);
+    let mut sessions = SessionRegistry::new();
+
+    if let Err(e) = store.register("admin", "password123") {
+        println!("Can't register admin: {}", e);
+    }
+    store.register("admin", "Adm1n!Secure").unwrap();
+
+    // Only a salted hash is stored, never the password
+    let user = store.user("admin").unwrap();
+    println!("Stored for admin: {}", user.password_hash());
+
+    let token = login(&mut store, &mut sessions, "admin", "Adm1n!Secure").unwrap();
+    println!("Logged in with token {}", token);
+    if let Err(e) = login(&mut store, &mut sessions, "admin", "adm1n!secure") {
+        println!("Login with a wrong password: {}", e);
+    }
+
+    // A second device logs in, then the first refreshes its token
+    let laptop = login(&mut store, &mut sessions, "admin", "Adm1n!Secure").unwrap();
+    let token = sessions.refresh(&token).unwrap();
+    println!("admin has {} sessions", sessions.sessions_for("admin").len());
+    sessions.logout(&laptop).unwrap();
+    println!("After logging out the laptop: {:?}", sessions.validate(&laptop).map(|s| s.username()));
+    println!("The refreshed token still works: {}", sessions.validate(&token).is_ok());
+
+    // Too many wrong guesses lock the account, even for the right password
+    for _ in 0..store.max_failures() {
+        let _ = store.verify("admin", "guess");
+    }
+    // The error doesn't say the account is locked: that would tell a
+    // stranger the username exists
+    if let Err(e) = store.verify("admin", "Adm1n!Secure") {
+        println!("Login refused: {}", e);
+    }
+    println!("admin is locked out: {}", store.user("admin").is_some_and(|user| user.is_locked()));
+    store.unlock("admin").unwrap();
+    println!("After unlocking: {}", login(&mut store, &mut sessions, "admin", "Adm1n!Secure").is_ok());
+}
+
+pub mod authentication {
+    use crate::password_policy::Violation;
+    use std::fmt;
+
+    /// Why registering or logging in failed
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum AuthError {
+        InvalidUsername(String),
+        /// Every rule of the store's password policy the password breaks
+        WeakPassword(Vec<Violation>),
+        UsernameTaken(String),
+        /// Wrong username or password, or a locked account. Deliberately
+        /// doesn't say which.
+        InvalidCredentials,
+        UnknownUser(String),
+        /// The token doesn't belong to any session, or was logged out or refreshed
+        InvalidSession,
+        SessionExpired,
+    }
+
+    impl fmt::Display for AuthError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                AuthError::InvalidUsername(name) => write!(
+                    f,
+                    "'{}' is not a valid username (at least {} letters, digits, '_' or '-')",
+                    name,
+                    validation::MIN_USERNAME_LEN
+                ),
+                AuthError::WeakPassword(violations) => {
+                    let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                    write!(f, "the password {}", reasons.join("; "))
+                }
+                AuthError::UsernameTaken(name) => write!(f, "the username '{}' is taken", name),
+                AuthError::InvalidCredentials => write!(f, "wrong username or password"),
+                AuthError::UnknownUser(name) => write!(f, "there is no user '{}'", name),
+                AuthError::InvalidSession => write!(f, "the session token is not valid"),
+                AuthError::SessionExpired => write!(f, "the session has expired, please log in again"),
+            }
+        }
+    }
+
+    impl std::error::Error for AuthError {}
+
+    //------------------------------------------------------
+    // VALIDATION
+    //------------------------------------------------------
+
+    pub mod validation {
+        use crate::password_policy::{CharClass, PasswordPolicy};
+
+        pub const MIN_USERNAME_LEN: usize = 3;
+
+        pub fn is_valid_username(username: &str) -> bool {
+            username.chars().count() >= MIN_USERNAME_LEN
+                && username
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        }
+
+        /// The rules from exercise 4 in 0_unit_testing.rs (at least 8
+        /// characters, an uppercase letter, a lowercase letter, a digit and a
+        /// special character), a length cap, and words every cracker tries first
+        pub fn password_policy() -> PasswordPolicy {
+            PasswordPolicy::new()
+                .min_length(8)
+                .max_length(128)
+                .require(CharClass::Uppercase)
+                .require(CharClass::Lowercase)
+                .require(CharClass::Digit)
+                .require(CharClass::Special)
+                .ban_words(&["password", "qwerty", "letmein"])
+        }
+    }
+
+    //------------------------------------------------------
+    // CREDENTIALS
+    //------------------------------------------------------
+
+    pub mod credentials {
+        use super::validation;
+        use super::AuthError;
+        use crate::password_policy::PasswordPolicy;
+        use crate::crypto::{constant_time_eq, from_hex, pbkdf2_hmac_sha256, sha256, to_hex, Sha256};
+        use std::collections::hash_map::RandomState;
+        use std::collections::HashMap;
+        use std::fmt;
+        use std::hash::{BuildHasher, Hasher};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        pub const SALT_LEN: usize = 16;
+        pub const HASH_LEN: usize = 32;
+
+        /// PBKDF2 iterations for new passwords. This keeps the examples
+        /// quick; OWASP currently suggests 600,000 for PBKDF2-HMAC-SHA-256.
+        pub const DEFAULT_ITERATIONS: u32 = 100_000;
+
+        /// Failed logins allowed before an account is locked
+        pub const DEFAULT_MAX_FAILURES: u32 = 5;
+
+        //--------------------------------------------------
+        // Salts
+        //--------------------------------------------------
+
+        /// Where new salts come from; tests use a predictable one
+        pub trait SaltSource {
+            fn next_salt(&mut self) -> [u8; SALT_LEN];
+        }
+
+        /// Salts built from std's randomly seeded `RandomState`, the time and
+        /// a counter. A salt only has to be unique, not secret, so this is
+        /// enough without an extra crate.
+        #[derive(Default)]
+        pub struct SystemSalt {
+            counter: u64,
+        }
+
+        impl SaltSource for SystemSalt {
+            fn next_salt(&mut self) -> [u8; SALT_LEN] {
+                self.counter += 1;
+                let mut seed = Sha256::new();
+                for _ in 0..2 {
+                    let mut hasher = RandomState::new().build_hasher();
+                    hasher.write_u64(self.counter);
+                    seed.update(&hasher.finish().to_le_bytes());
+                }
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                seed.update(&nanos.to_le_bytes());
+
+                let mut salt = [0u8; SALT_LEN];
+                salt.copy_from_slice(&seed.finish()[..SALT_LEN]);
+                salt
+            }
+        }
+
+        //--------------------------------------------------
+        // Password hashes
+        //--------------------------------------------------
+
+        /// A salted PBKDF2-HMAC-SHA-256 password hash. Its text form,
+        /// "pbkdf2-sha256$<iterations>$<salt>$<hash>", is what gets stored.
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct PasswordHash {
+            iterations: u32,
+            salt: [u8; SALT_LEN],
+            hash: [u8; HASH_LEN],
+        }
+
+        impl PasswordHash {
+            pub fn new(password: &str, salt: [u8; SALT_LEN], iterations: u32) -> PasswordHash {
+                let mut hash = [0u8; HASH_LEN];
+                pbkdf2_hmac_sha256(password.as_bytes(), &salt, iterations, &mut hash);
+                PasswordHash { iterations, salt, hash }
+            }
+
+            /// Hashes the guess with the same salt and iterations, then
+            /// compares in constant time
+            pub fn verify(&self, password: &str) -> bool {
+                let guess = PasswordHash::new(password, self.salt, self.iterations);
+                constant_time_eq(&guess.hash, &self.hash)
+            }
+
+            pub fn iterations(&self) -> u32 {
+                self.iterations
+            }
+
+            /// Reads back the text form written by `Display`
+            pub fn parse(text: &str) -> Option<PasswordHash> {
+                let mut parts = text.split('$');
+                if parts.next()? != "pbkdf2-sha256" {
+                    return None;
+                }
+                let iterations = parts.next()?.parse().ok().filter(|n| *n > 0)?;
+                let salt = from_hex(parts.next()?)?.try_into().ok()?;
+                let hash = from_hex(parts.next()?)?.try_into().ok()?;
+                if parts.next().is_some() {
+                    return None;
+                }
+                Some(PasswordHash { iterations, salt, hash })
+            }
+        }
+
+        impl fmt::Display for PasswordHash {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "pbkdf2-sha256${}${}${}",
+                    self.iterations,
+                    to_hex(&self.salt),
+                    to_hex(&self.hash)
+                )
+            }
+        }
+
+        //--------------------------------------------------
+        // Users and the store
+        //--------------------------------------------------
+
+        /// A registered user. There is no password field, only its hash,
+        /// and nothing outside this module can change either.
+        #[derive(Debug, Clone)]
+        pub struct User {
+            username: String,
+            password_hash: PasswordHash,
+            failed_attempts: u32,
+            locked: bool,
+        }
+
+        impl User {
+            pub fn get_username(&self) -> &str {
+                &self.username
+            }
+
+            pub fn password_hash(&self) -> &PasswordHash {
+                &self.password_hash
+            }
+
+            pub fn failed_attempts(&self) -> u32 {
+                self.failed_attempts
+            }
+
+            pub fn is_locked(&self) -> bool {
+                self.locked
+            }
+        }
+
+        pub struct CredentialStore {
+            users: HashMap<String, User>,
+            salts: Box<dyn SaltSource>,
+            iterations: u32,
+            max_failures: u32,
+            policy: PasswordPolicy,
+            // Checked for unknown usernames, so they take as long to
+            // reject as a wrong password
+            decoy: PasswordHash,
+        }
+
+        impl Default for CredentialStore {
+            fn default() -> Self {
+                CredentialStore::new()
+            }
+        }
+
+        impl CredentialStore {
+            pub fn new() -> CredentialStore {
+                CredentialStore::with_settings(Box::new(SystemSalt::default()), DEFAULT_ITERATIONS, DEFAULT_MAX_FAILURES)
+            }
+
+            pub fn with_settings(mut salts: Box<dyn SaltSource>, iterations: u32, max_failures: u32) -> CredentialStore {
+                let iterations = iterations.max(1);
+                let decoy = CredentialStore::make_decoy(salts.as_mut(), iterations);
+                CredentialStore {
+                    users: HashMap::new(),
+                    salts,
+                    iterations,
+                    max_failures: max_failures.max(1),
+                    policy: validation::password_policy(),
+                    decoy,
+                }
+            }
+
+            // A hash of a random password nobody knows
+            fn make_decoy(salts: &mut dyn SaltSource, iterations: u32) -> PasswordHash {
+                let decoy_password = to_hex(&sha256(&salts.next_salt()));
+                PasswordHash::new(&decoy_password, salts.next_salt(), iterations)
+            }
+
+            pub fn max_failures(&self) -> u32 {
+                self.max_failures
+            }
+
+            /// Changes the iteration count for passwords hashed from now on.
+            /// Existing hashes are upgraded the next time their user logs in.
+            pub fn set_iterations(&mut self, iterations: u32) {
+                self.iterations = iterations.max(1);
+                // The decoy must cost as much as a real hash
+                self.decoy = CredentialStore::make_decoy(self.salts.as_mut(), self.iterations);
+            }
+
+            /// Replaces `validation::password_policy()` for new and changed
+            /// passwords. Existing passwords keep working.
+            pub fn set_policy(&mut self, policy: PasswordPolicy) {
+                self.policy = policy;
+            }
+
+            pub fn policy(&self) -> &PasswordPolicy {
+                &self.policy
+            }
+
+            pub fn user(&self, username: &str) -> Option<&User> {
+                self.users.get(username)
+            }
+
+            pub fn register(&mut self, username: &str, password: &str) -> Result<(), AuthError> {
+                if !validation::is_valid_username(username) {
+                    return Err(AuthError::InvalidUsername(username.to_string()));
+                }
+                self.policy.check(password).map_err(AuthError::WeakPassword)?;
+                if self.users.contains_key(username) {
+                    return Err(AuthError::UsernameTaken(username.to_string()));
+                }
+
+                let password_hash = PasswordHash::new(password, self.salts.next_salt(), self.iterations);
+                self.users.insert(
+                    username.to_string(),
+                    User {
+                        username: username.to_string(),
+                        password_hash,
+                        failed_attempts: 0,
+                        locked: false,
+                    },
+                );
+                Ok(())
+            }
+
+            /// Checks a password. Locked accounts are refused even with the
+            /// right password; `max_failures` wrong ones in a row lock it.
+            /// Unknown users, wrong passwords and locked accounts all get the
+            /// same `InvalidCredentials` after the same hashing work, so the
+            /// answer never reveals whether a username exists.
+            pub fn verify(&mut self, username: &str, password: &str) -> Result<(), AuthError> {
+                let Some(user) = self.users.get_mut(username) else {
+                    self.decoy.verify(password);
+                    return Err(AuthError::InvalidCredentials);
+                };
+                let matches = user.password_hash.verify(password);
+                if user.locked {
+                    return Err(AuthError::InvalidCredentials);
+                }
+
+                if !matches {
+                    user.failed_attempts += 1;
+                    if user.failed_attempts >= self.max_failures {
+                        user.locked = true;
+                    }
+                    return Err(AuthError::InvalidCredentials);
+                }
+
+                user.failed_attempts = 0;
+                if user.password_hash.iterations() != self.iterations {
+                    user.password_hash = PasswordHash::new(password, self.salts.next_salt(), self.iterations);
+                }
+                Ok(())
+            }
+
+            pub fn change_password(&mut self, username: &str, old: &str, new: &str) -> Result<(), AuthError> {
+                self.verify(username, old)?;
+                self.policy.check(new).map_err(AuthError::WeakPassword)?;
+                let password_hash = PasswordHash::new(new, self.salts.next_salt(), self.iterations);
+                if let Some(user) = self.users.get_mut(username) {
+                    user.password_hash = password_hash;
+                }
+                Ok(())
+            }
+
+            /// Lets a locked-out user try again
+            pub fn unlock(&mut self, username: &str) -> Result<(), AuthError> {
+                let user = self
+                    .users
+                    .get_mut(username)
+                    .ok_or_else(|| AuthError::UnknownUser(username.to_string()))?;
+                user.locked = false;
+                user.failed_attempts = 0;
+                Ok(())
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // SESSIONS
+    //------------------------------------------------------
+
+    pub mod sessions {
+        use super::credentials::{SaltSource, SystemSalt};
+        use super::AuthError;
+        use crate::crypto::{sha256, to_hex, SHA256_LEN};
+        use std::collections::hash_map::Entry;
+        use std::collections::HashMap;
+        use std::fmt;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        /// How long a session lasts after login or its last refresh
+        pub const DEFAULT_TTL_SECS: u64 = 30 * 60;
+
+        pub trait Clock {
+            /// Seconds since the Unix epoch
+            fn now_secs(&self) -> u64;
+        }
+
+        pub struct SystemClock;
+
+        impl Clock for SystemClock {
+            fn now_secs(&self) -> u64 {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            }
+        }
+
+        /// Where new tokens come from; tests use a predictable one
+        pub trait TokenSource {
+            fn next_token(&mut self) -> String;
+        }
+
+        /// 32 bytes from two `SystemSalt`s, as hex. Unlike salts, tokens
+        /// must be unguessable; `RandomState`'s keys are random per process,
+        /// which is fine for learning, but a real service should draw tokens
+        /// from the OS (the `getrandom` crate)
+        #[derive(Default)]
+        pub struct RandomTokens {
+            salts: SystemSalt,
+        }
+
+        impl TokenSource for RandomTokens {
+            fn next_token(&mut self) -> String {
+                let mut bytes = self.salts.next_salt().to_vec();
+                bytes.extend_from_slice(&self.salts.next_salt());
+                to_hex(&bytes)
+            }
+        }
+
+        /// What a client gets back from `login` and presents on every request
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct SessionToken(String);
+
+        impl SessionToken {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for SessionToken {
+            fn from(token: &str) -> SessionToken {
+                SessionToken(token.to_string())
+            }
+        }
+
+        impl fmt::Display for SessionToken {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Session {
+            username: String,
+            created_at: u64,
+            expires_at: u64,
+        }
+
+        impl Session {
+            pub fn username(&self) -> &str {
+                &self.username
+            }
+
+            pub fn created_at(&self) -> u64 {
+                self.created_at
+            }
+
+            pub fn expires_at(&self) -> u64 {
+                self.expires_at
+            }
+
+            pub fn is_expired(&self, now: u64) -> bool {
+                now >= self.expires_at
+            }
+        }
+
+        /// Live sessions, keyed by the SHA-256 of their token so the
+        /// registry never holds a token that could be replayed
+        pub struct SessionRegistry {
+            sessions: HashMap<[u8; SHA256_LEN], Session>,
+            clock: Box<dyn Clock>,
+            tokens: Box<dyn TokenSource>,
+            ttl_secs: u64,
+        }
+
+        impl Default for SessionRegistry {
+            fn default() -> Self {
+                SessionRegistry::new()
+            }
+        }
+
+        fn key(token: &SessionToken) -> [u8; SHA256_LEN] {
+            sha256(token.as_str().as_bytes())
+        }
+
+        impl SessionRegistry {
+            pub fn new() -> SessionRegistry {
+                SessionRegistry::with_settings(Box::new(SystemClock), Box::new(RandomTokens::default()), DEFAULT_TTL_SECS)
+            }
+
+            pub fn with_settings(clock: Box<dyn Clock>, tokens: Box<dyn TokenSource>, ttl_secs: u64) -> SessionRegistry {
+                SessionRegistry {
+                    sessions: HashMap::new(),
+                    clock,
+                    tokens,
+                    ttl_secs: ttl_secs.max(1),
+                }
+            }
+
+            pub fn ttl_secs(&self) -> u64 {
+                self.ttl_secs
+            }
+
+            /// Only `login` starts sessions, after checking the password
+            pub(super) fn start(&mut self, username: &str) -> SessionToken {
+                let now = self.clock.now_secs();
+                let session = Session {
+                    username: username.to_string(),
+                    created_at: now,
+                    // A huge TTL means "never", not an overflow
+                    expires_at: now.saturating_add(self.ttl_secs),
+                };
+                self.insert(session)
+            }
+
+            fn insert(&mut self, session: Session) -> SessionToken {
+                loop {
+                    let token = SessionToken(self.tokens.next_token());
+                    if let Entry::Vacant(slot) = self.sessions.entry(key(&token)) {
+                        slot.insert(session);
+                        return token;
+                    }
+                }
+            }
+
+            /// The session behind a token, if it is still live
+            pub fn validate(&self, token: &SessionToken) -> Result<&Session, AuthError> {
+                let session = self.sessions.get(&key(token)).ok_or(AuthError::InvalidSession)?;
+                if session.is_expired(self.clock.now_secs()) {
+                    return Err(AuthError::SessionExpired);
+                }
+                Ok(session)
+            }
+
+            /// Swaps a live token for a new one with a fresh expiry. The old
+            /// token stops working, so a stolen one can't be refreshed twice.
+            pub fn refresh(&mut self, token: &SessionToken) -> Result<SessionToken, AuthError> {
+                let now = self.clock.now_secs();
+                let mut session = self.sessions.remove(&key(token)).ok_or(AuthError::InvalidSession)?;
+                if session.is_expired(now) {
+                    return Err(AuthError::SessionExpired);
+                }
+                session.expires_at = now.saturating_add(self.ttl_secs);
+                Ok(self.insert(session))
+            }
+
+            pub fn logout(&mut self, token: &SessionToken) -> Result<(), AuthError> {
+                self.sessions
+                    .remove(&key(token))
+                    .map(|_| ())
+                    .ok_or(AuthError::InvalidSession)
+            }
+
+            /// Ends every session for a user and returns how many there were
+            pub fn logout_all(&mut self, username: &str) -> usize {
+                let before = self.sessions.len();
+                self.sessions.retain(|_, session| session.username != username);
+                before - self.sessions.len()
+            }
+
+            /// A user's live sessions, oldest first
+            pub fn sessions_for(&self, username: &str) -> Vec<&Session> {
+                let now = self.clock.now_secs();
+                let mut sessions: Vec<&Session> = self
+                    .sessions
+                    .values()
+                    .filter(|session| session.username == username && !session.is_expired(now))
+                    .collect();
+                sessions.sort_by_key(|session| (session.created_at, session.expires_at));
+                sessions
+            }
+
+            /// Drops expired sessions and returns how many there were
+            pub fn purge_expired(&mut self) -> usize {
+                let now = self.clock.now_secs();
+                let before = self.sessions.len();
+                self.sessions.retain(|_, session| !session.is_expired(now));
+                before - self.sessions.len()
+            }
+        }
+    }
+
+    /// Checks the password and starts a session for the user
+    pub fn login(
+        store: &mut credentials::CredentialStore,
+        sessions: &mut sessions::SessionRegistry,
+        username: &str,
+        password: &str,
+    ) -> Result<sessions::SessionToken, AuthError> {
+        store.verify(username, password)?;
+        Ok(sessions.start(username))
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::authentication::credentials::{CredentialStore, PasswordHash, SaltSource, SALT_LEN};
+    use super::authentication::sessions::{Clock, SessionRegistry, SessionToken, TokenSource};
+    use super::authentication::validation::{is_valid_username, password_policy};
+    use super::authentication::{login, AuthError};
+    use super::password_policy::{CharClass, PasswordPolicy, Violation};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // Salts 1, 2, 3, ... so tests are repeatable
+    struct CountingSalt(u8);
+
+    impl SaltSource for CountingSalt {
+        fn next_salt(&mut self) -> [u8; SALT_LEN] {
+            self.0 += 1;
+            [self.0; SALT_LEN]
+        }
+    }
+
+    // Few iterations keep the tests fast; the hashing is the same
+    fn store() -> CredentialStore {
+        CredentialStore::with_settings(Box::new(CountingSalt(0)), 1_000, 3)
+    }
+
+    // A clock the test moves by hand
+    struct FakeClock {
+        now: Rc<Cell<u64>>,
+    }
+
+    impl Clock for FakeClock {
+        fn now_secs(&self) -> u64 {
+            self.now.get()
+        }
+    }
+
+    // Tokens "token-1", "token-2", ...
+    struct CountingTokens(u32);
+
+    impl TokenSource for CountingTokens {
+        fn next_token(&mut self) -> String {
+            self.0 += 1;
+            format!("token-{}", self.0)
+        }
+    }
+
+    const START: u64 = 1_700_000_000;
+    const TTL: u64 = 600;
+
+    fn sessions(now: &Rc<Cell<u64>>) -> SessionRegistry {
+        let clock = FakeClock { now: Rc::clone(now) };
+        SessionRegistry::with_settings(Box::new(clock), Box::new(CountingTokens(0)), TTL)
+    }
+
+    const PASSWORD: &str = "Adm1n!Secure";
+
+    #[test]
+    fn test_challenge_scenario() {
+        // Challenge 4 logs in "admin" with "password123", which the
+        // password rules no longer accept
+        let mut store = store();
+        assert_eq!(
+            store.register("admin", "password123"),
+            Err(AuthError::WeakPassword(vec![
+                Violation::MissingClass(CharClass::Uppercase),
+                Violation::MissingClass(CharClass::Special),
+                Violation::BannedWord("password".to_string()),
+            ]))
+        );
+
+        store.register("admin", PASSWORD).unwrap();
+        let mut sessions = sessions(&Rc::new(Cell::new(START)));
+        let token = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+        assert_eq!(sessions.validate(&token).unwrap().username(), "admin");
+        assert_eq!(login(&mut store, &mut sessions, "admin", "password123"), Err(AuthError::InvalidCredentials));
+    }
+
+    #[test]
+    fn test_password_rules() {
+        // The same cases as exercise 4 in 0_unit_testing.rs, except that
+        // "ValidP@ssw0rd" is "password" dressed up
+        let cases = vec![
+            ("short", false),
+            ("LongEnough123!", true),
+            ("nouppercase123!", false),
+            ("NOLOWERCASE123!", false),
+            ("NoDigits!", false),
+            ("NoSpecialChar123", false),
+            ("ValidP@ssw0rd", false),
+            ("An0ther!Valid1", true),
+        ];
+        for (password, expected) in cases {
+            assert_eq!(password_policy().is_valid(password), expected, "{}", password);
+        }
+
+        assert!(is_valid_username("admin"));
+        assert!(is_valid_username("ana_b-2"));
+        assert!(!is_valid_username("ab"));
+        assert!(!is_valid_username("has space"));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let mut store = store();
+        store.set_policy(PasswordPolicy::new().min_length(16).ban_word("rust"));
+
+        assert_eq!(
+            store.register("admin", PASSWORD),
+            Err(AuthError::WeakPassword(vec![Violation::TooShort { min: 16, actual: 12 }]))
+        );
+        let error = store.register("admin", "trusty and long enough").unwrap_err();
+        assert_eq!(error.to_string(), "the password must not contain the word 'rust'");
+        store.register("admin", "correct horse battery staple").unwrap();
+    }
+
+    #[test]
+    fn test_registration_errors() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+
+        assert_eq!(store.register("admin", PASSWORD), Err(AuthError::UsernameTaken("admin".to_string())));
+        assert_eq!(store.register("x", PASSWORD), Err(AuthError::InvalidUsername("x".to_string())));
+    }
+
+    #[test]
+    fn test_only_salted_hashes_are_stored() {
+        let mut store = store();
+        store.register("ana", PASSWORD).unwrap();
+        store.register("ben", PASSWORD).unwrap();
+
+        let ana = store.user("ana").unwrap().password_hash().to_string();
+        let ben = store.user("ben").unwrap().password_hash().to_string();
+        assert!(!ana.contains(PASSWORD));
+        assert!(ana.starts_with("pbkdf2-sha256$1000$"));
+        // Same password, different salts, different hashes
+        assert_ne!(ana, ben);
+    }
+
+    #[test]
+    fn test_password_hash_text_round_trip() {
+        let hash = PasswordHash::new(PASSWORD, [7; SALT_LEN], 10);
+        let parsed = PasswordHash::parse(&hash.to_string()).unwrap();
+        assert_eq!(parsed, hash);
+        assert!(parsed.verify(PASSWORD));
+        assert!(!parsed.verify("Adm1n!Secure "));
+
+        let text = hash.to_string();
+        assert!(PasswordHash::parse(&text.replace("pbkdf2-sha256", "md5")).is_none());
+        assert!(PasswordHash::parse(&text.replace("$10$", "$0$")).is_none());
+        assert!(PasswordHash::parse(&format!("{}$extra", text)).is_none());
+        assert!(PasswordHash::parse(&text[..text.len() - 2]).is_none());
+    }
+
+    #[test]
+    fn test_lockout_after_failed_attempts() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+
+        // A success resets the count
+        assert_eq!(store.verify("admin", "wrong"), Err(AuthError::InvalidCredentials));
+        assert_eq!(store.verify("admin", "wrong"), Err(AuthError::InvalidCredentials));
+        store.verify("admin", PASSWORD).unwrap();
+        assert_eq!(store.user("admin").unwrap().failed_attempts(), 0);
+
+        for _ in 0..3 {
+            assert_eq!(store.verify("admin", "wrong"), Err(AuthError::InvalidCredentials));
+        }
+        assert!(store.user("admin").unwrap().is_locked());
+        // Locked looks the same as a wrong password or an unknown user
+        assert_eq!(store.verify("admin", PASSWORD), Err(AuthError::InvalidCredentials));
+        assert_eq!(store.verify("admin", "wrong"), store.verify("nobody", PASSWORD));
+        assert_eq!(store.user("admin").unwrap().failed_attempts(), 3);
+
+        store.unlock("admin").unwrap();
+        assert!(store.verify("admin", PASSWORD).is_ok());
+        assert_eq!(store.unlock("nobody"), Err(AuthError::UnknownUser("nobody".to_string())));
+    }
+
+    #[test]
+    fn test_unknown_user_looks_like_a_wrong_password() {
+        let mut store = store();
+        assert_eq!(store.verify("nobody", PASSWORD), Err(AuthError::InvalidCredentials));
+    }
+
+    #[test]
+    fn test_change_password() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+
+        assert_eq!(store.change_password("admin", "wrong", "N3w!Passphrase"), Err(AuthError::InvalidCredentials));
+        assert!(matches!(
+            store.change_password("admin", PASSWORD, "weak"),
+            Err(AuthError::WeakPassword(violations)) if violations.len() == 4
+        ));
+        store.change_password("admin", PASSWORD, "N3w!Passphrase").unwrap();
+        assert!(store.verify("admin", PASSWORD).is_err());
+        assert!(store.verify("admin", "N3w!Passphrase").is_ok());
+    }
+
+    #[test]
+    fn test_hashes_are_upgraded_on_login() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+
+        store.set_iterations(2_000);
+        assert_eq!(store.user("admin").unwrap().password_hash().iterations(), 1_000);
+        store.verify("admin", PASSWORD).unwrap();
+        assert_eq!(store.user("admin").unwrap().password_hash().iterations(), 2_000);
+        assert!(store.verify("admin", PASSWORD).is_ok());
+    }
+
+    #[test]
+    fn test_login_starts_a_session() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+        let now = Rc::new(Cell::new(START));
+        let mut sessions = sessions(&now);
+
+        let token = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+        assert_eq!(token, SessionToken::from("token-1"));
+        let session = sessions.validate(&token).unwrap();
+        assert_eq!(session.created_at(), START);
+        assert_eq!(session.expires_at(), START + TTL);
+
+        assert_eq!(sessions.validate(&SessionToken::from("token-9")), Err(AuthError::InvalidSession));
+        assert_eq!(login(&mut store, &mut sessions, "nobody", PASSWORD), Err(AuthError::InvalidCredentials));
+        assert!(sessions.sessions_for("nobody").is_empty());
+    }
+
+    #[test]
+    fn test_sessions_expire() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+        let now = Rc::new(Cell::new(START));
+        let mut sessions = sessions(&now);
+        let token = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+
+        // Test cases: (seconds after login, still valid)
+        let cases = vec![(0, true), (TTL - 1, true), (TTL, false), (TTL * 2, false)];
+        for (elapsed, valid) in cases {
+            now.set(START + elapsed);
+            assert_eq!(sessions.validate(&token).is_ok(), valid, "{}s after login", elapsed);
+        }
+        assert_eq!(sessions.validate(&token), Err(AuthError::SessionExpired));
+        assert_eq!(sessions.refresh(&token), Err(AuthError::SessionExpired));
+        // A failed refresh of an expired session drops it
+        assert_eq!(sessions.validate(&token), Err(AuthError::InvalidSession));
+    }
+
+    #[test]
+    fn test_huge_ttl_never_expires() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+        let now = Rc::new(Cell::new(START));
+        let clock = FakeClock { now: Rc::clone(&now) };
+        let mut sessions = SessionRegistry::with_settings(Box::new(clock), Box::new(CountingTokens(0)), u64::MAX);
+
+        // The expiry stops at u64::MAX instead of overflowing
+        let token = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+        assert_eq!(sessions.validate(&token).unwrap().expires_at(), u64::MAX);
+
+        now.set(u64::MAX - 1);
+        let token = sessions.refresh(&token).unwrap();
+        assert_eq!(sessions.validate(&token).unwrap().expires_at(), u64::MAX);
+    }
+
+    #[test]
+    fn test_refresh_rotates_the_token() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+        let now = Rc::new(Cell::new(START));
+        let mut sessions = sessions(&now);
+        let old = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+
+        now.set(START + TTL - 10);
+        let new = sessions.refresh(&old).unwrap();
+        assert_ne!(new, old);
+        assert_eq!(sessions.validate(&old), Err(AuthError::InvalidSession));
+        assert_eq!(sessions.refresh(&old), Err(AuthError::InvalidSession));
+
+        // The session keeps its start time but gets a new expiry
+        now.set(START + TTL + 10);
+        let session = sessions.validate(&new).unwrap();
+        assert_eq!(session.created_at(), START);
+        assert_eq!(session.expires_at(), START + TTL - 10 + TTL);
+    }
+
+    #[test]
+    fn test_logout_and_listing_sessions() {
+        let mut store = store();
+        store.register("admin", PASSWORD).unwrap();
+        store.register("guest", "Gu3st!Pass").unwrap();
+        let now = Rc::new(Cell::new(START));
+        let mut sessions = sessions(&now);
+
+        let phone = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+        now.set(START + 100);
+        let laptop = login(&mut store, &mut sessions, "admin", PASSWORD).unwrap();
+        now.set(START + 200);
+        let _guest = login(&mut store, &mut sessions, "guest", "Gu3st!Pass").unwrap();
+
+        let started: Vec<u64> = sessions.sessions_for("admin").iter().map(|s| s.created_at()).collect();
+        assert_eq!(started, vec![START, START + 100]);
+
+        sessions.logout(&phone).unwrap();
+        assert_eq!(sessions.logout(&phone), Err(AuthError::InvalidSession));
+        assert_eq!(sessions.sessions_for("admin").len(), 1);
+        assert!(sessions.validate(&laptop).is_ok());
+
+        // Expired sessions aren't listed, and purging removes them
+        now.set(START + 100 + TTL);
+        assert!(sessions.sessions_for("admin").is_empty());
+        assert_eq!(sessions.sessions_for("guest").len(), 1);
+        assert_eq!(sessions.purge_expired(), 1);
+
+        assert_eq!(sessions.logout_all("guest"), 1);
+        assert_eq!(sessions.logout_all("guest"), 0);
+    }
+}
@@ -0,0 +1,831 @@
+// Restaurant Orders: The `restaurant` Module as a Working Order Pipeline
+//
+// The `restaurant` module at the bottom of 0_modules.rs shows how to lay out
+// modules, but its functions only print. This file keeps the same layout,
+// `front_of_house::{hosting, serving}` and `back_of_house`, and runs a real
+// order through it:
+//
+// 1. `hosting`: parties wait in a queue and are seated at the smallest free
+//    table that fits them.
+// 2. `serving::take_order` sends the table's dishes to the kitchen.
+// 3. `back_of_house`: the kitchen cooks tickets in order, moving each order
+//    from Queued to Cooking to Ready. `fix_incorrect_order` sends an order
+//    that hasn't been served yet back to the front of the queue.
+// 4. `serving::serve_order` brings ready orders to the table, and
+//    `serving::take_payment` settles the bill, split however the party
+//    likes, and frees the table.
+//
+// The `Restaurant` struct's fields are private to the `restaurant` module,
+// so only its submodules can touch the waitlist, tables and kitchen.
+// Prices use the shared fixed-point `Money` type (chapters/shared/money.rs).
+//
+// Try the module exercises in 0_modules.rs before reading this file!
+
+// The restaurant only uses part of the money module
+#[allow(dead_code)]
+#[path = "../../shared/money.rs"]
+mod money;
+
+fn main() {
+    println!("A restaurant order pipeline organized with modules!");
+
+    if let Err(e) = restaurant::eat_at_restaurant() {
+        println!("Something went wrong: {}", e);
+    }
+}
+
+pub mod restaurant {
+    use crate::money::{Currency, Money, MoneyError};
+    use std::collections::{BTreeMap, VecDeque};
+    use std::fmt;
+
+    pub type PartyId = u32;
+    pub type TableId = u32;
+    pub type OrderId = u32;
+
+    //------------------------------------------------------
+    // ERRORS
+    //------------------------------------------------------
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum RestaurantError {
+        UnknownParty(PartyId),
+        UnknownTable(TableId),
+        UnknownOrder(OrderId),
+        TableNotSeated(TableId),
+        EmptyOrder,
+        InvalidTransition {
+            order: OrderId,
+            from: OrderStatus,
+            to: OrderStatus,
+        },
+        /// Payment was asked for while some orders are still in the kitchen
+        OrdersOutstanding(Vec<OrderId>),
+        InvalidSplit(String),
+        Money(MoneyError),
+    }
+
+    impl fmt::Display for RestaurantError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RestaurantError::UnknownParty(id) => write!(f, "no party #{}", id),
+                RestaurantError::UnknownTable(id) => write!(f, "no table #{}", id),
+                RestaurantError::UnknownOrder(id) => write!(f, "no order #{}", id),
+                RestaurantError::TableNotSeated(id) => write!(f, "nobody is seated at table #{}", id),
+                RestaurantError::EmptyOrder => write!(f, "an order needs at least one dish"),
+                RestaurantError::InvalidTransition { order, from, to } => {
+                    write!(f, "order #{} can't go from {:?} to {:?}", order, from, to)
+                }
+                RestaurantError::OrdersOutstanding(ids) => {
+                    write!(f, "orders {:?} haven't been served yet", ids)
+                }
+                RestaurantError::InvalidSplit(reason) => write!(f, "can't split the bill: {}", reason),
+                RestaurantError::Money(e) => write!(f, "{}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for RestaurantError {}
+
+    impl From<MoneyError> for RestaurantError {
+        fn from(e: MoneyError) -> Self {
+            RestaurantError::Money(e)
+        }
+    }
+
+    //------------------------------------------------------
+    // RESTAURANT STATE
+    //------------------------------------------------------
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Party {
+        pub id: PartyId,
+        pub name: String,
+        pub size: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Table {
+        pub id: TableId,
+        pub seats: u32,
+        party: Option<Party>,
+    }
+
+    impl Table {
+        pub fn party(&self) -> Option<&Party> {
+            self.party.as_ref()
+        }
+
+        pub fn is_free(&self) -> bool {
+            self.party.is_none()
+        }
+    }
+
+    /// Where an order is in its life
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum OrderStatus {
+        Queued,
+        Cooking,
+        Ready,
+        Served,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Order {
+        id: OrderId,
+        table: TableId,
+        dishes: Vec<back_of_house::Dish>,
+        status: OrderStatus,
+        remakes: u32,
+    }
+
+    impl Order {
+        pub fn id(&self) -> OrderId {
+            self.id
+        }
+
+        pub fn table(&self) -> TableId {
+            self.table
+        }
+
+        pub fn dishes(&self) -> &[back_of_house::Dish] {
+            &self.dishes
+        }
+
+        pub fn status(&self) -> OrderStatus {
+            self.status
+        }
+
+        /// How many times the kitchen had to make this order again
+        pub fn remakes(&self) -> u32 {
+            self.remakes
+        }
+
+        pub fn total(&self) -> Result<Money, MoneyError> {
+            Money::sum(self.dishes.iter().map(|dish| dish.price()), Currency::USD)
+        }
+
+        // One of the two ways an order's status changes (see `remake`);
+        // anything other than the next step in
+        // Queued -> Cooking -> Ready -> Served is refused
+        fn advance(&mut self, to: OrderStatus) -> Result<(), RestaurantError> {
+            let allowed = matches!(
+                (self.status, to),
+                (OrderStatus::Queued, OrderStatus::Cooking)
+                    | (OrderStatus::Cooking, OrderStatus::Ready)
+                    | (OrderStatus::Ready, OrderStatus::Served)
+            );
+            if !allowed {
+                return Err(RestaurantError::InvalidTransition {
+                    order: self.id,
+                    from: self.status,
+                    to,
+                });
+            }
+            self.status = to;
+            Ok(())
+        }
+
+        // Sends the order back to Queued with new dishes. An order that
+        // was already served can't be remade.
+        fn remake(&mut self, dishes: Vec<back_of_house::Dish>) -> Result<(), RestaurantError> {
+            if self.status == OrderStatus::Served {
+                return Err(RestaurantError::InvalidTransition {
+                    order: self.id,
+                    from: self.status,
+                    to: OrderStatus::Queued,
+                });
+            }
+            self.dishes = dishes;
+            self.status = OrderStatus::Queued;
+            self.remakes += 1;
+            Ok(())
+        }
+    }
+
+    /// A restaurant with its tables, waitlist and kitchen. Everything is
+    /// private to this module: customers go through `front_of_house`.
+    pub struct Restaurant {
+        tables: Vec<Table>,
+        waitlist: VecDeque<Party>,
+        next_party_id: PartyId,
+        orders: BTreeMap<OrderId, Order>,
+        next_order_id: OrderId,
+        // Order ids waiting for a cook, front first
+        kitchen_queue: VecDeque<OrderId>,
+    }
+
+    impl Restaurant {
+        /// A restaurant with one table per entry in `table_seats`,
+        /// numbered from 1
+        pub fn new(table_seats: &[u32]) -> Restaurant {
+            let tables = table_seats
+                .iter()
+                .enumerate()
+                .map(|(index, &seats)| Table {
+                    id: index as TableId + 1,
+                    seats,
+                    party: None,
+                })
+                .collect();
+            Restaurant {
+                tables,
+                waitlist: VecDeque::new(),
+                next_party_id: 1,
+                orders: BTreeMap::new(),
+                next_order_id: 1,
+                kitchen_queue: VecDeque::new(),
+            }
+        }
+
+        pub fn tables(&self) -> &[Table] {
+            &self.tables
+        }
+
+        pub fn waitlist(&self) -> impl Iterator<Item = &Party> {
+            self.waitlist.iter()
+        }
+
+        pub fn order(&self, id: OrderId) -> Result<&Order, RestaurantError> {
+            self.orders.get(&id).ok_or(RestaurantError::UnknownOrder(id))
+        }
+
+        pub fn orders_for(&self, table: TableId) -> Vec<&Order> {
+            self.orders.values().filter(|order| order.table == table).collect()
+        }
+
+        /// Order ids in the order the kitchen will cook them
+        pub fn kitchen_queue(&self) -> Vec<OrderId> {
+            self.kitchen_queue.iter().copied().collect()
+        }
+
+        fn table_mut(&mut self, id: TableId) -> Result<&mut Table, RestaurantError> {
+            self.tables
+                .iter_mut()
+                .find(|table| table.id == id)
+                .ok_or(RestaurantError::UnknownTable(id))
+        }
+
+        fn order_mut(&mut self, id: OrderId) -> Result<&mut Order, RestaurantError> {
+            self.orders.get_mut(&id).ok_or(RestaurantError::UnknownOrder(id))
+        }
+    }
+
+    //------------------------------------------------------
+    // FRONT OF HOUSE
+    //------------------------------------------------------
+
+    pub mod front_of_house {
+        pub mod hosting {
+            use super::super::{Party, PartyId, Restaurant, RestaurantError, TableId};
+
+            /// Puts a party at the back of the waitlist; returns its id
+            pub fn add_to_waitlist(restaurant: &mut Restaurant, name: &str, size: u32) -> PartyId {
+                let id = restaurant.next_party_id;
+                restaurant.next_party_id += 1;
+                restaurant.waitlist.push_back(Party {
+                    id,
+                    name: name.to_string(),
+                    size,
+                });
+                id
+            }
+
+            /// Seats the first waiting party that fits at a free table,
+            /// using the smallest table that is big enough. A large party
+            /// at the front doesn't hold up smaller ones behind it.
+            pub fn seat_at_table(restaurant: &mut Restaurant) -> Option<(PartyId, TableId)> {
+                for position in 0..restaurant.waitlist.len() {
+                    let size = restaurant.waitlist[position].size;
+                    let table = restaurant
+                        .tables
+                        .iter_mut()
+                        .filter(|table| table.is_free() && table.seats >= size)
+                        .min_by_key(|table| table.seats);
+                    if let Some(table) = table {
+                        let party = restaurant.waitlist.remove(position)?;
+                        let seated = (party.id, table.id);
+                        table.party = Some(party);
+                        return Some(seated);
+                    }
+                }
+                None
+            }
+
+            /// Seats as many waiting parties as the free tables allow
+            pub fn seat_everyone_possible(restaurant: &mut Restaurant) -> Vec<(PartyId, TableId)> {
+                std::iter::from_fn(|| seat_at_table(restaurant)).collect()
+            }
+
+            /// A party that gives up waiting
+            pub fn leave_waitlist(restaurant: &mut Restaurant, party: PartyId) -> Result<(), RestaurantError> {
+                let position = restaurant
+                    .waitlist
+                    .iter()
+                    .position(|waiting| waiting.id == party)
+                    .ok_or(RestaurantError::UnknownParty(party))?;
+                restaurant.waitlist.remove(position);
+                Ok(())
+            }
+        }
+
+        pub mod serving {
+            use super::super::back_of_house::Dish;
+            use super::super::{Order, OrderId, OrderStatus, Restaurant, RestaurantError, TableId};
+            use crate::money::{Currency, Money};
+
+            /// How a table wants to pay
+            #[derive(Debug, Clone, PartialEq)]
+            pub enum Split {
+                /// One person pays everything
+                Single,
+                /// Everyone pays the same; leftover cents go to the first payers
+                Evenly(usize),
+                /// Each order is paid by whoever ordered it
+                ByOrder,
+            }
+
+            /// A settled bill
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Bill {
+                pub table: TableId,
+                pub party: String,
+                /// (dish, price) for everything the table ate
+                pub lines: Vec<(String, Money)>,
+                pub total: Money,
+                /// What each payer owes; always adds up to `total`
+                pub shares: Vec<Money>,
+            }
+
+            /// Sends a seated table's dishes to the kitchen
+            pub fn take_order(
+                restaurant: &mut Restaurant,
+                table: TableId,
+                dishes: Vec<Dish>,
+            ) -> Result<OrderId, RestaurantError> {
+                if restaurant.table_mut(table)?.is_free() {
+                    return Err(RestaurantError::TableNotSeated(table));
+                }
+                if dishes.is_empty() {
+                    return Err(RestaurantError::EmptyOrder);
+                }
+
+                let id = restaurant.next_order_id;
+                restaurant.next_order_id += 1;
+                restaurant.orders.insert(
+                    id,
+                    Order {
+                        id,
+                        table,
+                        dishes,
+                        status: OrderStatus::Queued,
+                        remakes: 0,
+                    },
+                );
+                restaurant.kitchen_queue.push_back(id);
+                Ok(id)
+            }
+
+            /// Brings a ready order to its table
+            pub fn serve_order(restaurant: &mut Restaurant, order: OrderId) -> Result<(), RestaurantError> {
+                restaurant.order_mut(order)?.advance(OrderStatus::Served)
+            }
+
+            /// Settles the table's bill once every order has been served,
+            /// then frees the table for the next party
+            pub fn take_payment(
+                restaurant: &mut Restaurant,
+                table: TableId,
+                split: Split,
+            ) -> Result<Bill, RestaurantError> {
+                let party = match restaurant.table_mut(table)?.party() {
+                    Some(party) => party.name.clone(),
+                    None => return Err(RestaurantError::TableNotSeated(table)),
+                };
+
+                let orders = restaurant.orders_for(table);
+                let outstanding: Vec<OrderId> = orders
+                    .iter()
+                    .filter(|order| order.status != OrderStatus::Served)
+                    .map(|order| order.id)
+                    .collect();
+                if !outstanding.is_empty() {
+                    return Err(RestaurantError::OrdersOutstanding(outstanding));
+                }
+
+                let lines: Vec<(String, Money)> = orders
+                    .iter()
+                    .flat_map(|order| order.dishes.iter())
+                    .map(|dish| (dish.name(), dish.price()))
+                    .collect();
+                let total = Money::sum(lines.iter().map(|(_, price)| *price), Currency::USD)?;
+                let shares = match split {
+                    Split::Single => vec![total],
+                    Split::Evenly(0) => return Err(RestaurantError::InvalidSplit("nobody to pay".to_string())),
+                    Split::Evenly(people) => total.allocate(people)?,
+                    Split::ByOrder if orders.is_empty() => {
+                        return Err(RestaurantError::InvalidSplit("there are no orders".to_string()))
+                    }
+                    Split::ByOrder => orders.iter().map(|order| order.total()).collect::<Result<_, _>>()?,
+                };
+
+                let ids: Vec<OrderId> = orders.iter().map(|order| order.id).collect();
+                for id in ids {
+                    restaurant.orders.remove(&id);
+                }
+                restaurant.table_mut(table)?.party = None;
+
+                Ok(Bill {
+                    table,
+                    party,
+                    lines,
+                    total,
+                    shares,
+                })
+            }
+        }
+    }
+
+    //------------------------------------------------------
+    // BACK OF HOUSE
+    //------------------------------------------------------
+
+    pub mod back_of_house {
+        use super::{OrderId, OrderStatus, Restaurant, RestaurantError};
+        use crate::money::Money;
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Breakfast {
+            pub toast: String,
+            seasonal_fruit: String,
+        }
+
+        impl Breakfast {
+            pub fn summer(toast: &str) -> Breakfast {
+                Breakfast {
+                    toast: String::from(toast),
+                    seasonal_fruit: String::from("peaches"),
+                }
+            }
+
+            pub fn winter(toast: &str) -> Breakfast {
+                Breakfast {
+                    toast: String::from(toast),
+                    seasonal_fruit: String::from("oranges"),
+                }
+            }
+
+            /// Customers can see the fruit, but only the kitchen picks it
+            pub fn seasonal_fruit(&self) -> &str {
+                &self.seasonal_fruit
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Appetizer {
+            Soup,
+            Salad,
+        }
+
+        /// Anything on the menu
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum Dish {
+            Breakfast(Breakfast),
+            Appetizer(Appetizer),
+        }
+
+        impl Dish {
+            pub fn name(&self) -> String {
+                match self {
+                    Dish::Breakfast(breakfast) => {
+                        format!("Breakfast ({} toast, {})", breakfast.toast, breakfast.seasonal_fruit)
+                    }
+                    Dish::Appetizer(Appetizer::Soup) => "Soup".to_string(),
+                    Dish::Appetizer(Appetizer::Salad) => "Salad".to_string(),
+                }
+            }
+
+            pub fn price(&self) -> Money {
+                match self {
+                    Dish::Breakfast(_) => Money::usd(950),
+                    Dish::Appetizer(Appetizer::Soup) => Money::usd(500),
+                    Dish::Appetizer(Appetizer::Salad) => Money::usd(650),
+                }
+            }
+        }
+
+        /// A cook takes the next ticket from the queue; returns its order id
+        pub fn start_next_order(restaurant: &mut Restaurant) -> Option<OrderId> {
+            let id = restaurant.kitchen_queue.pop_front()?;
+            // Only queued orders are ever in the queue, so this can't fail
+            restaurant
+                .order_mut(id)
+                .and_then(|order| order.advance(OrderStatus::Cooking))
+                .ok()?;
+            Some(id)
+        }
+
+        /// A cook finishes an order, ready for a server to bring it out
+        pub fn finish_order(restaurant: &mut Restaurant, order: OrderId) -> Result<(), RestaurantError> {
+            restaurant.order_mut(order)?.advance(OrderStatus::Ready)
+        }
+
+        /// Cooks everything in the queue, in order; returns the order ids
+        pub fn cook_all(restaurant: &mut Restaurant) -> Vec<OrderId> {
+            let mut cooked = Vec::new();
+            while let Some(id) = start_next_order(restaurant) {
+                if cook_order(restaurant, id).is_ok() {
+                    cooked.push(id);
+                }
+            }
+            cooked
+        }
+
+        /// Replaces the dishes of an order that came out wrong and puts it
+        /// back at the front of the kitchen queue. Served orders can't be fixed.
+        pub fn fix_incorrect_order(
+            restaurant: &mut Restaurant,
+            order: OrderId,
+            dishes: Vec<Dish>,
+        ) -> Result<(), RestaurantError> {
+            if dishes.is_empty() {
+                return Err(RestaurantError::EmptyOrder);
+            }
+            restaurant.order_mut(order)?.remake(dishes)?;
+
+            restaurant.kitchen_queue.retain(|id| *id != order);
+            restaurant.kitchen_queue.push_front(order);
+            Ok(())
+        }
+
+        // Private: the kitchen decides how an order gets cooked
+        fn cook_order(restaurant: &mut Restaurant, order: OrderId) -> Result<(), RestaurantError> {
+            finish_order(restaurant, order)
+        }
+    }
+
+    /// The same walk through the restaurant as in 0_modules.rs, now with
+    /// real orders: wait, sit, order, cook, fix a mistake, serve and pay
+    pub fn eat_at_restaurant() -> Result<(), RestaurantError> {
+        use back_of_house::{Appetizer, Breakfast, Dish};
+        use front_of_house::serving::Split;
+
+        let mut restaurant = Restaurant::new(&[2, 4]);
+
+        // Absolute path
+        let ana = crate::restaurant::front_of_house::hosting::add_to_waitlist(&mut restaurant, "Ana", 3);
+        // Relative path
+        front_of_house::hosting::add_to_waitlist(&mut restaurant, "Ben", 2);
+        front_of_house::hosting::add_to_waitlist(&mut restaurant, "Cy", 2);
+        for (party, table) in front_of_house::hosting::seat_everyone_possible(&mut restaurant) {
+            println!("Party #{} seated at table #{}", party, table);
+        }
+        let waiting: Vec<&str> = restaurant.waitlist().map(|party| party.name.as_str()).collect();
+        println!("Still waiting: {:?}", waiting);
+
+        let table = restaurant
+            .tables()
+            .iter()
+            .find(|table| table.party().map(|party| party.id) == Some(ana))
+            .map(|table| table.id)
+            .ok_or(RestaurantError::UnknownParty(ana))?;
+
+        // Order a breakfast in the summer with Rye toast, then change our
+        // mind about the bread
+        let mut meal = Breakfast::summer("Rye");
+        meal.toast = String::from("Wheat");
+        println!("I'd like {} toast please", meal.toast);
+
+        // This would cause an error:
+        // meal.seasonal_fruit = String::from("blueberries");
+
+        let first = front_of_house::serving::take_order(&mut restaurant, table, vec![Dish::Breakfast(meal)])?;
+        let second = front_of_house::serving::take_order(
+            &mut restaurant,
+            table,
+            vec![Dish::Appetizer(Appetizer::Soup), Dish::Appetizer(Appetizer::Salad)],
+        )?;
+        println!("Kitchen cooked orders {:?}", back_of_house::cook_all(&mut restaurant));
+
+        // The soup should have been a second salad
+        back_of_house::fix_incorrect_order(
+            &mut restaurant,
+            second,
+            vec![Dish::Appetizer(Appetizer::Salad), Dish::Appetizer(Appetizer::Salad)],
+        )?;
+        println!("Kitchen remade orders {:?}", back_of_house::cook_all(&mut restaurant));
+
+        front_of_house::serving::serve_order(&mut restaurant, first)?;
+        front_of_house::serving::serve_order(&mut restaurant, second)?;
+
+        let bill = front_of_house::serving::take_payment(&mut restaurant, table, Split::Evenly(3))?;
+        println!("\nBill for {} at table #{}:", bill.party, bill.table);
+        for (dish, price) in &bill.lines {
+            println!("  {:<34} {:>7}", dish, price.to_string());
+        }
+        println!("  {:<34} {:>7}", "Total", bill.total.to_string());
+        let shares: Vec<String> = bill.shares.iter().map(|share| share.to_string()).collect();
+        println!("  Each of the 3 pays: {}", shares.join(", "));
+
+        // The table is free again, so the next party can sit down
+        for (party, table) in front_of_house::hosting::seat_everyone_possible(&mut restaurant) {
+            println!("\nParty #{} seated at table #{}", party, table);
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::money::Money;
+    use super::restaurant::back_of_house::{self, Appetizer, Breakfast, Dish};
+    use super::restaurant::front_of_house::hosting;
+    use super::restaurant::front_of_house::serving::{self, Split};
+    use super::restaurant::{OrderStatus, Restaurant, RestaurantError, TableId};
+
+    fn soup() -> Dish {
+        Dish::Appetizer(Appetizer::Soup)
+    }
+
+    fn salad() -> Dish {
+        Dish::Appetizer(Appetizer::Salad)
+    }
+
+    // A restaurant with one seated party of two at table 1
+    fn seated() -> (Restaurant, TableId) {
+        let mut restaurant = Restaurant::new(&[2]);
+        hosting::add_to_waitlist(&mut restaurant, "Ana", 2);
+        let (_, table) = hosting::seat_at_table(&mut restaurant).unwrap();
+        (restaurant, table)
+    }
+
+    #[test]
+    fn test_waitlist_seats_smallest_fitting_table() {
+        let mut restaurant = Restaurant::new(&[6, 2, 4]);
+        let big = hosting::add_to_waitlist(&mut restaurant, "Big", 8);
+        let pair = hosting::add_to_waitlist(&mut restaurant, "Pair", 2);
+        let four = hosting::add_to_waitlist(&mut restaurant, "Four", 3);
+
+        // The party of eight fits nowhere, but doesn't block the others
+        assert_eq!(hosting::seat_at_table(&mut restaurant), Some((pair, 2)));
+        assert_eq!(hosting::seat_at_table(&mut restaurant), Some((four, 3)));
+        assert_eq!(hosting::seat_at_table(&mut restaurant), None);
+        assert_eq!(restaurant.waitlist().map(|p| p.id).collect::<Vec<_>>(), vec![big]);
+
+        hosting::leave_waitlist(&mut restaurant, big).unwrap();
+        assert_eq!(hosting::leave_waitlist(&mut restaurant, big), Err(RestaurantError::UnknownParty(big)));
+    }
+
+    #[test]
+    fn test_order_goes_through_kitchen_states() {
+        let (mut restaurant, table) = seated();
+        let order = serving::take_order(&mut restaurant, table, vec![soup()]).unwrap();
+        assert_eq!(restaurant.order(order).unwrap().status(), OrderStatus::Queued);
+
+        // Can't serve what hasn't been cooked
+        assert_eq!(
+            serving::serve_order(&mut restaurant, order),
+            Err(RestaurantError::InvalidTransition {
+                order,
+                from: OrderStatus::Queued,
+                to: OrderStatus::Served
+            })
+        );
+
+        assert_eq!(back_of_house::start_next_order(&mut restaurant), Some(order));
+        assert_eq!(restaurant.order(order).unwrap().status(), OrderStatus::Cooking);
+        back_of_house::finish_order(&mut restaurant, order).unwrap();
+        assert_eq!(restaurant.order(order).unwrap().status(), OrderStatus::Ready);
+        serving::serve_order(&mut restaurant, order).unwrap();
+        assert_eq!(restaurant.order(order).unwrap().status(), OrderStatus::Served);
+        assert!(serving::serve_order(&mut restaurant, order).is_err());
+    }
+
+    #[test]
+    fn test_orders_need_a_seated_table_and_dishes() {
+        let (mut restaurant, table) = seated();
+        assert_eq!(serving::take_order(&mut restaurant, table, vec![]), Err(RestaurantError::EmptyOrder));
+        assert_eq!(serving::take_order(&mut restaurant, 9, vec![soup()]), Err(RestaurantError::UnknownTable(9)));
+
+        let mut empty = Restaurant::new(&[4]);
+        assert_eq!(serving::take_order(&mut empty, 1, vec![soup()]), Err(RestaurantError::TableNotSeated(1)));
+    }
+
+    #[test]
+    fn test_kitchen_cooks_in_order_and_fixes_jump_the_queue() {
+        let (mut restaurant, table) = seated();
+        let first = serving::take_order(&mut restaurant, table, vec![soup()]).unwrap();
+        let second = serving::take_order(&mut restaurant, table, vec![salad()]).unwrap();
+        let third = serving::take_order(&mut restaurant, table, vec![soup()]).unwrap();
+        assert_eq!(restaurant.kitchen_queue(), vec![first, second, third]);
+
+        assert_eq!(back_of_house::start_next_order(&mut restaurant), Some(first));
+        back_of_house::finish_order(&mut restaurant, first).unwrap();
+
+        // The first order was wrong: it goes back to the front of the queue
+        back_of_house::fix_incorrect_order(&mut restaurant, first, vec![salad()]).unwrap();
+        assert_eq!(restaurant.kitchen_queue(), vec![first, second, third]);
+        assert_eq!(restaurant.order(first).unwrap().status(), OrderStatus::Queued);
+        assert_eq!(restaurant.order(first).unwrap().remakes(), 1);
+        assert_eq!(restaurant.order(first).unwrap().dishes(), &[salad()]);
+
+        // A queued order that gets fixed also moves to the front
+        back_of_house::fix_incorrect_order(&mut restaurant, third, vec![salad()]).unwrap();
+        assert_eq!(restaurant.kitchen_queue(), vec![third, first, second]);
+        assert_eq!(back_of_house::cook_all(&mut restaurant), vec![third, first, second]);
+        assert!(restaurant.kitchen_queue().is_empty());
+
+        // Once served, an order stays served
+        serving::serve_order(&mut restaurant, first).unwrap();
+        assert_eq!(
+            back_of_house::fix_incorrect_order(&mut restaurant, first, vec![soup()]),
+            Err(RestaurantError::InvalidTransition {
+                order: first,
+                from: OrderStatus::Served,
+                to: OrderStatus::Queued
+            })
+        );
+        assert_eq!(restaurant.order(first).unwrap().remakes(), 1);
+        assert_eq!(restaurant.order(first).unwrap().dishes(), &[salad()]);
+        assert!(restaurant.kitchen_queue().is_empty());
+    }
+
+    #[test]
+    fn test_payment_waits_for_every_order() {
+        let (mut restaurant, table) = seated();
+        let first = serving::take_order(&mut restaurant, table, vec![soup()]).unwrap();
+        let second = serving::take_order(&mut restaurant, table, vec![salad()]).unwrap();
+        back_of_house::cook_all(&mut restaurant);
+        serving::serve_order(&mut restaurant, first).unwrap();
+
+        assert_eq!(
+            serving::take_payment(&mut restaurant, table, Split::Single),
+            Err(RestaurantError::OrdersOutstanding(vec![second]))
+        );
+        serving::serve_order(&mut restaurant, second).unwrap();
+
+        let bill = serving::take_payment(&mut restaurant, table, Split::Single).unwrap();
+        assert_eq!(bill.party, "Ana");
+        assert_eq!(bill.total, Money::usd(1_150));
+        assert_eq!(bill.shares, vec![Money::usd(1_150)]);
+        assert_eq!(bill.lines, vec![("Soup".to_string(), Money::usd(500)), ("Salad".to_string(), Money::usd(650))]);
+
+        // The table is free and its orders are gone
+        assert!(restaurant.tables()[0].is_free());
+        assert!(restaurant.orders_for(table).is_empty());
+        assert_eq!(
+            serving::take_payment(&mut restaurant, table, Split::Single),
+            Err(RestaurantError::TableNotSeated(table))
+        );
+    }
+
+    #[test]
+    fn test_bill_splitting() {
+        // Test cases: (split, expected shares)
+        let cases = vec![
+            (Split::Evenly(4), vec![Money::usd(563), Money::usd(563), Money::usd(562), Money::usd(562)]),
+            (Split::Evenly(2), vec![Money::usd(1_125), Money::usd(1_125)]),
+            (Split::ByOrder, vec![Money::usd(950), Money::usd(1_300)]),
+        ];
+
+        for (split, expected) in cases {
+            let (mut restaurant, table) = seated();
+            let breakfast = Dish::Breakfast(Breakfast::winter("Rye"));
+            let first = serving::take_order(&mut restaurant, table, vec![breakfast]).unwrap();
+            let second = serving::take_order(&mut restaurant, table, vec![soup(), soup()]).unwrap();
+            back_of_house::fix_incorrect_order(&mut restaurant, second, vec![salad(), salad()]).unwrap();
+            back_of_house::cook_all(&mut restaurant);
+            serving::serve_order(&mut restaurant, first).unwrap();
+            serving::serve_order(&mut restaurant, second).unwrap();
+
+            let bill = serving::take_payment(&mut restaurant, table, split.clone()).unwrap();
+            assert_eq!(bill.total, Money::usd(2_250), "{:?}", split);
+            assert_eq!(bill.shares, expected, "{:?}", split);
+        }
+
+        let (mut restaurant, table) = seated();
+        assert!(matches!(
+            serving::take_payment(&mut restaurant, table, Split::Evenly(0)),
+            Err(RestaurantError::InvalidSplit(_))
+        ));
+    }
+
+    #[test]
+    fn test_paying_frees_the_table_for_the_waitlist() {
+        let (mut restaurant, table) = seated();
+        let ben = hosting::add_to_waitlist(&mut restaurant, "Ben", 2);
+        assert_eq!(hosting::seat_at_table(&mut restaurant), None);
+
+        let order = serving::take_order(&mut restaurant, table, vec![soup()]).unwrap();
+        back_of_house::cook_all(&mut restaurant);
+        serving::serve_order(&mut restaurant, order).unwrap();
+        serving::take_payment(&mut restaurant, table, Split::Single).unwrap();
+
+        assert_eq!(hosting::seat_at_table(&mut restaurant), Some((ben, table)));
+        assert_eq!(restaurant.tables()[0].party().unwrap().name, "Ben");
+    }
+}
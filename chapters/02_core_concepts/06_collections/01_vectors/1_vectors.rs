@@ -241,6 +241,9 @@ fn filter_even_numbers(values: Vec<i32>) -> Vec<i32> {
 // and returns a tuple with the following statistics:
 // (minimum, maximum, sum, average)
 // If the vector is empty, return None.
+//
+// Once you're done, challenge_3_statistics.rs implements it with a reusable
+// statistics module (median, spread, percentiles, histograms, streaming).
 
 fn calculate_statistics(values: &Vec<i32>) -> Option<(i32, i32, i32, f64)> {
     // TODO: Implement this function.
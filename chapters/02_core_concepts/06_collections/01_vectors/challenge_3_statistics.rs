@@ -0,0 +1,97 @@
+// Vector Statistics: A Working Version of Challenge 3
+//
+// Challenge 3 in 1_vectors.rs asks for `calculate_statistics`, which
+// returns the minimum, maximum, sum and average of a vector. This file
+// implements it on top of the shared statistics module
+// (chapters/shared/statistics.rs), and shows what else the module can
+// tell you about a vector: median, modes, spread, percentiles, a histogram,
+// and streaming statistics for values that arrive one at a time.
+//
+// Try challenge 3 yourself before reading this file!
+
+#[allow(dead_code)]
+#[path = "../../../shared/statistics.rs"]
+mod statistics;
+
+use statistics::{modes, percentile, summarize, Histogram, RunningStats, StreamingQuantile, Variance};
+
+fn main() {
+    println!("Vector statistics with a reusable statistics module!");
+
+    let values = vec![5, 2, 9, 1, 7, 6, 3, 8, 4, 9];
+
+    if let Some((min, max, sum, average)) = calculate_statistics(&values) {
+        println!("min={} max={} sum={} average={}", min, max, sum, average);
+    }
+
+    if let Some(summary) = summarize(&values) {
+        println!("Summary: {}", summary);
+    }
+    println!("Modes: {:?}", modes(&values));
+    println!("90th percentile: {:?}", percentile(&values, 90.0));
+
+    if let Some(histogram) = Histogram::from_values(&values, 4) {
+        println!("Histogram:\n{}", histogram);
+    }
+
+    // Streaming: nothing is stored, yet the mean and spread are exact
+    // and the median is a close estimate
+    let mut running = RunningStats::new();
+    let mut median = StreamingQuantile::median();
+    for i in 0..10_000 {
+        let reading = (i * 37 % 101) as f64;
+        running.push(reading);
+        median.push(reading);
+    }
+    println!(
+        "Stream of {} readings: mean={:.2} sd={:.2} median~{:.1}",
+        running.count(),
+        running.mean().unwrap_or(0.0),
+        running.std_dev(Variance::Sample).unwrap_or(0.0),
+        median.estimate().unwrap_or(0.0)
+    );
+}
+
+/// Returns (minimum, maximum, sum, average), or None for an empty vector
+/// or a sum that doesn't fit in an i32
+fn calculate_statistics(values: &[i32]) -> Option<(i32, i32, i32, f64)> {
+    let summary = summarize(values)?;
+    // The summary works in f64; min and max are still whole numbers, and
+    // the sum is taken in i32 to match the challenge's signature
+    let sum = values.iter().try_fold(0i32, |acc, &v| acc.checked_add(v))?;
+    Some((summary.min as i32, summary.max as i32, sum, summary.mean))
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_statistics() {
+        // The same checks as `test_calculate_statistics` in challenge 3
+        let input = vec![5, 2, 9, 1, 7, 6, 3, 8, 4];
+        let expected = Some((1, 9, 45, 5.0));
+        assert_eq!(calculate_statistics(&input), expected);
+
+        let empty: Vec<i32> = Vec::new();
+        assert_eq!(calculate_statistics(&empty), None);
+    }
+
+    #[test]
+    fn test_negative_values() {
+        assert_eq!(calculate_statistics(&[-3, 4, -10]), Some((-10, 4, -9, -3.0)));
+        assert_eq!(calculate_statistics(&[7]), Some((7, 7, 7, 7.0)));
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        assert_eq!(calculate_statistics(&[i32::MAX, 1]), None);
+        assert_eq!(calculate_statistics(&[i32::MIN, -1]), None);
+        // A sum of exactly i32::MAX still fits
+        assert_eq!(calculate_statistics(&[i32::MAX, -1, 1]), Some((-1, i32::MAX, i32::MAX, i32::MAX as f64 / 3.0)));
+    }
+}
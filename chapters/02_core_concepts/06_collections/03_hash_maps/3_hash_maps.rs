@@ -322,6 +322,9 @@ fn merge_maps(map1: HashMap<String, i32>, map2: HashMap<String, i32>) -> HashMap
 // - Adding a grade for a student in a subject
 // - Getting the average grade for a student across all subjects
 // - Getting the average grade for a subject across all students
+//
+// Once you're done, challenge_4_gradebook.rs shows a complete grade book with
+// subject weights, letter grades, a class ranking and CSV import/export.
 
 struct GradeBook {
     // TODO: Define the structure to store student grades
@@ -0,0 +1,810 @@
+// Grade Book: A Working Version of Challenge 4
+//
+// Challenge 4 in 3_hash_maps.rs asks for a `GradeBook` with per-student and
+// per-subject averages. This file builds a complete one on top of nested
+// hash maps (student -> subject -> grades):
+//
+// - Several grades per subject (homework, exams...), averaged per subject
+// - Per-subject weights for the overall student average
+// - A configurable letter-grade scale
+// - A class ranking, with ties sharing a rank
+// - CSV import and export; malformed rows are reported with their line number
+// - Subject reports (spread, quartiles, histogram, letter counts) built on
+//   the shared statistics module (chapters/shared/statistics.rs)
+//
+// Try challenge 4 yourself before reading this file!
+
+#[allow(dead_code)]
+#[path = "../../../shared/statistics.rs"]
+mod statistics;
+
+use statistics::{mean, quartiles, summarize, Histogram, Summary};
+use std::collections::HashMap;
+use std::fmt;
+
+fn main() {
+    println!("A complete grade book built on HashMaps!");
+
+    let csv = "\
+student,subject,grade
+Alice,Math,92
+Alice,Math,88
+Alice,Science,85
+Bob,Math,78
+Bob,Science,91
+Charlie,Math,65
+Charlie,Science,72.5
+";
+
+    let mut book = GradeBook::new();
+    let imported = book.import_csv(csv).unwrap();
+    println!("Imported {} grades", imported);
+
+    // Math counts twice as much as Science
+    book.set_subject_weight("Math", 2.0).unwrap();
+
+    println!("\nRanking:");
+    for entry in book.ranking() {
+        println!("  {}. {:<8} {:>6.2} {}", entry.rank, entry.student, entry.average, entry.letter);
+    }
+
+    for report in book.subject_reports() {
+        println!("\n{}", report);
+    }
+
+    // Broken rows are reported, and nothing is imported
+    let broken = "student,subject,grade\nDana,Math,abc\nDana,,90\nDana,Art,120\n";
+    if let Err(errors) = book.import_csv(broken) {
+        println!("\nImport failed:\n{}", errors);
+    }
+
+    println!("\nExported:\n{}", book.export_csv());
+}
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+/// Why a grade, weight or scale was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradeError {
+    InvalidGrade(f64),
+    InvalidWeight(f64),
+    EmptyName,
+    /// Names can't contain control characters such as line breaks
+    InvalidName(String),
+    InvalidScale(String),
+}
+
+impl fmt::Display for GradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GradeError::InvalidGrade(grade) => write!(f, "grade must be between 0 and 100, got {}", grade),
+            GradeError::InvalidWeight(weight) => write!(f, "weight must be positive, got {}", weight),
+            GradeError::EmptyName => write!(f, "student and subject names cannot be empty"),
+            GradeError::InvalidName(name) => write!(f, "names cannot contain control characters, got {:?}", name),
+            GradeError::InvalidScale(message) => write!(f, "invalid letter scale: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GradeError {}
+
+/// A malformed CSV row
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvError {
+    /// 1-based line number in the input
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Every malformed row of a CSV import
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvErrors(pub Vec<CsvError>);
+
+impl fmt::Display for CsvErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for CsvErrors {}
+
+//------------------------------------------------------
+// LETTER SCALE
+//------------------------------------------------------
+
+/// Maps an average to a letter: the first threshold the average reaches wins
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetterScale {
+    // (minimum average, letter), highest minimum first
+    thresholds: Vec<(f64, String)>,
+    lowest: String,
+}
+
+impl LetterScale {
+    /// A scale from (minimum, letter) pairs, plus the letter for anything below them all
+    pub fn new(thresholds: &[(f64, &str)], lowest: &str) -> Result<LetterScale, GradeError> {
+        let mut sorted: Vec<(f64, String)> = Vec::new();
+        for (minimum, letter) in thresholds {
+            if !minimum.is_finite() || !(0.0..=100.0).contains(minimum) {
+                return Err(GradeError::InvalidScale(format!("threshold {} is out of range", minimum)));
+            }
+            if letter.is_empty() {
+                return Err(GradeError::InvalidScale("empty letter".to_string()));
+            }
+            if sorted.iter().any(|(m, _)| m == minimum) {
+                return Err(GradeError::InvalidScale(format!("threshold {} appears twice", minimum)));
+            }
+            sorted.push((*minimum, letter.to_string()));
+        }
+        sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(LetterScale { thresholds: sorted, lowest: lowest.to_string() })
+    }
+
+    /// A (90), B (80), C (70), D (60), F
+    pub fn standard() -> LetterScale {
+        LetterScale::new(&[(90.0, "A"), (80.0, "B"), (70.0, "C"), (60.0, "D")], "F").unwrap()
+    }
+
+    /// Like `standard`, with + and - in the top and bottom three points of each band
+    pub fn plus_minus() -> LetterScale {
+        let thresholds = [
+            (97.0, "A+"), (93.0, "A"), (90.0, "A-"),
+            (87.0, "B+"), (83.0, "B"), (80.0, "B-"),
+            (77.0, "C+"), (73.0, "C"), (70.0, "C-"),
+            (67.0, "D+"), (63.0, "D"), (60.0, "D-"),
+        ];
+        LetterScale::new(&thresholds, "F").unwrap()
+    }
+
+    /// Every letter, best first
+    pub fn letters(&self) -> Vec<&str> {
+        let mut letters: Vec<&str> = self.thresholds.iter().map(|(_, letter)| letter.as_str()).collect();
+        letters.push(&self.lowest);
+        letters
+    }
+
+    pub fn letter_for(&self, average: f64) -> &str {
+        self.thresholds
+            .iter()
+            .find(|(minimum, _)| average >= *minimum)
+            .map(|(_, letter)| letter.as_str())
+            .unwrap_or(&self.lowest)
+    }
+}
+
+impl Default for LetterScale {
+    fn default() -> Self {
+        LetterScale::standard()
+    }
+}
+
+//------------------------------------------------------
+// GRADE BOOK
+//------------------------------------------------------
+
+/// One row of the class ranking
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankEntry {
+    /// Tied students share a rank, and the next rank is skipped (1, 1, 3)
+    pub rank: usize,
+    pub student: String,
+    pub average: f64,
+    pub letter: String,
+}
+
+/// How a class did in one subject, based on each student's subject average
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubjectReport {
+    pub subject: String,
+    pub summary: Summary,
+    /// 25th, 50th and 75th percentiles
+    pub quartiles: (f64, f64, f64),
+    /// Ten bins of ten points from 0 to 100
+    pub histogram: Histogram,
+    /// How many students got each letter, best letter first
+    pub letters: Vec<(String, usize)>,
+}
+
+impl fmt::Display for SubjectReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.subject, self.summary)?;
+        let (q1, q2, q3) = self.quartiles;
+        writeln!(f, "quartiles: {:.2} / {:.2} / {:.2}", q1, q2, q3)?;
+        let letters: Vec<String> = self.letters.iter().map(|(letter, count)| format!("{}={}", letter, count)).collect();
+        writeln!(f, "letters: {}", letters.join(" "))?;
+        write!(f, "{}", self.histogram)
+    }
+}
+
+/// Grades per student and subject
+#[derive(Debug, Default)]
+pub struct GradeBook {
+    // student -> subject -> grades, in the order they were added
+    grades: HashMap<String, HashMap<String, Vec<f64>>>,
+    // subject -> weight; subjects not listed weigh 1.0
+    weights: HashMap<String, f64>,
+    scale: LetterScale,
+}
+
+impl GradeBook {
+    pub fn new() -> GradeBook {
+        GradeBook::default()
+    }
+
+    pub fn with_scale(scale: LetterScale) -> GradeBook {
+        GradeBook { scale, ..GradeBook::default() }
+    }
+
+    pub fn set_scale(&mut self, scale: LetterScale) {
+        self.scale = scale;
+    }
+
+    /// Adds a grade between 0 and 100
+    pub fn add_grade(&mut self, student: &str, subject: &str, grade: f64) -> Result<(), GradeError> {
+        let (student, subject) = (student.trim(), subject.trim());
+        if student.is_empty() || subject.is_empty() {
+            return Err(GradeError::EmptyName);
+        }
+        if let Some(name) = [student, subject].into_iter().find(|name| has_control_chars(name)) {
+            return Err(GradeError::InvalidName(name.to_string()));
+        }
+        if !grade.is_finite() || !(0.0..=100.0).contains(&grade) {
+            return Err(GradeError::InvalidGrade(grade));
+        }
+        self.grades
+            .entry(student.to_string())
+            .or_default()
+            .entry(subject.to_string())
+            .or_default()
+            .push(grade);
+        Ok(())
+    }
+
+    /// How much a subject counts in a student's overall average (default 1.0)
+    pub fn set_subject_weight(&mut self, subject: &str, weight: f64) -> Result<(), GradeError> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(GradeError::InvalidWeight(weight));
+        }
+        self.weights.insert(subject.trim().to_string(), weight);
+        Ok(())
+    }
+
+    pub fn subject_weight(&self, subject: &str) -> f64 {
+        self.weights.get(subject).copied().unwrap_or(1.0)
+    }
+
+    /// All students, sorted by name
+    pub fn students(&self) -> Vec<String> {
+        let mut students: Vec<String> = self.grades.keys().cloned().collect();
+        students.sort();
+        students
+    }
+
+    /// All subjects with at least one grade, sorted by name
+    pub fn subjects(&self) -> Vec<String> {
+        let mut subjects: Vec<String> = self
+            .grades
+            .values()
+            .flat_map(|by_subject| by_subject.keys().cloned())
+            .collect();
+        subjects.sort();
+        subjects.dedup();
+        subjects
+    }
+
+    pub fn grades(&self, student: &str, subject: &str) -> Option<&[f64]> {
+        self.grades.get(student)?.get(subject).map(|grades| grades.as_slice())
+    }
+
+    /// The mean of a student's grades in one subject
+    pub fn get_student_subject_average(&self, student: &str, subject: &str) -> Option<f64> {
+        mean(self.grades(student, subject)?)
+    }
+
+    /// The weighted mean of the student's subject averages
+    pub fn get_student_average(&self, student: &str) -> Option<f64> {
+        let by_subject = self.grades.get(student)?;
+        // Subject order, so that students with the same grades get exactly
+        // the same average whatever order their hash map iterates in
+        let mut subjects: Vec<(&String, &Vec<f64>)> = by_subject.iter().collect();
+        subjects.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for (subject, grades) in subjects {
+            if let Some(average) = mean(grades) {
+                let weight = self.subject_weight(subject);
+                weighted_sum += average * weight;
+                total_weight += weight;
+            }
+        }
+        if total_weight == 0.0 {
+            None
+        } else {
+            Some(weighted_sum / total_weight)
+        }
+    }
+
+    /// The mean of every student's average in the subject, so a student
+    /// with many grades does not outweigh the others
+    pub fn get_subject_average(&self, subject: &str) -> Option<f64> {
+        mean(&self.subject_averages(subject))
+    }
+
+    // Each student's average in the subject, in student-name order so that
+    // floating-point sums do not depend on hash map order
+    fn subject_averages(&self, subject: &str) -> Vec<f64> {
+        let mut averages: Vec<(&String, f64)> = self
+            .grades
+            .iter()
+            .filter_map(|(student, by_subject)| Some((student, mean(by_subject.get(subject)?)?)))
+            .collect();
+        averages.sort_by(|a, b| a.0.cmp(b.0));
+        averages.into_iter().map(|(_, average)| average).collect()
+    }
+
+    /// Statistics for one subject; None if nobody has a grade in it
+    pub fn subject_report(&self, subject: &str) -> Option<SubjectReport> {
+        let averages = self.subject_averages(subject);
+        let summary = summarize(&averages)?;
+        let quartiles = quartiles(&averages)?;
+
+        let mut histogram = Histogram::with_range(0.0, 100.0, 10)?;
+        let mut letter_counts: HashMap<&str, usize> = HashMap::new();
+        for &average in &averages {
+            histogram.add(average);
+            *letter_counts.entry(self.scale.letter_for(average)).or_insert(0) += 1;
+        }
+        // Follow the scale's order, from the best letter down
+        let letters = self
+            .scale
+            .letters()
+            .into_iter()
+            .filter_map(|letter| Some((letter.to_string(), *letter_counts.get(letter)?)))
+            .collect();
+
+        Some(SubjectReport { subject: subject.to_string(), summary, quartiles, histogram, letters })
+    }
+
+    /// A report for every subject, sorted by subject name
+    pub fn subject_reports(&self) -> Vec<SubjectReport> {
+        self.subjects().iter().filter_map(|subject| self.subject_report(subject)).collect()
+    }
+
+    pub fn get_student_letter(&self, student: &str) -> Option<&str> {
+        self.get_student_average(student).map(|average| self.scale.letter_for(average))
+    }
+
+    /// Students from best to worst average; equal averages share a rank
+    pub fn ranking(&self) -> Vec<RankEntry> {
+        let mut averages: Vec<(String, f64)> = self
+            .students()
+            .into_iter()
+            .filter_map(|student| {
+                let average = self.get_student_average(&student)?;
+                Some((student, average))
+            })
+            .collect();
+        // Best first; `students()` is sorted, and the sort is stable, so ties stay alphabetical
+        averages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let mut ranking: Vec<RankEntry> = Vec::new();
+        for (position, (student, average)) in averages.into_iter().enumerate() {
+            let rank = match ranking.last() {
+                Some(previous) if previous.average == average => previous.rank,
+                _ => position + 1,
+            };
+            let letter = self.scale.letter_for(average).to_string();
+            ranking.push(RankEntry { rank, student, average, letter });
+        }
+        ranking
+    }
+
+    //------------------------------------------------------
+    // CSV
+    //------------------------------------------------------
+
+    /// Writes every grade as `student,subject,grade`, sorted by student and
+    /// subject, with a header row. Names containing commas or quotes are quoted.
+    pub fn export_csv(&self) -> String {
+        let mut out = format!("{}\n", CSV_HEADER);
+        for student in self.students() {
+            let by_subject = &self.grades[&student];
+            let mut subjects: Vec<&String> = by_subject.keys().collect();
+            subjects.sort();
+            for subject in subjects {
+                for grade in &by_subject[subject] {
+                    out.push_str(&format!("{},{},{}\n", csv_field(&student), csv_field(subject), grade));
+                }
+            }
+        }
+        out
+    }
+
+    /// Adds the grades from CSV text with a `student,subject,grade` header.
+    /// Either every row is imported, or none is and all malformed rows are
+    /// returned with their line numbers. Returns the number of grades added.
+    pub fn import_csv(&mut self, text: &str) -> Result<usize, CsvErrors> {
+        let mut errors = Vec::new();
+        let mut rows = Vec::new();
+
+        let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line));
+        let header = lines.next().map(|(_, header)| header.trim());
+        if header != Some(CSV_HEADER) {
+            errors.push(CsvError { line: 1, message: format!("expected header '{}'", CSV_HEADER) });
+        }
+
+        for (line, text) in lines {
+            if text.trim().is_empty() {
+                continue;
+            }
+            match parse_grade_row(text) {
+                Ok(row) => rows.push(row),
+                Err(message) => errors.push(CsvError { line, message }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(CsvErrors(errors));
+        }
+        let count = rows.len();
+        for (student, subject, grade) in rows {
+            // Rows were validated above
+            let _ = self.add_grade(&student, &subject, grade);
+        }
+        Ok(count)
+    }
+}
+
+const CSV_HEADER: &str = "student,subject,grade";
+
+// Line breaks would split a row in two, so names may not contain them
+fn has_control_chars(name: &str) -> bool {
+    name.contains(char::is_control)
+}
+
+// Quotes a field if it contains a comma or a quote
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Splits one CSV line into fields. Quoted fields may contain commas and
+// doubled quotes ("").
+fn parse_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut field = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err("unterminated quoted field".to_string()),
+                }
+            }
+            match chars.next() {
+                None => {
+                    fields.push(field);
+                    return Ok(fields);
+                }
+                Some(',') => fields.push(field),
+                Some(c) => return Err(format!("unexpected '{}' after a quoted field", c)),
+            }
+        } else {
+            loop {
+                match chars.next() {
+                    None => {
+                        fields.push(field);
+                        return Ok(fields);
+                    }
+                    Some(',') => break,
+                    Some('"') => return Err("quote inside an unquoted field".to_string()),
+                    Some(c) => field.push(c),
+                }
+            }
+            fields.push(field);
+        }
+    }
+}
+
+fn parse_grade_row(line: &str) -> Result<(String, String, f64), String> {
+    let fields = parse_csv_line(line)?;
+    let [student, subject, grade] = fields.as_slice() else {
+        return Err(format!("expected 3 fields, found {}", fields.len()));
+    };
+    let (student, subject) = (student.trim(), subject.trim());
+    if student.is_empty() {
+        return Err("missing student".to_string());
+    }
+    if subject.is_empty() {
+        return Err("missing subject".to_string());
+    }
+    if has_control_chars(student) || has_control_chars(subject) {
+        return Err("names cannot contain control characters".to_string());
+    }
+    let grade: f64 = grade
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid grade '{}'", grade.trim()))?;
+    if !grade.is_finite() || !(0.0..=100.0).contains(&grade) {
+        return Err(format!("grade {} is not between 0 and 100", grade));
+    }
+    Ok((student.to_string(), subject.to_string(), grade))
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_book() -> GradeBook {
+        let mut book = GradeBook::new();
+        book.add_grade("Alice", "Math", 90.0).unwrap();
+        book.add_grade("Alice", "Science", 85.0).unwrap();
+        book.add_grade("Bob", "Math", 80.0).unwrap();
+        book.add_grade("Bob", "Science", 90.0).unwrap();
+        book
+    }
+
+    #[test]
+    fn test_challenge_scenario() {
+        // The same checks as `test_grade_book` in challenge 4
+        let book = sample_book();
+
+        assert_eq!(book.get_student_average("Alice"), Some(87.5));
+        assert_eq!(book.get_student_average("Bob"), Some(85.0));
+        assert_eq!(book.get_student_average("Charlie"), None);
+
+        assert_eq!(book.get_subject_average("Math"), Some(85.0));
+        assert_eq!(book.get_subject_average("Science"), Some(87.5));
+        assert_eq!(book.get_subject_average("History"), None);
+    }
+
+    #[test]
+    fn test_several_grades_per_subject() {
+        let mut book = GradeBook::new();
+        book.add_grade("Alice", "Math", 100.0).unwrap();
+        book.add_grade("Alice", "Math", 80.0).unwrap();
+        book.add_grade("Alice", "Math", 90.0).unwrap();
+        book.add_grade("Bob", "Math", 70.0).unwrap();
+
+        assert_eq!(book.grades("Alice", "Math"), Some(&[100.0, 80.0, 90.0][..]));
+        assert_eq!(book.get_student_subject_average("Alice", "Math"), Some(90.0));
+        // Alice's three grades count as one subject average next to Bob's
+        assert_eq!(book.get_subject_average("Math"), Some(80.0));
+    }
+
+    #[test]
+    fn test_weighted_student_average() {
+        let mut book = sample_book();
+        book.set_subject_weight("Math", 3.0).unwrap();
+
+        // Alice: (90 * 3 + 85) / 4, Bob: (80 * 3 + 90) / 4
+        assert_eq!(book.get_student_average("Alice"), Some(88.75));
+        assert_eq!(book.get_student_average("Bob"), Some(82.5));
+        // Subject averages are not affected by weights
+        assert_eq!(book.get_subject_average("Math"), Some(85.0));
+
+        assert_eq!(book.set_subject_weight("Art", 0.0), Err(GradeError::InvalidWeight(0.0)));
+        assert!(book.set_subject_weight("Art", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_invalid_grades() {
+        let mut book = GradeBook::new();
+        assert_eq!(book.add_grade("Alice", "Math", 101.0), Err(GradeError::InvalidGrade(101.0)));
+        assert_eq!(book.add_grade("Alice", "Math", -1.0), Err(GradeError::InvalidGrade(-1.0)));
+        assert!(book.add_grade("Alice", "Math", f64::NAN).is_err());
+        assert_eq!(book.add_grade(" ", "Math", 50.0), Err(GradeError::EmptyName));
+        // A line break would not survive a CSV round trip
+        assert_eq!(
+            book.add_grade("Ann\nBob", "Math", 50.0),
+            Err(GradeError::InvalidName("Ann\nBob".to_string()))
+        );
+        assert_eq!(book.add_grade("Alice", "Art\t1", 50.0), Err(GradeError::InvalidName("Art\t1".to_string())));
+        assert!(book.students().is_empty());
+    }
+
+    #[test]
+    fn test_letter_scales() {
+        let standard = LetterScale::standard();
+        // Test cases: (average, standard letter, plus/minus letter)
+        let test_cases = vec![
+            (100.0, "A", "A+"),
+            (95.0, "A", "A"),
+            (90.0, "A", "A-"),
+            (89.99, "B", "B+"),
+            (84.0, "B", "B"),
+            (80.0, "B", "B-"),
+            (72.5, "C", "C-"),
+            (60.0, "D", "D-"),
+            (59.9, "F", "F"),
+            (0.0, "F", "F"),
+        ];
+        let plus_minus = LetterScale::plus_minus();
+        for (average, letter, fine_letter) in test_cases {
+            assert_eq!(standard.letter_for(average), letter, "standard letter for {}", average);
+            assert_eq!(plus_minus.letter_for(average), fine_letter, "plus/minus letter for {}", average);
+        }
+
+        let pass_fail = LetterScale::new(&[(50.0, "Pass")], "Fail").unwrap();
+        assert_eq!(pass_fail.letter_for(50.0), "Pass");
+        assert_eq!(pass_fail.letter_for(49.0), "Fail");
+
+        assert!(LetterScale::new(&[(50.0, "P"), (50.0, "Q")], "F").is_err());
+        assert!(LetterScale::new(&[(150.0, "P")], "F").is_err());
+    }
+
+    #[test]
+    fn test_student_letter_uses_book_scale() {
+        let mut book = sample_book();
+        assert_eq!(book.get_student_letter("Alice"), Some("B"));
+
+        book.set_scale(LetterScale::plus_minus());
+        assert_eq!(book.get_student_letter("Alice"), Some("B+"));
+        assert_eq!(book.get_student_letter("Nobody"), None);
+    }
+
+    #[test]
+    fn test_ranking_with_ties() {
+        let mut book = GradeBook::new();
+        book.add_grade("Dave", "Math", 70.0).unwrap();
+        book.add_grade("Carol", "Math", 95.0).unwrap();
+        book.add_grade("Bob", "Math", 88.0).unwrap();
+        book.add_grade("Alice", "Math", 88.0).unwrap();
+
+        let ranking: Vec<(usize, String, String)> = book
+            .ranking()
+            .into_iter()
+            .map(|entry| (entry.rank, entry.student, entry.letter))
+            .collect();
+        assert_eq!(
+            ranking,
+            vec![
+                (1, "Carol".to_string(), "A".to_string()),
+                (2, "Alice".to_string(), "B".to_string()),
+                (2, "Bob".to_string(), "B".to_string()),
+                (4, "Dave".to_string(), "C".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical_grades_tie_exactly() {
+        // These four averages add up differently in different orders; each
+        // student's subjects sit in their own hash map, so try many books
+        for _ in 0..50 {
+            let mut book = GradeBook::new();
+            for student in ["Alice", "Bob"] {
+                for (subject, grade) in [("Art", 92.3), ("History", 85.1), ("Math", 77.7), ("Science", 66.6)] {
+                    book.add_grade(student, subject, grade).unwrap();
+                }
+            }
+
+            assert_eq!(book.get_student_average("Alice"), book.get_student_average("Bob"));
+            let ranks: Vec<usize> = book.ranking().iter().map(|entry| entry.rank).collect();
+            assert_eq!(ranks, vec![1, 1]);
+        }
+    }
+
+    #[test]
+    fn test_subject_report() {
+        let mut book = GradeBook::new();
+        for (student, grade) in [("Alice", 95.0), ("Bob", 82.0), ("Carol", 88.0), ("Dave", 58.0), ("Erin", 82.0)] {
+            book.add_grade(student, "Math", grade).unwrap();
+        }
+        // Frank's two grades count as one average of 75
+        book.add_grade("Frank", "Math", 70.0).unwrap();
+        book.add_grade("Frank", "Math", 80.0).unwrap();
+        book.add_grade("Alice", "Art", 99.0).unwrap();
+
+        let report = book.subject_report("Math").unwrap();
+        assert_eq!(report.summary.count, 6);
+        assert_eq!((report.summary.min, report.summary.max), (58.0, 95.0));
+        assert_eq!(report.summary.mean, 80.0);
+        assert_eq!(report.summary.median, 82.0);
+        assert_eq!(report.quartiles, (76.75, 82.0, 86.5));
+        assert_eq!(report.histogram.counts(), &[0, 0, 0, 0, 0, 1, 0, 1, 3, 1]);
+
+        let letters: Vec<(&str, usize)> = report.letters.iter().map(|(l, c)| (l.as_str(), *c)).collect();
+        assert_eq!(letters, vec![("A", 1), ("B", 3), ("C", 1), ("F", 1)]);
+
+        assert_eq!(book.get_subject_average("Math"), Some(report.summary.mean));
+        assert!(book.subject_report("History").is_none());
+        let subjects: Vec<String> = book.subject_reports().into_iter().map(|r| r.subject).collect();
+        assert_eq!(subjects, vec!["Art", "Math"]);
+    }
+
+    #[test]
+    fn test_subject_report_display() {
+        let book = sample_book();
+        let report = book.subject_report("Math").unwrap().to_string();
+        assert!(report.starts_with("Math: n=2 min=80.00 max=90.00 mean=85.00 median=85.00 sd=5.00\n"));
+        assert!(report.contains("quartiles: 82.50 / 85.00 / 87.50\n"));
+        assert!(report.contains("letters: A=1 B=1\n"));
+        assert!(report.ends_with("[ 90.0, 100.0]   1 #"));
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let mut book = sample_book();
+        book.add_grade("O'Brien, Pat", "Art \"studio\"", 77.5).unwrap();
+        book.add_grade("Alice", "Math", 70.0).unwrap();
+
+        let csv = book.export_csv();
+        assert_eq!(
+            csv,
+            "student,subject,grade\n\
+             Alice,Math,90\n\
+             Alice,Math,70\n\
+             Alice,Science,85\n\
+             Bob,Math,80\n\
+             Bob,Science,90\n\
+             \"O'Brien, Pat\",\"Art \"\"studio\"\"\",77.5\n"
+        );
+
+        let mut copy = GradeBook::new();
+        assert_eq!(copy.import_csv(&csv), Ok(6));
+        assert_eq!(copy.export_csv(), csv);
+        assert_eq!(copy.get_student_average("O'Brien, Pat"), Some(77.5));
+    }
+
+    #[test]
+    fn test_csv_errors_report_line_numbers() {
+        let csv = "student,subject,grade\n\
+                   Alice,Math,90\n\
+                   Bob,Math\n\
+                   \n\
+                   Carol,Math,ninety\n\
+                   Dave,,80\n\
+                   Erin,Math,120\n\
+                   \"Frank,Math,80\n\
+                   Gina,Math,85\n\
+                   \"Hal\tHu\",Math,85\n";
+
+        let mut book = GradeBook::new();
+        let errors = book.import_csv(csv).unwrap_err();
+        let lines: Vec<usize> = errors.0.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 5, 6, 7, 8, 10]);
+        assert_eq!(errors.0[0].to_string(), "line 3: expected 3 fields, found 2");
+        assert_eq!(errors.0[1].message, "invalid grade 'ninety'");
+        assert_eq!(errors.0[2].message, "missing subject");
+        assert_eq!(errors.0[3].message, "grade 120 is not between 0 and 100");
+        assert_eq!(errors.0[4].message, "unterminated quoted field");
+        assert_eq!(errors.0[5].message, "names cannot contain control characters");
+
+        // Nothing was imported, not even the good rows
+        assert!(book.students().is_empty());
+    }
+
+    #[test]
+    fn test_csv_requires_header() {
+        let mut book = GradeBook::new();
+        let errors = book.import_csv("Alice,Math,90\n").unwrap_err();
+        assert_eq!(errors.0[0].line, 1);
+        assert!(book.import_csv("").is_err());
+        assert_eq!(book.import_csv("student,subject,grade\n"), Ok(0));
+    }
+}
@@ -0,0 +1,127 @@
+// Calculator REPL
+//
+// An interactive calculator built on the shared calculator module
+// (chapters/shared/calculator.rs). Type an expression and press Enter:
+//
+//   > 2 * (3 + 4)
+//   14
+//   > 10 / (5 - 5)
+//     10 / (5 - 5)
+//        ^
+//   error: division by zero at position 3
+//
+// Every way an expression can fail is a `MathError` variant, so the loop
+// below never panics on bad input: it matches on the `Result`, prints the
+// error with a marker under the position it carries, and reads the next
+// line. Compare that with challenges 2 and 3 in 0_error_handling.rs, where
+// `MathError` has just one variant.
+//
+// Commands:
+//   :int     evaluate with 64-bit integers (the default)
+//   :float   evaluate with floating point numbers
+//   :tree    show how the last expression was grouped
+//   :help    show this list
+//   :quit    leave (so does Ctrl-D)
+//
+// Build and run it from this folder with:
+//   rustc --edition 2021 calculator_repl.rs && ./calculator_repl
+
+use std::io::{self, BufRead, Write};
+
+#[allow(dead_code)]
+#[path = "../../shared/calculator.rs"]
+mod calculator;
+
+use calculator::{Expr, MathError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Integer,
+    Float,
+}
+
+const HELP: &str = "\
+Operators: + - * / % ^ and parentheses, e.g. 2 ^ 10 - (3 + 4) * -2
+Commands:
+  :int     evaluate with 64-bit integers (the default)
+  :float   evaluate with floating point numbers
+  :tree    show how the last expression was grouped
+  :help    show this list
+  :quit    leave (so does Ctrl-D)";
+
+fn evaluate(expr: &Expr, mode: Mode) -> Result<String, MathError> {
+    match mode {
+        Mode::Integer => expr.eval::<i64>().map(|value| value.to_string()),
+        Mode::Float => expr.eval::<f64>().map(|value| value.to_string()),
+    }
+}
+
+// Prints the input again with a ^ under the character the error is about
+fn report(input: &str, error: &MathError) {
+    if let Some(position) = error.position() {
+        println!("  {}", input);
+        println!("  {}^", " ".repeat(position));
+    }
+    println!("error: {}", error);
+}
+
+fn main() {
+    println!("Calculator. Type an expression, or :help for commands.");
+
+    let mut mode = Mode::Integer;
+    let mut last: Option<Expr> = None;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("> ");
+        // A prompt that doesn't show up is a bug, not a reason to stop
+        io::stdout().flush().ok();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(error)) => {
+                eprintln!("could not read input: {}", error);
+                break;
+            }
+            // End of input
+            None => {
+                println!();
+                break;
+            }
+        };
+        let input = line.trim_end();
+
+        match input.trim() {
+            "" => continue,
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":int" => {
+                mode = Mode::Integer;
+                println!("integer mode");
+            }
+            ":float" => {
+                mode = Mode::Float;
+                println!("float mode");
+            }
+            ":tree" => match &last {
+                Some(expr) => println!("{}", expr),
+                None => println!("no expression yet"),
+            },
+            command if command.starts_with(':') => {
+                println!("unknown command '{}'; try :help", command);
+            }
+            _ => {
+                let result = calculator::parse(input).and_then(|expr| {
+                    let value = evaluate(&expr, mode);
+                    last = Some(expr);
+                    value
+                });
+                match result {
+                    Ok(value) => println!("{}", value),
+                    Err(error) => report(input, &error),
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,13 @@
 
 use std::fmt::{Display, Debug};
 
+// Product prices use the course-wide fixed-point `Money` type
+#[allow(dead_code)]
+#[path = "../../../shared/money.rs"]
+mod money;
+
+use money::Money;
+
 fn main() {
     println!("Combining Generics, Traits, and Lifetimes in Rust!");
     
@@ -74,11 +81,11 @@ fn main() {
     let product = Product {
         id: 101,
         name: String::from("Rust Book"),
-        price: 29.99,
+        price: Money::usd(2999),
     };
     
     // Create repositories for users and products
-    let user_repo = InMemoryRepository::<User>::new();
+    let mut user_repo = InMemoryRepository::<User>::new();
     let mut product_repo = InMemoryRepository::<Product>::new();
     
     // Save items to repositories
@@ -101,11 +108,11 @@ fn main() {
     
     // Update a product
     if let Some(mut product) = product_repo.find_by_id(product_id) {
-        product.price = 24.99;
+        product.price = Money::usd(2499);
         product_repo.save(product_id, product);
         
         if let Some(updated_product) = product_repo.find_by_id(product_id) {
-            println!("Updated product price: ${:.2}", updated_product.price);
+            println!("Updated product price: {}", updated_product.price);
         }
     }
     
@@ -119,21 +126,26 @@ fn main() {
         base_url: String::from("https://api.example.com"),
         timeout: 30,
         retry_count: 3,
+        requests_per_second: 5,
+        burst_size: 10,
+        failure_threshold: 3,
+        reset_timeout_ms: 5_000,
     };
     
     // Create an API client with a reference to the configuration
-    let client = ApiClient::new(&config);
+    // (mutable, because the rate limiter and circuit breaker keep state)
+    let mut client = ApiClient::new(&config);
     
     // Simulate fetching a user
     if let Ok(fetch_result) = client.fetch::<User>("/users/1") {
         println!("Fetched user: {}", fetch_result.data);
-        println!("Response metadata: {:?}", fetch_result.metadata);
+        println!("Response metadata: {}", fetch_result.metadata);
     }
     
     // Simulate fetching a product
     if let Ok(fetch_result) = client.fetch::<Product>("/products/101") {
         println!("Fetched product: {}", fetch_result.data);
-        println!("Response metadata: {:?}", fetch_result.metadata);
+        println!("Response metadata: {}", fetch_result.metadata);
     }
     
     // Example of error handling with generic types
@@ -141,6 +153,9 @@ fn main() {
         Ok(result) => println!("Successfully fetched: {}", result.data),
         Err(error) => println!("Error: {}", error),
     }
+
+    // A "not found" answer is not a backend failure, so the circuit stays closed
+    println!("Circuit state: {:?}", client.circuit_state());
 }
 
 //------------------------------------------------------
@@ -247,7 +262,7 @@ impl Display for User {
 struct Product {
     id: usize,
     name: String,
-    price: f64,
+    price: Money,
 }
 
 // Implement Entity trait for Product
@@ -260,7 +275,7 @@ impl Entity for Product {
 // Implement Display for Product
 impl Display for Product {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Product {{ id: {}, name: {}, price: ${:.2} }}", self.id, self.name, self.price)
+        write!(f, "Product {{ id: {}, name: {}, price: {} }}", self.id, self.name, self.price)
     }
 }
 
@@ -309,11 +324,249 @@ struct ApiConfig {
     base_url: String,
     timeout: u32,
     retry_count: u32,
+    // Token bucket: sustained rate and the size of a burst
+    requests_per_second: u32,
+    burst_size: u32,
+    // Circuit breaker: consecutive failures before tripping, and how long
+    // to stay open before letting a probe request through
+    failure_threshold: u32,
+    reset_timeout_ms: u64,
+}
+
+// A source of the current time in milliseconds.
+// Taking the clock as a trait object lets tests control time precisely.
+trait Clock {
+    fn now_ms(&self) -> u64;
+}
+
+// Clock backed by the operating system
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+// What the transport layer hands back for a completed request
+#[derive(Debug, Clone, PartialEq)]
+struct RawResponse {
+    status_code: u16,
+    response_time_ms: u64,
+    cache_hit: bool,
+}
+
+// The layer that actually talks to the backend.
+// An `Err` means the request never completed (connection refused, timeout...).
+trait Transport {
+    fn send(&self, url: &str) -> Result<RawResponse, String>;
+}
+
+// Transport that simulates a healthy backend serving a few known endpoints
+struct SimulatedTransport;
+
+impl Transport for SimulatedTransport {
+    fn send(&self, url: &str) -> Result<RawResponse, String> {
+        let (status_code, response_time_ms, cache_hit) = if url.ends_with("/users/1") {
+            (200, 120, false)
+        } else if url.ends_with("/products/101") {
+            (200, 85, true)
+        } else {
+            (404, 40, false)
+        };
+
+        Ok(RawResponse { status_code, response_time_ms, cache_hit })
+    }
+}
+
+//------------------------------------------------------
+// RATE LIMITER
+//------------------------------------------------------
+
+// A token bucket rate limiter.
+//
+// The bucket holds up to `capacity` tokens and refills at `refill_per_second`.
+// Every request takes one token; when the bucket is empty the request is rejected.
+// Tokens are tracked in thousandths so refills stay exact with integer math.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: u64,
+    refill_per_second: u64,
+    milli_tokens: u64,
+    last_refill_ms: u64,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, refill_per_second: u32, now_ms: u64) -> Self {
+        TokenBucket {
+            capacity: capacity as u64,
+            refill_per_second: refill_per_second as u64,
+            milli_tokens: capacity as u64 * 1000,
+            last_refill_ms: now_ms,
+        }
+    }
+
+    fn refill(&mut self, now_ms: u64) {
+        let elapsed = now_ms.saturating_sub(self.last_refill_ms);
+        // One token per second is exactly one milli-token per millisecond
+        let gained = elapsed.saturating_mul(self.refill_per_second);
+        self.milli_tokens = (self.milli_tokens.saturating_add(gained)).min(self.capacity * 1000);
+        self.last_refill_ms = now_ms;
+    }
+
+    // Take a token, or return how many milliseconds until one is available
+    fn try_acquire(&mut self, now_ms: u64) -> Result<(), u64> {
+        self.refill(now_ms);
+
+        if self.milli_tokens >= 1000 {
+            self.milli_tokens -= 1000;
+            return Ok(());
+        }
+
+        if self.refill_per_second == 0 {
+            return Err(u64::MAX);
+        }
+        let missing = 1000 - self.milli_tokens;
+        Err(missing.div_ceil(self.refill_per_second))
+    }
+
+    fn available(&self) -> u64 {
+        self.milli_tokens / 1000
+    }
+}
+
+//------------------------------------------------------
+// CIRCUIT BREAKER
+//------------------------------------------------------
+
+// The three states of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CircuitState {
+    // Requests flow normally; failures are being counted
+    Closed,
+    // Requests are rejected without touching the backend
+    Open,
+    // A single probe request is allowed to test if the backend recovered
+    HalfOpen,
+}
+
+// A recorded state transition of the breaker
+#[derive(Debug, Clone, PartialEq)]
+struct BreakerEvent {
+    at_ms: u64,
+    from: CircuitState,
+    to: CircuitState,
+}
+
+impl BreakerEvent {
+    // A trip is any transition into the open state
+    fn is_trip(&self) -> bool {
+        self.to == CircuitState::Open
+    }
+
+    // A recovery is the half-open probe succeeding
+    fn is_recovery(&self) -> bool {
+        self.from == CircuitState::HalfOpen && self.to == CircuitState::Closed
+    }
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    failure_threshold: u32,
+    reset_timeout_ms: u64,
+    consecutive_failures: u32,
+    opened_at_ms: u64,
+    // Set while the one half-open probe is waiting for its result
+    probe_in_flight: bool,
+    // Transitions not yet reported to the caller
+    pending_events: Vec<BreakerEvent>,
+}
+
+impl CircuitBreaker {
+    fn new(failure_threshold: u32, reset_timeout_ms: u64) -> Self {
+        CircuitBreaker {
+            state: CircuitState::Closed,
+            failure_threshold: failure_threshold.max(1),
+            reset_timeout_ms,
+            consecutive_failures: 0,
+            opened_at_ms: 0,
+            probe_in_flight: false,
+            pending_events: Vec::new(),
+        }
+    }
+
+    fn transition(&mut self, to: CircuitState, now_ms: u64) {
+        if self.state == to {
+            return;
+        }
+        self.pending_events.push(BreakerEvent { at_ms: now_ms, from: self.state, to });
+        self.state = to;
+
+        if to == CircuitState::Open {
+            self.opened_at_ms = now_ms;
+        }
+    }
+
+    // Decide whether a request may go through, or how long until it may
+    fn allow_request(&mut self, now_ms: u64) -> Result<(), u64> {
+        if self.state == CircuitState::Open {
+            let elapsed = now_ms.saturating_sub(self.opened_at_ms);
+            if elapsed < self.reset_timeout_ms {
+                return Err(self.reset_timeout_ms - elapsed);
+            }
+            self.transition(CircuitState::HalfOpen, now_ms);
+        }
+        if self.state == CircuitState::HalfOpen {
+            // Only one probe at a time; the rest wait as if still open
+            if self.probe_in_flight {
+                return Err(self.reset_timeout_ms);
+            }
+            self.probe_in_flight = true;
+        }
+        Ok(())
+    }
+
+    fn record_success(&mut self, now_ms: u64) {
+        self.probe_in_flight = false;
+        self.consecutive_failures = 0;
+        self.transition(CircuitState::Closed, now_ms);
+    }
+
+    fn record_failure(&mut self, now_ms: u64) {
+        self.probe_in_flight = false;
+        match self.state {
+            CircuitState::Closed => {
+                self.consecutive_failures += 1;
+                if self.consecutive_failures >= self.failure_threshold {
+                    self.transition(CircuitState::Open, now_ms);
+                }
+            }
+            // A failed probe sends the breaker straight back to open
+            CircuitState::HalfOpen => self.transition(CircuitState::Open, now_ms),
+            CircuitState::Open => {}
+        }
+    }
+
+    fn take_events(&mut self) -> Vec<BreakerEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
 }
 
-// API client that references a configuration
+//------------------------------------------------------
+// API CLIENT
+//------------------------------------------------------
+
+// API client that references a configuration, a transport and a clock
 struct ApiClient<'a> {
     config: &'a ApiConfig,
+    transport: &'a dyn Transport,
+    clock: &'a dyn Clock,
+    rate_limiter: TokenBucket,
+    breaker: CircuitBreaker,
 }
 
 // Response metadata
@@ -322,6 +575,36 @@ struct ResponseMetadata {
     status_code: u16,
     response_time_ms: u64,
     cache_hit: bool,
+    // How many requests were sent to the backend (1 + retries)
+    attempts: u32,
+    // Breaker state after this response
+    circuit_state: CircuitState,
+    // Trips and recoveries that happened since the previous response
+    breaker_events: Vec<BreakerEvent>,
+}
+
+impl ResponseMetadata {
+    fn trips(&self) -> usize {
+        self.breaker_events.iter().filter(|e| e.is_trip()).count()
+    }
+
+    fn recovered(&self) -> bool {
+        self.breaker_events.iter().any(|e| e.is_recovery())
+    }
+}
+
+impl Display for ResponseMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "status {} in {} ms (cache hit: {}), {} attempt(s), circuit {:?}",
+            self.status_code, self.response_time_ms, self.cache_hit, self.attempts, self.circuit_state
+        )?;
+        if self.trips() > 0 || self.recovered() {
+            write!(f, ", {} trip(s), recovered: {}", self.trips(), self.recovered())?;
+        }
+        Ok(())
+    }
 }
 
 // Generic API response that contains data of type T
@@ -331,77 +614,426 @@ struct ApiResponse<T> {
 }
 
 // API error type
-struct ApiError(String);
+#[derive(Debug, PartialEq)]
+enum ApiError {
+    // The backend answered, but has nothing at this endpoint
+    NotFound(String),
+    // The backend could not be reached or answered with a server error
+    Backend(String),
+    // The client is over its request budget
+    RateLimited { retry_after_ms: u64 },
+    // The circuit breaker is open and is protecting the backend
+    CircuitOpen { retry_after_ms: u64 },
+}
 
 // Implement Display for ApiError
 impl Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "API Error: {}", self.0)
+        match self {
+            ApiError::NotFound(message) | ApiError::Backend(message) => {
+                write!(f, "API Error: {}", message)
+            }
+            ApiError::RateLimited { retry_after_ms } => {
+                write!(f, "API Error: rate limit exceeded, retry in {} ms", retry_after_ms)
+            }
+            ApiError::CircuitOpen { retry_after_ms } => {
+                write!(f, "API Error: circuit open, retry in {} ms", retry_after_ms)
+            }
+        }
     }
 }
 
 // Implementation of ApiClient
 impl<'a> ApiClient<'a> {
     fn new(config: &'a ApiConfig) -> Self {
-        ApiClient { config }
+        ApiClient::with_transport(config, &SimulatedTransport, &SystemClock)
+    }
+
+    // Build a client on a custom transport and clock (used by the tests)
+    fn with_transport(config: &'a ApiConfig, transport: &'a dyn Transport, clock: &'a dyn Clock) -> Self {
+        let now = clock.now_ms();
+        ApiClient {
+            config,
+            transport,
+            clock,
+            rate_limiter: TokenBucket::new(config.burst_size, config.requests_per_second, now),
+            breaker: CircuitBreaker::new(config.failure_threshold, config.reset_timeout_ms),
+        }
+    }
+
+    fn circuit_state(&self) -> CircuitState {
+        self.breaker.state
+    }
+
+    // Send one request through the rate limiter and the circuit breaker,
+    // retrying backend failures up to `retry_count` times. The request
+    // takes one token however many retries it needs, and a retry the
+    // breaker refuses ends with the backend error that caused it.
+    fn send_with_protection(&mut self, url: &str) -> Result<(RawResponse, u32), ApiError> {
+        let now = self.clock.now_ms();
+        self.breaker
+            .allow_request(now)
+            .map_err(|retry_after_ms| ApiError::CircuitOpen { retry_after_ms })?;
+        if let Err(retry_after_ms) = self.rate_limiter.try_acquire(now) {
+            // The request never went out, so the probe slot is free again
+            self.breaker.probe_in_flight = false;
+            return Err(ApiError::RateLimited { retry_after_ms });
+        }
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let failure = match self.transport.send(url) {
+                Ok(raw) if raw.status_code < 500 => {
+                    self.breaker.record_success(self.clock.now_ms());
+                    return Ok((raw, attempts));
+                }
+                Ok(raw) => format!("server error {} from {}", raw.status_code, url),
+                Err(message) => message,
+            };
+
+            self.breaker.record_failure(self.clock.now_ms());
+
+            if attempts > self.config.retry_count || self.breaker.allow_request(self.clock.now_ms()).is_err() {
+                return Err(ApiError::Backend(failure));
+            }
+        }
     }
     
     // Fetch data of type T from the API
-    fn fetch<T: Entity + Clone>(&self, endpoint: &str) -> Result<ApiResponse<T>, ApiError> {
-        // In a real implementation, this would make an HTTP request
-        // For this example, we'll simulate responses based on the endpoint
+    fn fetch<T: Entity + Clone + 'static>(&mut self, endpoint: &str) -> Result<ApiResponse<T>, ApiError> {
+        let url = format!("{}{}", self.config.base_url, endpoint);
         
-        println!("Fetching from: {}{}", self.config.base_url, endpoint);
+        println!("Fetching from: {}", url);
         println!("Timeout: {} seconds", self.config.timeout);
         println!("Retries: {}", self.config.retry_count);
+        println!("Rate limit tokens left: {}", self.rate_limiter.available());
         
-        // Simulate different responses based on the endpoint
-        if endpoint == "/users/1" {
-            // Simulate a successful user response
+        let (raw, attempts) = self.send_with_protection(&url)?;
+
+        let metadata = ResponseMetadata {
+            status_code: raw.status_code,
+            response_time_ms: raw.response_time_ms,
+            cache_hit: raw.cache_hit,
+            attempts,
+            circuit_state: self.breaker.state,
+            breaker_events: self.breaker.take_events(),
+        };
+
+        // In a real implementation we would deserialize the response body.
+        // For this example, we'll build the entity based on the endpoint.
+        if raw.status_code == 200 && endpoint == "/users/1" {
             let user = User {
                 id: 1,
                 name: String::from("John Doe"),
                 email: String::from("john@example.com"),
             };
             
-            let metadata = ResponseMetadata {
-                status_code: 200,
-                response_time_ms: 120,
-                cache_hit: false,
-            };
-            
             // This is a bit of a hack for the example
             // In a real implementation we'd have proper type checking
             if std::any::TypeId::of::<T>() == std::any::TypeId::of::<User>() {
                 return Ok(ApiResponse {
-                    data: unsafe { std::mem::transmute_copy(&user) },
+                    data: unsafe { std::mem::transmute_copy(&std::mem::ManuallyDrop::new(user)) },
                     metadata,
                 });
             }
-        } else if endpoint == "/products/101" {
-            // Simulate a successful product response
+        } else if raw.status_code == 200 && endpoint == "/products/101" {
             let product = Product {
                 id: 101,
                 name: String::from("Rust Programming"),
-                price: 39.99,
-            };
-            
-            let metadata = ResponseMetadata {
-                status_code: 200,
-                response_time_ms: 85,
-                cache_hit: true,
+                price: Money::usd(3999),
             };
             
             if std::any::TypeId::of::<T>() == std::any::TypeId::of::<Product>() {
                 return Ok(ApiResponse {
-                    data: unsafe { std::mem::transmute_copy(&product) },
+                    data: unsafe { std::mem::transmute_copy(&std::mem::ManuallyDrop::new(product)) },
                     metadata,
                 });
             }
         }
         
-        // Simulate an error for any other endpoint
-        Err(ApiError(format!("Resource not found at {}", endpoint)))
+        // Any other endpoint (or type) is not available
+        Err(ApiError::NotFound(format!("Resource not found at {}", endpoint)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // A clock that only moves when the test says so
+    struct FakeClock {
+        now: Cell<u64>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            FakeClock { now: Cell::new(1_000) }
+        }
+
+        fn advance(&self, ms: u64) {
+            self.now.set(self.now.get() + ms);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now_ms(&self) -> u64 {
+            self.now.get()
+        }
+    }
+
+    // A transport that fails on demand and counts the calls it receives
+    struct MockTransport {
+        failing: Cell<bool>,
+        calls: Cell<u32>,
+    }
+
+    impl MockTransport {
+        fn new() -> Self {
+            MockTransport { failing: Cell::new(false), calls: Cell::new(0) }
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn send(&self, url: &str) -> Result<RawResponse, String> {
+            self.calls.set(self.calls.get() + 1);
+            if self.failing.get() {
+                return Err(format!("connection refused: {}", url));
+            }
+            SimulatedTransport.send(url)
+        }
+    }
+
+    fn test_config() -> ApiConfig {
+        ApiConfig {
+            base_url: String::from("https://api.example.com"),
+            timeout: 30,
+            retry_count: 0,
+            requests_per_second: 10,
+            burst_size: 100,
+            failure_threshold: 3,
+            reset_timeout_ms: 5_000,
+        }
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let mut bucket = TokenBucket::new(2, 4, 0);
+
+        assert!(bucket.try_acquire(0).is_ok());
+        assert!(bucket.try_acquire(0).is_ok());
+        // Empty: 4 tokens per second means one token every 250 ms
+        assert_eq!(bucket.try_acquire(0), Err(250));
+        assert_eq!(bucket.try_acquire(100), Err(150));
+        assert!(bucket.try_acquire(250).is_ok());
+
+        // Refilling never goes past the capacity
+        bucket.refill(60_000);
+        assert_eq!(bucket.available(), 2);
+    }
+
+    #[test]
+    fn test_rate_limiter_rejects_bursts() {
+        let mut config = test_config();
+        config.burst_size = 2;
+        config.requests_per_second = 1;
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        assert!(client.fetch::<User>("/users/1").is_ok());
+        assert!(client.fetch::<User>("/users/1").is_ok());
+
+        let result = client.fetch::<User>("/users/1");
+        assert!(matches!(result, Err(ApiError::RateLimited { retry_after_ms: 1000 })));
+        // The rejected request never reached the backend
+        assert_eq!(transport.calls.get(), 2);
+
+        clock.advance(1000);
+        assert!(client.fetch::<User>("/users/1").is_ok());
+    }
+
+    #[test]
+    fn test_breaker_trips_after_threshold() {
+        let config = test_config();
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        transport.failing.set(true);
+        for _ in 0..2 {
+            assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+            assert_eq!(client.circuit_state(), CircuitState::Closed);
+        }
+
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        // While open, requests are rejected without reaching the backend
+        clock.advance(2_000);
+        let result = client.fetch::<User>("/users/1");
+        assert!(matches!(result, Err(ApiError::CircuitOpen { retry_after_ms: 3_000 })));
+        assert_eq!(transport.calls.get(), 3);
+    }
+
+    #[test]
+    fn test_breaker_recovers_through_half_open() {
+        let config = test_config();
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        transport.failing.set(true);
+        for _ in 0..3 {
+            let _ = client.fetch::<User>("/users/1");
+        }
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        // After the reset timeout a successful probe closes the circuit
+        clock.advance(5_000);
+        transport.failing.set(false);
+        let response = client.fetch::<User>("/users/1").unwrap();
+
+        assert_eq!(response.data.name, "John Doe");
+        assert_eq!(response.metadata.circuit_state, CircuitState::Closed);
+        assert_eq!(response.metadata.trips(), 1);
+        assert!(response.metadata.recovered());
+
+        let transitions: Vec<(CircuitState, CircuitState)> = response
+            .metadata
+            .breaker_events
+            .iter()
+            .map(|e| (e.from, e.to))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (CircuitState::Closed, CircuitState::Open),
+                (CircuitState::Open, CircuitState::HalfOpen),
+                (CircuitState::HalfOpen, CircuitState::Closed),
+            ]
+        );
+
+        // Events are reported only once
+        let response = client.fetch::<User>("/users/1").unwrap();
+        assert!(response.metadata.breaker_events.is_empty());
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_circuit() {
+        let config = test_config();
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        transport.failing.set(true);
+        for _ in 0..3 {
+            let _ = client.fetch::<User>("/users/1");
+        }
+
+        clock.advance(5_000);
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        // The open timer restarts from the failed probe
+        clock.advance(4_999);
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::CircuitOpen { .. })));
+        clock.advance(1);
+        transport.failing.set(false);
+        assert!(client.fetch::<User>("/users/1").is_ok());
+    }
+
+    #[test]
+    fn test_retries_count_as_attempts() {
+        let mut config = test_config();
+        config.retry_count = 2;
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        transport.failing.set(true);
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+        // One request plus two retries, which also trips the breaker
+        assert_eq!(transport.calls.get(), 3);
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_retries_do_not_spend_tokens() {
+        let mut config = test_config();
+        config.retry_count = 2;
+        config.burst_size = 1;
+        config.failure_threshold = 10;
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        // Three sends on one token, and the error is the backend's
+        transport.failing.set(true);
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+        assert_eq!(transport.calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retries_stop_when_the_breaker_trips() {
+        let mut config = test_config();
+        config.retry_count = 5;
+        config.failure_threshold = 2;
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        transport.failing.set(true);
+        assert!(matches!(client.fetch::<User>("/users/1"), Err(ApiError::Backend(_))));
+        assert_eq!(transport.calls.get(), 2);
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_half_open_allows_one_probe() {
+        let mut breaker = CircuitBreaker::new(1, 1_000);
+        breaker.record_failure(0);
+
+        assert!(breaker.allow_request(1_000).is_ok());
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+        // A second request while the probe is out is turned away
+        assert_eq!(breaker.allow_request(1_000), Err(1_000));
+
+        breaker.record_success(1_010);
+        assert_eq!(breaker.state, CircuitState::Closed);
+        assert!(breaker.allow_request(1_010).is_ok());
+        assert!(breaker.allow_request(1_010).is_ok());
+    }
+
+    #[test]
+    fn test_not_found_does_not_count_as_failure() {
+        let mut config = test_config();
+        config.failure_threshold = 1;
+        let clock = FakeClock::new();
+        let transport = MockTransport::new();
+        let mut client = ApiClient::with_transport(&config, &transport, &clock);
+
+        let result = client.fetch::<User>("/non-existent");
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_product_prices_are_exact() {
+        let mut repo = InMemoryRepository::<Product>::new();
+        repo.save(101, Product { id: 101, name: String::from("Rust Book"), price: Money::usd(2999) });
+
+        let mut product = repo.find_by_id(101).unwrap();
+        product.price = Money::usd(2499);
+        repo.save(101, product);
+
+        let updated = repo.find_by_id(101).unwrap();
+        assert_eq!(updated.price, Money::usd(2499));
+        assert_eq!(updated.to_string(), "Product { id: 101, name: Rust Book, price: $24.99 }");
+
+        // Three copies cost exactly $74.97, not 74.97000000000001
+        assert_eq!(updated.price.checked_mul(3), Ok(Money::usd(7497)));
     }
 }
 
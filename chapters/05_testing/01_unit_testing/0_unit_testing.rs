@@ -20,9 +20,11 @@ fn main() {
     // This would panic:
     // let result = divide(10, 0);
     
-    let temp_f = 77.0;
-    let temp_c = fahrenheit_to_celsius(temp_f);
-    println!("{}°F = {}°C", temp_f, temp_c);
+    let temp_f = Temperature::<Fahrenheit>::new(77.0);
+    println!("{} = {}", temp_f, temp_f.to::<Celsius>());
+
+    let body: Temperature<Celsius> = "98.6°F".parse().unwrap();
+    println!("98.6°F = {:.1}", body);
     
     println!("\nComplete the exercises at the end of this file.");
 }
@@ -45,6 +47,25 @@ pub fn factorial(n: u64) -> u64 {
     }
 }
 
+// `factorial` overflows `u64` from 21! on. The course-wide bigint module
+// has integers that grow as needed, so `big_factorial` is exact for any n.
+#[allow(dead_code)]
+#[path = "../../shared/bigint.rs"]
+mod bigint;
+
+pub use bigint::BigUint;
+
+pub fn big_factorial(n: u32) -> BigUint {
+    (1..=n).map(BigUint::from).product()
+}
+
+// 1 + 2 + ... + n = n(n + 1) / 2, worked out in BigUint so that no step
+// can overflow, however large n is
+pub fn big_sum_up_to(n: u32) -> BigUint {
+    let n = BigUint::from(n);
+    &n * &(&n + &BigUint::from(1u32)) / BigUint::from(2u32)
+}
+
 // Unit tests for the add and factorial functions
 #[cfg(test)]
 mod basic_tests {
@@ -63,6 +84,44 @@ mod basic_tests {
         assert_eq!(factorial(1), 1);
         assert_eq!(factorial(5), 120);
     }
+
+    #[test]
+    fn test_big_factorial() {
+        // Where u64 runs out, and the first value it can't hold
+        assert_eq!(big_factorial(20), BigUint::from(factorial(20)));
+        assert_eq!(big_factorial(21).to_string(), "51090942171709440000");
+        assert!(factorial(20).checked_mul(21).is_none());
+
+        assert_eq!(
+            big_factorial(100).to_string(),
+            "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000"
+        );
+        assert_eq!(big_factorial(0), BigUint::from(1u32));
+    }
+
+    #[test]
+    fn test_big_add() {
+        // u8 gives up at 255, a BigUint just grows another digit
+        assert_eq!(u8::MAX.checked_add(1), None);
+        assert_eq!((BigUint::from(u8::MAX) + BigUint::from(1u8)).to_string(), "256");
+        assert_eq!((BigUint::from(u8::MAX) + BigUint::from(u8::MAX)).to_string(), "510");
+    }
+
+    #[test]
+    fn test_big_sum_up_to() {
+        assert_eq!(big_sum_up_to(0), BigUint::from(0u32));
+        assert_eq!(big_sum_up_to(100), BigUint::from(5050u32));
+
+        // The last n whose sum still fits in a u32, and the first that doesn't
+        assert_eq!(big_sum_up_to(92681), BigUint::from(4294930221u32));
+        assert_eq!(big_sum_up_to(92682).to_string(), "4295022903");
+
+        // Same as adding the terms one by one
+        let added: BigUint = (1..=1000u32).map(BigUint::from).sum();
+        assert_eq!(big_sum_up_to(1000), added);
+
+        assert_eq!(big_sum_up_to(u32::MAX).to_string(), "9223372034707292160");
+    }
 }
 
 //------------------------------------------------------
@@ -161,15 +220,15 @@ mod rectangle_tests {
 // TEST ORGANIZATION
 //------------------------------------------------------
 
-// Function to convert Fahrenheit to Celsius
-pub fn fahrenheit_to_celsius(f: f64) -> f64 {
-    (f - 32.0) * 5.0 / 9.0
-}
+// Temperatures come from the course-wide units module. The scale is part
+// of the type, so a `Temperature<Fahrenheit>` can't be passed where a
+// `Temperature<Celsius>` is expected; `to` converts between them. The
+// module also has lengths, masses and times, and parses text like "3 km".
+#[allow(dead_code)]
+#[path = "../../shared/units.rs"]
+mod units;
 
-// Function to convert Celsius to Fahrenheit
-pub fn celsius_to_fahrenheit(c: f64) -> f64 {
-    c * 9.0 / 5.0 + 32.0
-}
+pub use units::{Celsius, Fahrenheit, Temperature};
 
 // Organized tests for temperature conversion functions
 #[cfg(test)]
@@ -192,8 +251,9 @@ mod temperature_tests {
         let test_cases = setup();
         
         for (f, c) in test_cases {
+            let celsius = Temperature::<Fahrenheit>::new(f).to::<Celsius>();
             // Use a delta for floating-point comparisons
-            assert!((fahrenheit_to_celsius(f) - c).abs() < 0.1, 
+            assert!((celsius.value() - c).abs() < 0.1,
                     "Failed conversion: {}°F should be approx {}°C", f, c);
         }
     }
@@ -203,8 +263,9 @@ mod temperature_tests {
         let test_cases = setup();
         
         for (f, c) in test_cases {
+            let fahrenheit = Temperature::<Celsius>::new(c).to::<Fahrenheit>();
             // Use a delta for floating-point comparisons
-            assert!((celsius_to_fahrenheit(c) - f).abs() < 0.1,
+            assert!((fahrenheit.value() - f).abs() < 0.1,
                     "Failed conversion: {}°C should be approx {}°F", c, f);
         }
     }
@@ -216,8 +277,8 @@ mod temperature_tests {
         let temperatures = vec![0.0, 32.0, 68.0, 100.0, -10.0];
         
         for temp in temperatures {
-            let round_trip = celsius_to_fahrenheit(fahrenheit_to_celsius(temp));
-            assert!((round_trip - temp).abs() < 0.0001,
+            let round_trip = Temperature::<Fahrenheit>::new(temp).to::<Celsius>().to::<Fahrenheit>();
+            assert!((round_trip.value() - temp).abs() < 0.0001,
                     "Round trip conversion failed for {}°F", temp);
         }
     }
@@ -267,22 +328,32 @@ mod calculator {
 // Let's implement a simple string utility library using TDD.
 // We'll start with the tests, then implement the functions.
 
+// Text comes from the course-wide text module. It works on graphemes,
+// what a reader sees as one character, instead of `char`s: "é" may be one
+// code point or "e" plus a combining accent, and a flag or a family emoji
+// is several code points. Comparing char by char gets all of these wrong.
+#[allow(dead_code)]
+#[path = "../../shared/text.rs"]
+mod text;
+
 pub mod string_utils {
+    use super::text;
+
     // Function to remove all whitespace from a string
     pub fn remove_whitespace(s: &str) -> String {
-        s.chars().filter(|c| !c.is_whitespace()).collect()
+        text::remove_whitespace(s)
     }
     
-    // Function to check if a string is a palindrome
+    // Function to check if a string is a palindrome. Case, spacing,
+    // punctuation and how accents are encoded don't matter.
     pub fn is_palindrome(s: &str) -> bool {
-        let s = remove_whitespace(&s.to_lowercase());
-        let reversed: String = s.chars().rev().collect();
-        s == reversed
+        text::is_palindrome(s)
     }
     
-    // Function to count the frequency of a character in a string
+    // Function to count the frequency of a character in a string, whether
+    // it's written precomposed or with a combining accent
     pub fn char_frequency(s: &str, c: char) -> usize {
-        s.chars().filter(|&ch| ch == c).count()
+        text::grapheme_frequency(s, c.encode_utf8(&mut [0; 4]))
     }
     
     #[cfg(test)]
@@ -311,6 +382,17 @@ pub mod string_utils {
             assert_eq!(char_frequency("Mississippi", 's'), 4);
             assert_eq!(char_frequency("counting", 'z'), 0);
         }
+
+        #[test]
+        fn test_multi_codepoint_text() {
+            // "été" with the first é written as e + combining acute accent
+            assert!(is_palindrome("e\u{301}t\u{E9}"));
+            assert!(!is_palindrome("e\u{301}te"));
+            assert!(is_palindrome("\u{1F1EB}\u{1F1F7} abba \u{1F1EB}\u{1F1F7}"));
+            assert_eq!(char_frequency("e\u{301}t\u{E9}", '\u{E9}'), 2);
+            assert_eq!(char_frequency("e\u{301}t\u{E9}", 'e'), 0);
+            assert_eq!(remove_whitespace("e\u{301} t"), "e\u{301}t");
+        }
     }
 }
 
@@ -335,6 +417,11 @@ pub fn sum_vector(v: &[i32]) -> i32 {
 // Implement a function `to_roman` that converts an integer (1-3999) to
 // a Roman numeral string. Write tests for various cases, including
 // edge cases. If the input is outside the valid range, return None.
+//
+// Once you're done, chapters/shared/roman.rs shows a complete version that
+// also parses numerals back (strictly, rejecting "IIII" and "VX", or
+// leniently), writes values over 3999 with overlines, and tests the round
+// trip for every value from 1 to 3999.
 
 // TODO: Implement to_roman
 pub fn to_roman(num: u16) -> Option<String> {
@@ -362,6 +449,10 @@ pub fn to_roman(num: u16) -> Option<String> {
 // 5. Contains at least one special character (!@#$%^&*()_+)
 //
 // Start by writing tests for each requirement, then implement the validation function.
+//
+// Once you're done, chapters/shared/password_policy.rs shows a configurable
+// `PasswordPolicy` that reports every rule a password breaks instead of a
+// single bool, and adds rules such as banned words and a minimum entropy.
 
 // TODO: Implement validate_password
 pub fn validate_password(password: &str) -> bool {
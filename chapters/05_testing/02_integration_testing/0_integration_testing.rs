@@ -38,81 +38,24 @@ my_project/
 // EXAMPLE: LIBRARY CODE (what would be in src/lib.rs)
 //------------------------------------------------------
 
-// This is an example of what might be in your lib.rs file
-pub struct User {
-    pub id: u64,
-    pub name: String,
-    pub email: String,
-    active: bool, // Note: private field
-}
+// This is an example of what might be in your lib.rs file.
+//
+// The `User` type and the user store live in a library module shared with
+// 04_test_organization. Note how `User` keeps its `active` field private:
+// integration tests can only see the public API, so they have to go
+// through `is_active()` and `UserService::deactivate_user()`.
+#[path = "../shared/user_service.rs"]
+pub mod user_service;
 
-impl User {
-    pub fn new(id: u64, name: &str, email: &str) -> Self {
-        User {
-            id,
-            name: name.to_string(),
-            email: email.to_string(),
-            active: true,
-        }
-    }
-    
-    pub fn is_active(&self) -> bool {
-        self.active
-    }
-    
-    pub fn deactivate(&mut self) {
-        self.active = false;
-    }
-    
-    // Private helper method
-    fn validate_email(email: &str) -> bool {
-        // Simple validation: check if email contains @ and .
-        email.contains('@') && email.contains('.')
-    }
-}
+pub use user_service::{User, UserEvent, UserService};
 
 pub mod user_manager {
-    use super::User;
-    use std::collections::HashMap;
-    
-    pub struct UserManager {
-        users: HashMap<u64, User>,
-    }
-    
-    impl UserManager {
-        pub fn new() -> Self {
-            UserManager {
-                users: HashMap::new(),
-            }
-        }
-        
-        pub fn add_user(&mut self, user: User) -> Result<(), String> {
-            // Check if user with this ID already exists
-            if self.users.contains_key(&user.id) {
-                return Err(format!("User with ID {} already exists", user.id));
-            }
-            
-            // Validate email format
-            if !super::User::validate_email(&user.email) {
-                return Err("Invalid email format".to_string());
-            }
-            
-            self.users.insert(user.id, user);
-            Ok(())
-        }
-        
-        pub fn get_user(&self, id: u64) -> Option<&User> {
-            self.users.get(&id)
-        }
-        
-        pub fn delete_user(&mut self, id: u64) -> bool {
-            self.users.remove(&id).is_some()
-        }
-        
-        pub fn count(&self) -> usize {
-            self.users.len()
-        }
-    }
+    // `UserManager` used to be its own store keyed by id. It is now the
+    // shared service. `get_user`, `delete_user` and `count` are unchanged,
+    // but `add_user` now returns the new user's id and reports failures as
+    // a `UserError` enum: `Result<u64, UserError>` instead of
+    // `Result<(), String>`.
+    pub use super::user_service::UserService as UserManager;
 }
 
 //------------------------------------------------------
@@ -136,11 +79,12 @@ fn test_user_creation() {
 
 #[test]
 fn test_user_deactivation() {
-    let mut user = User::new(1, "Alice", "alice@example.com");
-    assert!(user.is_active());
+    let mut manager = UserManager::new();
+    manager.add_user(User::new(1, "Alice", "alice@example.com")).unwrap();
+    assert!(manager.get_user(1).unwrap().is_active());
     
-    user.deactivate();
-    assert!(!user.is_active());
+    assert!(manager.deactivate_user(1));
+    assert!(!manager.get_user(1).unwrap().is_active());
 }
 
 #[test]
@@ -17,6 +17,8 @@ fn main() {
     let palindrome = is_palindrome("racecar");
     println!("Is 'racecar' a palindrome? {}", palindrome);
     
+    println!("Prime factors of 360: {:?}", number_theory::factorize(360));
+
     println!("\nComplete the exercises at the end of this file.");
 }
 
@@ -428,4 +430,16 @@ pub mod math {
         
         true
     }
-} 
\ No newline at end of file
+}
+
+//------------------------------------------------------
+// NUMBER THEORY
+//------------------------------------------------------
+
+// Once you've documented `math` above, number_theory.rs shows a fully
+// documented take on the same functions: it works on u64 and adds modular
+// arithmetic, prime sieves and factorization. It's documented with `//!`
+// for the module and `///` for each function, and every example in it is
+// a doc-test.
+#[path = "number_theory.rs"]
+pub mod number_theory;
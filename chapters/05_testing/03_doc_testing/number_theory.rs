@@ -0,0 +1,422 @@
+//! Number theory on `u64`: greatest common divisors, modular arithmetic,
+//! primality testing, prime sieves and factorization.
+//!
+//! The `math` module in exercise 4 has simple versions of `gcd`, `lcm` and
+//! `is_prime` on `u32`. The versions here work across the whole `u64`
+//! range without overflowing and stay fast on large inputs:
+//!
+//! - Products are taken in `u128`, so `mod_pow` and `lcm` never overflow
+//!   partway through.
+//! - `is_prime` runs Miller-Rabin with a fixed set of bases that is known
+//!   to give the right answer for every `u64`, instead of trial division.
+//! - `primes_in_range` sieves a window of numbers a segment at a time, so
+//!   it needs memory for the window, not for everything up to its end.
+//! - `factorize` uses Pollard's rho method for the large factors trial
+//!   division would take billions of steps to find.
+//!
+//! Every function's examples below are doc-tests.
+//!
+//! # Examples
+//!
+//! ```
+//! use doc_testing::number_theory::{factorize, is_prime, mod_pow};
+//!
+//! // 2^61 - 1 is a Mersenne prime
+//! let mersenne = (1u64 << 61) - 1;
+//! assert!(is_prime(mersenne));
+//!
+//! // Fermat's little theorem: a^(p-1) = 1 (mod p) for a prime p
+//! assert_eq!(mod_pow(3, mersenne - 1, mersenne), 1);
+//!
+//! assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+//! ```
+
+/// Returns the greatest common divisor of two numbers, using Euclid's
+/// algorithm. `gcd(0, 0)` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::gcd;
+///
+/// assert_eq!(gcd(48, 18), 6);
+/// assert_eq!(gcd(17, 5), 1);
+/// assert_eq!(gcd(0, 7), 7);
+/// ```
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the least common multiple of two numbers, or `None` if it
+/// doesn't fit in a `u64`. The lcm of 0 and anything is 0.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::lcm;
+///
+/// assert_eq!(lcm(4, 6), Some(12));
+/// assert_eq!(lcm(0, 5), Some(0));
+///
+/// // Too large for u64
+/// assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+/// ```
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    // Dividing first keeps the intermediate value small
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The extended Euclidean algorithm. Returns `(g, x, y)` where `g` is
+/// `gcd(a, b)` and `a * x + b * y == g`.
+///
+/// The coefficients can be negative, and are returned as `i128` so that
+/// they always fit.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::extended_gcd;
+///
+/// let (g, x, y) = extended_gcd(240, 46);
+/// assert_eq!(g, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+pub fn extended_gcd(a: u64, b: u64) -> (u64, i128, i128) {
+    // Invariants: a * old_x + b * old_y == old_r, and the same for r
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    (old_r as u64, old_x, old_y)
+}
+
+/// Returns `a * b % modulus` without overflowing.
+///
+/// # Panics
+///
+/// Panics if `modulus` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::mod_mul;
+///
+/// // u64::MAX * u64::MAX would overflow a u64 many times over
+/// assert_eq!(mod_mul(u64::MAX, u64::MAX, 1_000_000_007), 114_944_269);
+/// ```
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// Returns `base` raised to `exponent`, modulo `modulus`, by repeated
+/// squaring: about 2 * log2(exponent) multiplications instead of
+/// `exponent` of them.
+///
+/// # Panics
+///
+/// Panics if `modulus` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::mod_pow;
+///
+/// assert_eq!(mod_pow(2, 10, 1000), 24);
+/// assert_eq!(mod_pow(7, 0, 13), 1);
+/// assert_eq!(mod_pow(5, 3, 1), 0);
+/// ```
+///
+/// ```should_panic
+/// // This will panic
+/// doc_testing::number_theory::mod_pow(2, 10, 0);
+/// ```
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus != 0, "modulus must not be zero");
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Returns the `x` in `0..modulus` with `a * x % modulus == 1`, or `None`
+/// if there isn't one, which is when `a` and `modulus` share a factor.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::mod_inverse;
+///
+/// assert_eq!(mod_inverse(3, 11), Some(4)); // 3 * 4 = 12 = 1 (mod 11)
+/// assert_eq!(mod_inverse(6, 9), None); // 6 and 9 are both multiples of 3
+/// assert_eq!(mod_inverse(5, 1), Some(0));
+/// ```
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a % modulus, modulus);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus as i128) as u64)
+}
+
+// Together these bases give the right answer for every n < 2^64
+// (Jim Sinclair's result; the first 12 primes also work)
+const MILLER_RABIN_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+/// Checks whether a number is prime with the Miller-Rabin test, using
+/// bases that make it exact (never wrong) for every `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::is_prime;
+///
+/// assert!(is_prime(2));
+/// assert!(is_prime(1_000_000_007));
+/// assert!(!is_prime(1));
+/// assert!(!is_prime(561)); // A Carmichael number: 3 * 11 * 17
+///
+/// // The largest prime below 2^64
+/// assert!(is_prime(18_446_744_073_709_551_557));
+/// ```
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // Write n - 1 as d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    'bases: for base in MILLER_RABIN_BASES {
+        let base = base % n;
+        if base == 0 {
+            continue;
+        }
+        let mut x = mod_pow(base, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mod_mul(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        // `base` is a witness that n is composite
+        return false;
+    }
+    true
+}
+
+// The sieve of Eratosthenes, for the small primes the segmented sieve
+// crosses out with
+fn simple_sieve(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut is_prime = vec![true; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if is_prime[n] {
+            primes.push(n as u64);
+            for multiple in (n * n..=limit).step_by(n) {
+                is_prime[multiple] = false;
+            }
+        }
+    }
+    primes
+}
+
+// How many numbers the segmented sieve looks at at once
+const SEGMENT_SIZE: u64 = 1 << 15;
+
+/// Returns every prime in `low..high`, in order, using a segmented sieve
+/// of Eratosthenes.
+///
+/// It finds the primes up to `sqrt(high)` first, then crosses out their
+/// multiples in one window of the range at a time, so a range far from
+/// zero needs memory for the window and those small primes, not for
+/// every number below it. Near the top of the `u64` range that is still
+/// every prime below 2^32, about 200 million of them; to check a handful
+/// of numbers that large, call `is_prime` on each instead.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::primes_in_range;
+///
+/// assert_eq!(primes_in_range(10, 30), vec![11, 13, 17, 19, 23, 29]);
+/// assert_eq!(primes_in_range(0, 10), vec![2, 3, 5, 7]);
+/// assert!(primes_in_range(24, 29).is_empty());
+///
+/// // The primes just past one trillion
+/// let start = 1_000_000_000_000;
+/// assert_eq!(
+///     primes_in_range(start, start + 100),
+///     vec![1_000_000_000_039, 1_000_000_000_061, 1_000_000_000_063, 1_000_000_000_091]
+/// );
+/// ```
+pub fn primes_in_range(low: u64, high: u64) -> Vec<u64> {
+    let low = low.max(2);
+    if low >= high {
+        return Vec::new();
+    }
+    let base_primes = simple_sieve((high - 1).isqrt());
+    let mut primes = Vec::new();
+
+    let mut start = low;
+    while start < high {
+        let end = high.min(start.saturating_add(SEGMENT_SIZE));
+        let mut is_prime = vec![true; (end - start) as usize];
+        for &p in &base_primes {
+            // Primes up to sqrt(end) are enough to find every composite
+            if p * p >= end {
+                break;
+            }
+            // The first multiple of p in the segment that isn't p itself
+            let first = (p * p).max(start.div_ceil(p).saturating_mul(p));
+            for multiple in (first..end).step_by(p as usize) {
+                is_prime[(multiple - start) as usize] = false;
+            }
+        }
+        primes.extend(
+            is_prime
+                .iter()
+                .enumerate()
+                .filter(|&(_, &prime)| prime)
+                .map(|(offset, _)| start + offset as u64),
+        );
+        start = end;
+    }
+    primes
+}
+
+/// Returns every prime up to and including `limit`.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::primes_up_to;
+///
+/// assert_eq!(primes_up_to(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+/// assert_eq!(primes_up_to(1_000_000).len(), 78_498);
+/// assert!(primes_up_to(1).is_empty());
+/// ```
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    // u64::MAX isn't prime, so stopping one short of it loses nothing
+    primes_in_range(0, limit.saturating_add(1))
+}
+
+// Finds some factor of a composite n with Pollard's rho method: iterating
+// x -> x^2 + c (mod n) eventually cycles modulo each prime factor p of n,
+// much sooner than modulo n itself, and when it does, gcd(difference, n)
+// is a multiple of p
+fn pollard_rho(n: u64) -> u64 {
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+    for c in 1u64.. {
+        // Added in u128: for n near u64::MAX, x² mod n + c can overflow u64
+        let step = |x: u64| ((mod_mul(x, x, n) as u128 + c as u128) % n as u128) as u64;
+        // Floyd's cycle detection: one value moves twice as fast
+        let (mut slow, mut fast, mut divisor) = (2, 2, 1);
+        while divisor == 1 {
+            slow = step(slow);
+            fast = step(step(fast));
+            divisor = gcd(slow.abs_diff(fast), n);
+        }
+        // divisor == n means this c failed; try another
+        if divisor != n {
+            return divisor;
+        }
+    }
+    unreachable!("every composite has a factor")
+}
+
+fn collect_prime_factors(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let divisor = pollard_rho(n);
+    collect_prime_factors(divisor, factors);
+    collect_prime_factors(n / divisor, factors);
+}
+
+/// Splits a number into primes, returned as `(prime, exponent)` pairs
+/// from smallest prime to largest. 0 and 1 have no prime factors.
+///
+/// Small factors are found by trial division and large ones with
+/// Pollard's rho method, so even a product of two large primes is quick.
+///
+/// # Examples
+///
+/// ```
+/// use doc_testing::number_theory::factorize;
+///
+/// assert_eq!(factorize(84), vec![(2, 2), (3, 1), (7, 1)]);
+/// assert_eq!(factorize(97), vec![(97, 1)]);
+/// assert_eq!(factorize(1), vec![]);
+///
+/// // Two primes near 2^32, which trial division would need about four
+/// // billion steps to separate
+/// assert_eq!(
+///     factorize(4_294_967_291 * 4_294_967_279),
+///     vec![(4_294_967_279, 1), (4_294_967_291, 1)]
+/// );
+///
+/// // A semiprime just below u64::MAX
+/// assert_eq!(
+///     factorize(u64::MAX - 38),
+///     vec![(139_646_831, 1), (132_095_686_967, 1)]
+/// );
+/// ```
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    if n == 0 {
+        return Vec::new();
+    }
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+    collect_prime_factors(n, &mut factors);
+    factors.sort_unstable();
+
+    let mut counted: Vec<(u64, u32)> = Vec::new();
+    for factor in factors {
+        match counted.last_mut() {
+            Some((prime, count)) if *prime == factor => *count += 1,
+            _ => counted.push((factor, 1)),
+        }
+    }
+    counted
+}
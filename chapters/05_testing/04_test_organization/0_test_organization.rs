@@ -3,7 +3,11 @@
 // This file demonstrates techniques for organizing tests in Rust,
 // including test modules, fixtures, and setup/teardown patterns.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
 
 fn main() {
     println!("Test Organization in Rust");
@@ -28,91 +32,98 @@ fn main() {
 // USER DATABASE EXAMPLE
 //------------------------------------------------------
 
-/// Represents a user in the system
-#[derive(Debug, Clone, PartialEq)]
-pub struct User {
-    pub name: String,
-    pub age: u32,
-    pub email: String,
-}
+// Users are stored by the shared `UserService` (also used by
+// 02_integration_testing). `UserDatabase` is a name-keyed front end to it
+// that adds search, secondary indexes and persistence.
+#[path = "../shared/user_service.rs"]
+mod user_service;
+
+pub use user_service::{EmailAddress, EmailError, User, UserError, UserEvent, UserService};
 
 /// A simple in-memory user database
 pub struct UserDatabase {
-    users: HashMap<String, User>,
+    service: UserService,
+    indexes: UserIndexes,
 }
 
 impl UserDatabase {
     /// Creates a new, empty user database
     pub fn new() -> Self {
         UserDatabase {
-            users: HashMap::new(),
+            service: UserService::new(),
+            indexes: UserIndexes::default(),
         }
     }
     
     /// Creates a new user and adds them to the database
     pub fn create_user(&mut self, name: &str, age: u32, email: &str) -> Result<(), String> {
-        // Check if user already exists
-        if self.users.contains_key(name) {
-            return Err(format!("User '{}' already exists", name));
-        }
+        // The service checks for duplicates and validates the email
+        let id = self
+            .service
+            .create_user(name, age, email)
+            .map_err(|e| e.to_string())?;
         
-        // Validate email format (very basic validation)
-        if !email.contains('@') {
-            return Err("Invalid email format".to_string());
+        if let Some(user) = self.service.get_user(id) {
+            self.indexes.add(user);
         }
-        
-        // Add the user
-        let user = User {
-            name: name.to_string(),
-            age,
-            email: email.to_string(),
-        };
-        
-        self.users.insert(name.to_string(), user);
         Ok(())
     }
     
     /// Retrieves a user by name
     pub fn get_user(&self, name: &str) -> Option<&User> {
-        self.users.get(name)
+        self.service.get_user_by_name(name)
     }
     
     /// Updates a user's information
     pub fn update_user(&mut self, name: &str, age: u32, email: &str) -> Result<(), String> {
         // Check if user exists
-        if !self.users.contains_key(name) {
-            return Err(format!("User '{}' not found", name));
-        }
-        
-        // Validate email format
-        if !email.contains('@') {
-            return Err("Invalid email format".to_string());
-        }
-        
-        // Update the user
-        let user = User {
-            name: name.to_string(),
-            age,
-            email: email.to_string(),
+        let old = match self.get_user(name) {
+            Some(user) => user.clone(),
+            None => return Err(format!("User '{}' not found", name)),
         };
         
-        self.users.insert(name.to_string(), user);
+        self.service
+            .update_user(old.id, age, email)
+            .map_err(|e| e.to_string())?;
+
+        // Re-index the user under its new age and email
+        self.indexes.remove(&old);
+        if let Some(user) = self.service.get_user(old.id) {
+            self.indexes.add(user);
+        }
         Ok(())
     }
     
+    /// Marks a user as inactive without removing them.
+    /// Returns false if the user does not exist or is already inactive.
+    pub fn deactivate_user(&mut self, name: &str) -> bool {
+        match self.get_user(name) {
+            Some(user) => {
+                let id = user.id;
+                self.service.deactivate_user(id)
+            }
+            None => false,
+        }
+    }
+
     /// Deletes a user from the database
     pub fn delete_user(&mut self, name: &str) -> bool {
-        self.users.remove(name).is_some()
+        self.remove_user(name).is_some()
     }
     
     /// Returns the number of users in the database
     pub fn count(&self) -> usize {
-        self.users.len()
+        self.service.count()
     }
     
     /// Lists all users in the database
     pub fn list_users(&self) -> Vec<&User> {
-        self.users.values().collect()
+        self.service.list_users()
+    }
+
+    /// Returns a receiver for the user events of the underlying service
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<UserEvent> {
+        self.service.subscribe()
     }
 }
 
@@ -378,16 +389,33 @@ mod parameterized_tests {
         // Test cases: (email, valid)
         let test_cases = vec![
             ("alice@example.com", true),
-            ("bob@example", true),       // Simple validation just checks for @
+            ("bob@example", false),      // The domain needs at least two labels
             ("invalid-email", false),
             ("charlie@example.org", true),
+            ("dave..x@example.org", false),
         ];
         
         for (email, expected_valid) in test_cases {
-            let valid = email.contains('@');
+            let valid = EmailAddress::is_valid(email);
             assert_eq!(valid, expected_valid, "Email '{}' validation failed", email);
         }
     }
+
+    #[test]
+    fn test_database_rejects_invalid_emails() {
+        // Test cases: (email, expected error)
+        let test_cases = vec![
+            ("bob@example", EmailError::SingleLabelDomain),
+            ("bob@@example.com", EmailError::InvalidDomainChar { ch: '@', position: 4 }),
+            (".bob@example.com", EmailError::LeadingDot { position: 0 }),
+        ];
+
+        for (email, expected) in test_cases {
+            let mut db = UserDatabase::new();
+            let error = db.create_user("bob", 35, email).unwrap_err();
+            assert!(error.contains(&expected.to_string()), "unexpected error: {}", error);
+        }
+    }
 }
 
 //------------------------------------------------------
@@ -454,16 +482,728 @@ mod search_tests {
 
 // Step 2: Implement the function to make the tests pass
 impl UserDatabase {
-    /// Searches for users whose names contain the given query string
+    /// Searches for users whose names contain the given query string.
+    /// Results are sorted by name; see `query` for indexed lookups.
     pub fn search_users(&self, query: &str) -> Vec<&User> {
         let query = query.to_lowercase();
-        self.users
-            .values()
+        let mut results: Vec<&User> = self
+            .service
+            .list_users()
+            .into_iter()
             .filter(|user| user.name.to_lowercase().contains(&query))
+            .collect();
+        results.sort_by(|a, b| a.name.cmp(&b.name));
+        results
+    }
+}
+
+//------------------------------------------------------
+// SECONDARY INDEXES AND QUERIES
+//------------------------------------------------------
+
+// `search_users` has to look at every user. For the common lookups the
+// database also keeps secondary indexes, updated on every insert and removal:
+//
+// - email domain -> names          (exact match, case-insensitive)
+// - age          -> names          (BTreeMap, so age ranges are a range scan)
+// - name prefix  -> names          (a trie over the lowercased names)
+//
+// Queries are built from small pieces and combined with `and` / `or`:
+//
+//     db.query(&age_between(20, 30).and(email_domain("example.com")))
+//
+// Every index stores names in a BTreeSet, so results always come back sorted
+// by name no matter how the query was put together.
+
+/// A trie over lowercased user names, used for prefix lookups
+#[derive(Default)]
+struct NameTrie {
+    root: TrieNode,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<char, TrieNode>,
+    // Names whose lowercased form ends at this node
+    names: BTreeSet<String>,
+}
+
+impl NameTrie {
+    fn insert(&mut self, name: &str) {
+        let mut node = &mut self.root;
+        for c in name.to_lowercase().chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.names.insert(name.to_string());
+    }
+
+    fn remove(&mut self, name: &str) {
+        let key: Vec<char> = name.to_lowercase().chars().collect();
+        Self::remove_from(&mut self.root, &key, name);
+    }
+
+    // Returns true when `node` became empty and can be pruned by its parent
+    fn remove_from(node: &mut TrieNode, key: &[char], name: &str) -> bool {
+        match key.split_first() {
+            None => {
+                node.names.remove(name);
+            }
+            Some((c, rest)) => {
+                if let Some(child) = node.children.get_mut(c) {
+                    if Self::remove_from(child, rest, name) {
+                        node.children.remove(c);
+                    }
+                }
+            }
+        }
+        node.names.is_empty() && node.children.is_empty()
+    }
+
+    fn with_prefix(&self, prefix: &str) -> BTreeSet<String> {
+        let mut node = &self.root;
+        for c in prefix.to_lowercase().chars() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return BTreeSet::new(),
+            }
+        }
+
+        let mut names = BTreeSet::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            names.extend(node.names.iter().cloned());
+            stack.extend(node.children.values());
+        }
+        names
+    }
+}
+
+/// The secondary indexes kept alongside the users
+#[derive(Default)]
+struct UserIndexes {
+    by_domain: HashMap<String, BTreeSet<String>>,
+    by_age: BTreeMap<u32, BTreeSet<String>>,
+    by_name: NameTrie,
+}
+
+/// The lowercased domain of a stored user's email
+fn email_domain_of(user: &User) -> String {
+    user.email_address()
+        .map(|email| email.normalized_domain())
+        .unwrap_or_default()
+}
+
+impl UserIndexes {
+    fn add(&mut self, user: &User) {
+        self.by_domain
+            .entry(email_domain_of(user))
+            .or_default()
+            .insert(user.name.clone());
+        self.by_age.entry(user.age).or_default().insert(user.name.clone());
+        self.by_name.insert(&user.name);
+    }
+
+    fn remove(&mut self, user: &User) {
+        let domain = email_domain_of(user);
+        if let Some(names) = self.by_domain.get_mut(&domain) {
+            names.remove(&user.name);
+            if names.is_empty() {
+                self.by_domain.remove(&domain);
+            }
+        }
+        if let Some(names) = self.by_age.get_mut(&user.age) {
+            names.remove(&user.name);
+            if names.is_empty() {
+                self.by_age.remove(&user.age);
+            }
+        }
+        self.by_name.remove(&user.name);
+    }
+}
+
+/// A query over the secondary indexes
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Users whose age is in `min..=max`
+    AgeBetween(u32, u32),
+    /// Users whose email is at this domain (case-insensitive)
+    EmailDomain(String),
+    /// Users whose name starts with this prefix (case-insensitive)
+    NamePrefix(String),
+    /// Users matching both queries
+    And(Box<Query>, Box<Query>),
+    /// Users matching either query
+    Or(Box<Query>, Box<Query>),
+}
+
+/// Users whose age is between `min` and `max`, inclusive
+pub fn age_between(min: u32, max: u32) -> Query {
+    Query::AgeBetween(min, max)
+}
+
+/// Users with an email address at `domain`
+pub fn email_domain(domain: &str) -> Query {
+    Query::EmailDomain(domain.to_lowercase())
+}
+
+/// Users whose name starts with `prefix`
+pub fn name_prefix(prefix: &str) -> Query {
+    Query::NamePrefix(prefix.to_string())
+}
+
+impl Query {
+    /// Combines two queries; a user must match both
+    pub fn and(self, other: Query) -> Query {
+        Query::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combines two queries; a user must match at least one
+    pub fn or(self, other: Query) -> Query {
+        Query::Or(Box::new(self), Box::new(other))
+    }
+}
+
+impl UserDatabase {
+    /// Adds a user (with its id) to the service and to every index
+    fn insert_user(&mut self, user: User) -> Result<(), UserError> {
+        let id = self.service.add_user(user)?;
+        if let Some(user) = self.service.get_user(id) {
+            self.indexes.add(user);
+        }
+        Ok(())
+    }
+
+    /// Removes a user from the service and from every index
+    fn remove_user(&mut self, name: &str) -> Option<User> {
+        let id = self.get_user(name)?.id;
+        let user = self.service.remove_user(id)?;
+        self.indexes.remove(&user);
+        Some(user)
+    }
+
+    fn matching_names(&self, query: &Query) -> BTreeSet<String> {
+        match query {
+            Query::AgeBetween(min, max) if min <= max => self
+                .indexes
+                .by_age
+                .range(*min..=*max)
+                .flat_map(|(_, names)| names.iter().cloned())
+                .collect(),
+            Query::AgeBetween(_, _) => BTreeSet::new(),
+            Query::EmailDomain(domain) => self
+                .indexes
+                .by_domain
+                .get(&domain.to_lowercase())
+                .cloned()
+                .unwrap_or_default(),
+            Query::NamePrefix(prefix) => self.indexes.by_name.with_prefix(prefix),
+            Query::And(left, right) => {
+                let left = self.matching_names(left);
+                if left.is_empty() {
+                    return left;
+                }
+                let right = self.matching_names(right);
+                left.intersection(&right).cloned().collect()
+            }
+            Query::Or(left, right) => {
+                let mut names = self.matching_names(left);
+                names.extend(self.matching_names(right));
+                names
+            }
+        }
+    }
+
+    /// Runs a query against the indexes.
+    /// Results are sorted by name.
+    pub fn query(&self, query: &Query) -> Vec<&User> {
+        self.matching_names(query)
+            .iter()
+            .filter_map(|name| self.get_user(name))
             .collect()
     }
 }
 
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+
+    fn setup_test_db() -> UserDatabase {
+        let mut db = UserDatabase::new();
+        db.create_user("alice", 28, "alice@example.com").unwrap();
+        db.create_user("bob", 35, "bob@example.com").unwrap();
+        db.create_user("alicia", 42, "alicia@Example.com").unwrap();
+        db.create_user("Alfred", 21, "alfred@other.org").unwrap();
+        db.create_user("carol", 30, "carol@other.org").unwrap();
+        db
+    }
+
+    fn names(users: Vec<&User>) -> Vec<&str> {
+        users.iter().map(|u| u.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_age_between_is_inclusive() {
+        let db = setup_test_db();
+        assert_eq!(names(db.query(&age_between(28, 35))), vec!["alice", "bob", "carol"]);
+        assert!(db.query(&age_between(50, 60)).is_empty());
+        assert!(db.query(&age_between(40, 20)).is_empty());
+    }
+
+    #[test]
+    fn test_email_domain_is_case_insensitive() {
+        let db = setup_test_db();
+        assert_eq!(
+            names(db.query(&email_domain("EXAMPLE.com"))),
+            vec!["alice", "alicia", "bob"]
+        );
+    }
+
+    #[test]
+    fn test_name_prefix() {
+        let db = setup_test_db();
+        assert_eq!(names(db.query(&name_prefix("al"))), vec!["Alfred", "alice", "alicia"]);
+        assert_eq!(names(db.query(&name_prefix("ALIC"))), vec!["alice", "alicia"]);
+        assert_eq!(names(db.query(&name_prefix(""))).len(), 5);
+        assert!(db.query(&name_prefix("z")).is_empty());
+    }
+
+    #[test]
+    fn test_and_query() {
+        let db = setup_test_db();
+        let query = age_between(20, 30).and(email_domain("example.com"));
+        assert_eq!(names(db.query(&query)), vec!["alice"]);
+    }
+
+    #[test]
+    fn test_or_query() {
+        let db = setup_test_db();
+        let query = name_prefix("b").or(email_domain("other.org"));
+        assert_eq!(names(db.query(&query)), vec!["Alfred", "bob", "carol"]);
+    }
+
+    #[test]
+    fn test_nested_query() {
+        let db = setup_test_db();
+        let query = name_prefix("al")
+            .and(age_between(20, 30).or(age_between(40, 50)))
+            .and(email_domain("example.com"));
+        assert_eq!(names(db.query(&query)), vec!["alice", "alicia"]);
+    }
+
+    #[test]
+    fn test_indexes_follow_updates() {
+        let mut db = setup_test_db();
+        db.update_user("bob", 25, "bob@other.org").unwrap();
+
+        assert_eq!(names(db.query(&email_domain("example.com"))), vec!["alice", "alicia"]);
+        assert_eq!(names(db.query(&age_between(20, 26))), vec!["Alfred", "bob"]);
+        assert!(db.query(&age_between(35, 35)).is_empty());
+    }
+
+    #[test]
+    fn test_indexes_follow_deletes() {
+        let mut db = setup_test_db();
+        assert!(db.delete_user("alice"));
+
+        assert_eq!(names(db.query(&name_prefix("ali"))), vec!["alicia"]);
+        assert_eq!(names(db.query(&age_between(0, 29))), vec!["Alfred"]);
+
+        // Deleting the last user removes every trace from the trie
+        for name in ["alicia", "Alfred", "bob", "carol"] {
+            db.delete_user(name);
+        }
+        assert!(db.indexes.by_name.root.children.is_empty());
+        assert!(db.indexes.by_domain.is_empty());
+        assert!(db.indexes.by_age.is_empty());
+    }
+
+    #[test]
+    fn test_indexes_rebuilt_on_load() {
+        let mut buffer = Vec::new();
+        setup_test_db().write_to(&mut buffer).unwrap();
+        let db = UserDatabase::read_from(buffer.as_slice()).unwrap();
+
+        let query = age_between(20, 30).and(email_domain("other.org"));
+        assert_eq!(names(db.query(&query)), vec!["Alfred", "carol"]);
+    }
+
+    #[test]
+    fn test_search_results_are_sorted() {
+        let db = setup_test_db();
+        assert_eq!(names(db.search_users("l")), vec!["Alfred", "alice", "alicia", "carol"]);
+    }
+}
+
+//------------------------------------------------------
+// PERSISTENCE WITH SCHEMA MIGRATIONS
+//------------------------------------------------------
+
+// The database is saved as a small text format:
+//
+//     userdb 3
+//     1	alice	28	alice@example.com	true
+//     2	bob	35	bob@example.com	false
+//
+// The header carries the schema version the file was written with. Each
+// following line is one user, fields separated by tabs (tabs, newlines and
+// backslashes inside a field are escaped). When an older file is loaded,
+// the migrations below are applied one by one until the records match the
+// current schema.
+
+/// The schema version written by `UserDatabase::save`
+pub const SCHEMA_VERSION: u32 = 3;
+
+const FILE_MAGIC: &str = "userdb";
+
+/// A migration upgrades one record from schema `version` to `version + 1`.
+/// It also gets the position of the record in the file (starting at 0).
+type Migration = fn(Vec<String>, usize) -> Result<Vec<String>, String>;
+
+/// Migrations indexed by the version they upgrade from (index 0 is v1 -> v2)
+const MIGRATIONS: [Migration; 2] = [migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// v1 stored (name, age, email). v2 adds the `active` flag; every user that
+/// existed before the flag was introduced is considered active.
+fn migrate_v1_to_v2(mut fields: Vec<String>, _position: usize) -> Result<Vec<String>, String> {
+    if fields.len() != 3 {
+        return Err(format!("expected 3 fields for schema v1, found {}", fields.len()));
+    }
+    fields.push("true".to_string());
+    Ok(fields)
+}
+
+/// v3 stores the user id from `UserService` in front of the other fields.
+/// Older files had no ids, so users are numbered in file order.
+fn migrate_v2_to_v3(mut fields: Vec<String>, position: usize) -> Result<Vec<String>, String> {
+    if fields.len() != 4 {
+        return Err(format!("expected 4 fields for schema v2, found {}", fields.len()));
+    }
+    fields.insert(0, (position + 1).to_string());
+    Ok(fields)
+}
+
+/// Errors that can happen while saving or loading a database
+#[derive(Debug)]
+pub enum PersistenceError {
+    /// The underlying file could not be read or written
+    Io(io::Error),
+    /// The file does not start with a valid `userdb <version>` header
+    InvalidHeader(String),
+    /// The file was written by a newer program than this one
+    UnsupportedVersion(u32),
+    /// A user record could not be parsed (line numbers start at 1)
+    InvalidRecord { line: usize, message: String },
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(err) => write!(f, "I/O error: {}", err),
+            PersistenceError::InvalidHeader(header) => write!(f, "invalid header: {:?}", header),
+            PersistenceError::UnsupportedVersion(version) => write!(
+                f,
+                "schema version {} is newer than supported version {}",
+                version, SCHEMA_VERSION
+            ),
+            PersistenceError::InvalidRecord { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<io::Error> for PersistenceError {
+    fn from(err: io::Error) -> Self {
+        PersistenceError::Io(err)
+    }
+}
+
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_field(field: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => return Err(format!("unknown escape sequence '\\{}'", other)),
+            None => return Err("dangling '\\' at end of field".to_string()),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Turns a record in the current schema into a `User` and its active flag
+fn user_from_fields(fields: &[String]) -> Result<(User, bool), String> {
+    if fields.len() != 5 {
+        return Err(format!("expected 5 fields, found {}", fields.len()));
+    }
+    let id = fields[0]
+        .parse()
+        .map_err(|_| format!("invalid id '{}'", fields[0]))?;
+    let age = fields[2]
+        .parse()
+        .map_err(|_| format!("invalid age '{}'", fields[2]))?;
+    let active = match fields[4].as_str() {
+        "true" => true,
+        "false" => false,
+        other => return Err(format!("invalid active flag '{}'", other)),
+    };
+    Ok((User::new(id, &fields[1], &fields[3]).with_age(age), active))
+}
+
+impl UserDatabase {
+    /// Writes the database in the current schema.
+    /// Users are written in id order so the output is deterministic.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), PersistenceError> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(writer, "{} {}", FILE_MAGIC, SCHEMA_VERSION)?;
+
+        for user in self.service.list_users() {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                user.id,
+                escape_field(&user.name),
+                user.age,
+                escape_field(&user.email),
+                user.is_active()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a database written with this or any older schema version
+    pub fn read_from<R: io::Read>(reader: R) -> Result<Self, PersistenceError> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let version = match header.split_once(' ') {
+            Some((FILE_MAGIC, version)) => version
+                .parse::<u32>()
+                .ok()
+                .filter(|v| *v >= 1)
+                .ok_or_else(|| PersistenceError::InvalidHeader(header.clone()))?,
+            _ => return Err(PersistenceError::InvalidHeader(header)),
+        };
+        if version > SCHEMA_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(version));
+        }
+
+        let mut db = UserDatabase::new();
+        let mut position = 0;
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let line_number = index + 2;
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |message: String| PersistenceError::InvalidRecord {
+                line: line_number,
+                message,
+            };
+
+            let mut fields = line
+                .split('\t')
+                .map(unescape_field)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid)?;
+            for migration in &MIGRATIONS[(version - 1) as usize..] {
+                fields = migration(fields, position).map_err(invalid)?;
+            }
+            position += 1;
+
+            let (user, active) = user_from_fields(&fields).map_err(invalid)?;
+            let id = user.id;
+            db.insert_user(user).map_err(|e| invalid(e.to_string()))?;
+            if !active {
+                db.service.deactivate_user(id);
+            }
+        }
+        Ok(db)
+    }
+
+    /// Saves the database to a file.
+    /// The data is written to a temporary file first and then renamed over
+    /// the target, so a crash never leaves a half-written database behind.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistenceError> {
+        let path = path.as_ref();
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+
+        self.write_to(File::create(&tmp_name)?)?;
+        fs::rename(&tmp_name, path)?;
+        Ok(())
+    }
+
+    /// Loads a database from a file, migrating it to the current schema
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+        UserDatabase::read_from(File::open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod persistence_tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // A path in the temp directory that is removed when the test ends
+    struct TempPath {
+        path: PathBuf,
+    }
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let file_name = format!("userdb_{}_{}.db", std::process::id(), name);
+            TempPath { path: std::env::temp_dir().join(file_name) }
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    fn setup_db() -> UserDatabase {
+        let mut db = UserDatabase::new();
+        db.create_user("alice", 28, "alice@example.com").unwrap();
+        db.create_user("bob", 35, "bob@example.com").unwrap();
+        db.create_user("tab\tname", 42, "\"odd\\name\"@example.com").unwrap();
+        db.deactivate_user("bob");
+        db
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let file = TempPath::new("round_trip");
+        let db = setup_db();
+
+        db.save(&file.path).unwrap();
+        let loaded = UserDatabase::load(&file.path).unwrap();
+
+        assert_eq!(loaded.count(), 3);
+        for user in db.list_users() {
+            assert_eq!(loaded.get_user(&user.name), Some(user));
+        }
+        assert!(!loaded.get_user("bob").unwrap().is_active());
+    }
+
+    #[test]
+    fn test_saved_file_has_version_header() {
+        let mut buffer = Vec::new();
+        setup_db().write_to(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("userdb 3"));
+        assert_eq!(lines.next(), Some("1\talice\t28\talice@example.com\ttrue"));
+        assert_eq!(lines.next(), Some("2\tbob\t35\tbob@example.com\tfalse"));
+        assert_eq!(lines.next(), Some("3\ttab\\tname\t42\t\"odd\\\\name\"@example.com\ttrue"));
+    }
+
+    #[test]
+    fn test_load_migrates_v1_file() {
+        let v1 = "userdb 1\nalice\t28\talice@example.com\nbob\t35\tbob@example.com\n";
+        let db = UserDatabase::read_from(v1.as_bytes()).unwrap();
+
+        assert_eq!(db.count(), 2);
+        let alice = db.get_user("alice").unwrap();
+        assert_eq!(alice.id, 1);
+        assert_eq!(alice.age, 28);
+        assert!(alice.is_active());
+        assert_eq!(db.get_user("bob").unwrap().id, 2);
+
+        // Saving again upgrades the file to the current version
+        let mut buffer = Vec::new();
+        db.write_to(&mut buffer).unwrap();
+        assert!(String::from_utf8(buffer).unwrap().starts_with("userdb 3\n"));
+    }
+
+    #[test]
+    fn test_load_migrates_v2_file() {
+        let v2 = "userdb 2\nalice\t28\talice@example.com\ttrue\nbob\t35\tbob@example.com\tfalse\n";
+        let db = UserDatabase::read_from(v2.as_bytes()).unwrap();
+
+        let bob = db.get_user("bob").unwrap();
+        assert_eq!(bob.id, 2);
+        assert!(!bob.is_active());
+    }
+
+    #[test]
+    fn test_load_rejects_duplicate_ids() {
+        let data = "userdb 3\n1\talice\t28\talice@example.com\ttrue\n1\tbob\t35\tbob@example.com\ttrue\n";
+        let result = UserDatabase::read_from(data.as_bytes());
+        assert!(matches!(result, Err(PersistenceError::InvalidRecord { line: 3, .. })));
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let result = UserDatabase::read_from("userdb 99\n".as_bytes());
+        assert!(matches!(result, Err(PersistenceError::UnsupportedVersion(99))));
+    }
+
+    #[test]
+    fn test_load_rejects_bad_header() {
+        let cases = vec!["", "users 2", "userdb", "userdb two", "userdb 0"];
+        for header in cases {
+            let result = UserDatabase::read_from(header.as_bytes());
+            assert!(
+                matches!(result, Err(PersistenceError::InvalidHeader(_))),
+                "header {:?} should be rejected",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_reports_bad_record_line() {
+        let data = "userdb 3\n1\talice\t28\talice@example.com\ttrue\n2\tbob\told\tbob@example.com\ttrue\n";
+        match UserDatabase::read_from(data.as_bytes()) {
+            Err(PersistenceError::InvalidRecord { line, message }) => {
+                assert_eq!(line, 3);
+                assert!(message.contains("invalid age"));
+            }
+            _ => panic!("expected an invalid record error"),
+        }
+    }
+
+    #[test]
+    fn test_v1_record_with_wrong_field_count() {
+        let data = "userdb 1\nalice\t28\talice@example.com\ttrue\n";
+        let result = UserDatabase::read_from(data.as_bytes());
+        assert!(matches!(result, Err(PersistenceError::InvalidRecord { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let file = TempPath::new("missing");
+        assert!(matches!(UserDatabase::load(&file.path), Err(PersistenceError::Io(_))));
+    }
+}
+
 //------------------------------------------------------
 // EXERCISES
 //------------------------------------------------------
@@ -478,6 +1218,17 @@ impl UserDatabase {
 // 3. Add appropriate test cases for each operation
 // 4. Handle edge cases (like division by zero)
 
+// `Calculator::evaluate` takes a whole expression such as "2 * (3 + 4)" and
+// hands it to the course-wide calculator module, which parses it with
+// precedence and parentheses and reports every failure as a `MathError`
+// carrying the position it happened at. That's plenty to organize tests
+// around: a group per operator, one for precedence, one for each error.
+#[allow(dead_code)]
+#[path = "../../shared/calculator.rs"]
+mod calculator;
+
+pub use calculator::MathError;
+
 /// A simple calculator that performs basic arithmetic operations.
 pub struct Calculator;
 
@@ -504,6 +1255,12 @@ impl Calculator {
         }
         Ok(a / b)
     }
+
+    /// Evaluates an expression with `i32` arithmetic, reporting overflow
+    /// instead of panicking
+    pub fn evaluate(&self, expression: &str) -> Result<i32, MathError> {
+        calculator::evaluate(expression)
+    }
 }
 
 // TODO: Organize tests for the Calculator struct here
@@ -521,6 +1278,10 @@ mod calculator_tests {
 // 4. Contains at least one digit
 //
 // First, write the tests for each requirement, then implement the validator.
+//
+// Once you're done, chapters/shared/password_policy.rs shows a configurable
+// `PasswordPolicy` that lists every rule a password breaks, with tests that
+// assert on the exact violations rather than a bool.
 
 // TODO: Implement tests for the password validator using TDD
 #[cfg(test)]
@@ -540,49 +1301,15 @@ pub fn validate_password(password: &str) -> bool {
 // that automatically populates the cart with some items
 // and ensures proper cleanup.
 
-/// A shopping cart item
-#[derive(Debug, Clone, PartialEq)]
-pub struct CartItem {
-    pub name: String,
-    pub price: f64,
-    pub quantity: u32,
-}
-
-/// A shopping cart
-pub struct ShoppingCart {
-    items: Vec<CartItem>,
-}
+// The cart lives in the course-wide shared folder, together with its
+// pricing engine (discounts, coupons, tax and itemized receipts) and the
+// fixed-point `Money` type its prices use. Adding an item that is already
+// in the cart merges the quantities.
+#[allow(dead_code)]
+#[path = "../../shared/shopping_cart.rs"]
+mod shopping_cart;
 
-impl ShoppingCart {
-    pub fn new() -> Self {
-        ShoppingCart { items: Vec::new() }
-    }
-    
-    pub fn add_item(&mut self, name: &str, price: f64, quantity: u32) {
-        self.items.push(CartItem {
-            name: name.to_string(),
-            price,
-            quantity,
-        });
-    }
-    
-    pub fn get_items(&self) -> &[CartItem] {
-        &self.items
-    }
-    
-    pub fn remove_item(&mut self, name: &str) -> bool {
-        if let Some(pos) = self.items.iter().position(|item| item.name == name) {
-            self.items.remove(pos);
-            true
-        } else {
-            false
-        }
-    }
-    
-    pub fn total(&self) -> f64 {
-        self.items.iter().map(|item| item.price * item.quantity as f64).sum()
-    }
-}
+pub use shopping_cart::{CartError, CartItem, Currency, Money, MoneyError, ShoppingCart};
 
 // TODO: Create a struct-based test fixture for ShoppingCart
 #[cfg(test)]
@@ -0,0 +1,418 @@
+// Email Addresses
+//
+// A parser for a practical subset of the RFC 5322 `addr-spec` grammar:
+//
+//     addr-spec  = local-part "@" domain
+//     local-part = dot-atom / quoted-string
+//     domain     = label *("." label)
+//
+// Supported:
+// - dot-atom local parts: letters, digits and !#$%&'*+-/=?^_`{|}~,
+//   separated by single dots
+// - quoted local parts: "john doe"@example.com, with \" and \\ escapes
+// - host name domains with at least two labels (RFC 1035 label rules)
+//
+// Not supported: comments, folding whitespace, obsolete syntax, domain
+// literals like [192.0.2.1] and non-ASCII addresses. Those are valid in the
+// RFC but almost never what a sign-up form should accept.
+//
+// This file is a library module, not a program. It is loaded by
+// user_service.rs, or directly with:
+//
+//     #[path = "../shared/email.rs"]
+//     mod email;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest address allowed in an SMTP path (RFC 5321)
+pub const MAX_ADDRESS_LEN: usize = 254;
+/// Longest local part allowed (RFC 5321)
+pub const MAX_LOCAL_PART_LEN: usize = 64;
+/// Longest domain allowed (RFC 1035)
+pub const MAX_DOMAIN_LEN: usize = 253;
+/// Longest domain label allowed (RFC 1035)
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// A syntactically valid email address
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EmailAddress {
+    local_part: String,
+    domain: String,
+    labels: Vec<String>,
+}
+
+/// Why a string is not a valid email address.
+/// Positions are byte offsets into the original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailError {
+    Empty,
+    TooLong { len: usize },
+    MissingAt,
+    EmptyLocalPart,
+    LocalPartTooLong { len: usize },
+    InvalidLocalChar { ch: char, position: usize },
+    LeadingDot { position: usize },
+    TrailingDot { position: usize },
+    ConsecutiveDots { position: usize },
+    UnterminatedQuote,
+    EmptyDomain,
+    DomainTooLong { len: usize },
+    DomainLiteralNotSupported,
+    EmptyLabel { position: usize },
+    LabelTooLong { position: usize, len: usize },
+    InvalidDomainChar { ch: char, position: usize },
+    LabelHyphen { position: usize },
+    SingleLabelDomain,
+    NumericTopLevelDomain,
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "address is empty"),
+            EmailError::TooLong { len } => {
+                write!(f, "address is {} bytes long, the maximum is {}", len, MAX_ADDRESS_LEN)
+            }
+            EmailError::MissingAt => write!(f, "missing '@' between local part and domain"),
+            EmailError::EmptyLocalPart => write!(f, "nothing before the '@'"),
+            EmailError::LocalPartTooLong { len } => write!(
+                f,
+                "local part is {} bytes long, the maximum is {}",
+                len, MAX_LOCAL_PART_LEN
+            ),
+            EmailError::InvalidLocalChar { ch, position } => {
+                write!(f, "character {:?} at position {} is not allowed in the local part", ch, position)
+            }
+            EmailError::LeadingDot { position } => {
+                write!(f, "'.' at position {} cannot start a part", position)
+            }
+            EmailError::TrailingDot { position } => {
+                write!(f, "'.' at position {} cannot end a part", position)
+            }
+            EmailError::ConsecutiveDots { position } => {
+                write!(f, "two dots in a row at position {}", position)
+            }
+            EmailError::UnterminatedQuote => write!(f, "quoted local part is not closed"),
+            EmailError::EmptyDomain => write!(f, "nothing after the '@'"),
+            EmailError::DomainTooLong { len } => {
+                write!(f, "domain is {} bytes long, the maximum is {}", len, MAX_DOMAIN_LEN)
+            }
+            EmailError::DomainLiteralNotSupported => {
+                write!(f, "domain literals like [192.0.2.1] are not supported")
+            }
+            EmailError::EmptyLabel { position } => {
+                write!(f, "empty domain label at position {}", position)
+            }
+            EmailError::LabelTooLong { position, len } => write!(
+                f,
+                "domain label at position {} is {} bytes long, the maximum is {}",
+                position, len, MAX_LABEL_LEN
+            ),
+            EmailError::InvalidDomainChar { ch, position } => {
+                write!(f, "character {:?} at position {} is not allowed in the domain", ch, position)
+            }
+            EmailError::LabelHyphen { position } => write!(
+                f,
+                "domain label at position {} starts or ends with '-'",
+                position
+            ),
+            EmailError::SingleLabelDomain => write!(f, "domain needs at least two labels"),
+            EmailError::NumericTopLevelDomain => write!(f, "top-level domain cannot be all digits"),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+// `atext` from RFC 5322 section 3.2.3
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+}
+
+// Validates a dot-atom local part starting at byte `offset` of the address
+fn check_dot_atom(local: &str, offset: usize) -> Result<(), EmailError> {
+    let mut previous_dot = false;
+    for (i, c) in local.char_indices() {
+        let position = offset + i;
+        if c == '.' {
+            if i == 0 {
+                return Err(EmailError::LeadingDot { position });
+            }
+            if previous_dot {
+                return Err(EmailError::ConsecutiveDots { position });
+            }
+            previous_dot = true;
+        } else if is_atext(c) {
+            previous_dot = false;
+        } else {
+            return Err(EmailError::InvalidLocalChar { ch: c, position });
+        }
+    }
+    if previous_dot {
+        return Err(EmailError::TrailingDot { position: offset + local.len() - 1 });
+    }
+    Ok(())
+}
+
+// Splits `"quoted"@rest` into the quoted local part (with quotes) and the
+// position of the '@'. Quoted strings may contain '@'.
+fn split_quoted(address: &str) -> Result<(&str, usize), EmailError> {
+    let mut escaped = false;
+    for (i, c) in address.char_indices().skip(1) {
+        if escaped {
+            if !(' '..='~').contains(&c) {
+                return Err(EmailError::InvalidLocalChar { ch: c, position: i });
+            }
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            let end = i + 1;
+            return match address[end..].chars().next() {
+                Some('@') => Ok((&address[..end], end)),
+                Some(other) => Err(EmailError::InvalidLocalChar { ch: other, position: end }),
+                None => Err(EmailError::MissingAt),
+            };
+        } else if !(' '..='~').contains(&c) {
+            // Printable ASCII and space only; no control characters
+            return Err(EmailError::InvalidLocalChar { ch: c, position: i });
+        }
+    }
+    Err(EmailError::UnterminatedQuote)
+}
+
+// Validates the domain starting at byte `offset` and returns its labels
+fn parse_domain(domain: &str, offset: usize) -> Result<Vec<String>, EmailError> {
+    if domain.is_empty() {
+        return Err(EmailError::EmptyDomain);
+    }
+    if domain.starts_with('[') {
+        return Err(EmailError::DomainLiteralNotSupported);
+    }
+    if domain.len() > MAX_DOMAIN_LEN {
+        return Err(EmailError::DomainTooLong { len: domain.len() });
+    }
+
+    let mut labels = Vec::new();
+    let mut start = 0;
+    for label in domain.split('.') {
+        let position = offset + start;
+        if label.is_empty() {
+            return Err(EmailError::EmptyLabel { position });
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(EmailError::LabelTooLong { position, len: label.len() });
+        }
+        if let Some((i, ch)) = label
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '-'))
+        {
+            return Err(EmailError::InvalidDomainChar { ch, position: position + i });
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(EmailError::LabelHyphen { position });
+        }
+        labels.push(label.to_string());
+        start += label.len() + 1;
+    }
+
+    if labels.len() < 2 {
+        return Err(EmailError::SingleLabelDomain);
+    }
+    if labels[labels.len() - 1].chars().all(|c| c.is_ascii_digit()) {
+        return Err(EmailError::NumericTopLevelDomain);
+    }
+    Ok(labels)
+}
+
+impl EmailAddress {
+    /// Parses and validates an email address
+    pub fn parse(address: &str) -> Result<Self, EmailError> {
+        if address.is_empty() {
+            return Err(EmailError::Empty);
+        }
+        if address.len() > MAX_ADDRESS_LEN {
+            return Err(EmailError::TooLong { len: address.len() });
+        }
+
+        let (local, at) = if address.starts_with('"') {
+            split_quoted(address)?
+        } else {
+            // An unquoted local part cannot contain '@', so the first one
+            // is the separator and any later one is reported by the domain
+            let at = address.find('@').ok_or(EmailError::MissingAt)?;
+            let local = &address[..at];
+            if local.is_empty() {
+                return Err(EmailError::EmptyLocalPart);
+            }
+            check_dot_atom(local, 0)?;
+            (local, at)
+        };
+
+        if local.len() > MAX_LOCAL_PART_LEN {
+            return Err(EmailError::LocalPartTooLong { len: local.len() });
+        }
+
+        let labels = parse_domain(&address[at + 1..], at + 1)?;
+        Ok(EmailAddress {
+            local_part: local.to_string(),
+            domain: address[at + 1..].to_string(),
+            labels,
+        })
+    }
+
+    /// Returns true if `address` parses
+    pub fn is_valid(address: &str) -> bool {
+        EmailAddress::parse(address).is_ok()
+    }
+
+    /// The part before the '@', including quotes if it was quoted
+    pub fn local_part(&self) -> &str {
+        &self.local_part
+    }
+
+    /// The part after the '@', as written
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// The domain split on '.', e.g. ["mail", "example", "com"]
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// The domain in lowercase. Domains are case-insensitive; local parts
+    /// are not, so they are left alone.
+    pub fn normalized_domain(&self) -> String {
+        self.domain.to_ascii_lowercase()
+    }
+}
+
+impl FromStr for EmailAddress {
+    type Err = EmailError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EmailAddress::parse(s)
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.local_part, self.domain)
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformance_valid_addresses() {
+        // Test cases: (address, local part, labels)
+        let test_cases = vec![
+            ("alice@example.com", "alice", vec!["example", "com"]),
+            ("first.last@example.com", "first.last", vec!["example", "com"]),
+            ("user+tag@mail.example.org", "user+tag", vec!["mail", "example", "org"]),
+            ("x@example.io", "x", vec!["example", "io"]),
+            ("o'brien@example.ie", "o'brien", vec!["example", "ie"]),
+            ("!#$%&'*+-/=?^_`{|}~@example.com", "!#$%&'*+-/=?^_`{|}~", vec!["example", "com"]),
+            ("\"john doe\"@example.com", "\"john doe\"", vec!["example", "com"]),
+            ("\"a@b\"@example.com", "\"a@b\"", vec!["example", "com"]),
+            ("\"quote\\\"inside\"@example.com", "\"quote\\\"inside\"", vec!["example", "com"]),
+            ("bob@EXAMPLE.Com", "bob", vec!["EXAMPLE", "Com"]),
+            ("a@x-y.example.com", "a", vec!["x-y", "example", "com"]),
+            ("a@123.example", "a", vec!["123", "example"]),
+            ("a@xn--bcher-kva.example", "a", vec!["xn--bcher-kva", "example"]),
+        ];
+
+        for (address, local, labels) in test_cases {
+            let parsed = EmailAddress::parse(address)
+                .unwrap_or_else(|e| panic!("'{}' should be valid: {}", address, e));
+            assert_eq!(parsed.local_part(), local, "local part of '{}'", address);
+            assert_eq!(parsed.labels(), labels.as_slice(), "labels of '{}'", address);
+            assert_eq!(parsed.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn test_conformance_invalid_addresses() {
+        // Test cases: (address, expected error)
+        let test_cases = vec![
+            ("", EmailError::Empty),
+            ("invalid-email", EmailError::MissingAt),
+            ("@example.com", EmailError::EmptyLocalPart),
+            ("alice@", EmailError::EmptyDomain),
+            ("bob@example", EmailError::SingleLabelDomain),
+            (".alice@example.com", EmailError::LeadingDot { position: 0 }),
+            ("alice.@example.com", EmailError::TrailingDot { position: 5 }),
+            ("al..ice@example.com", EmailError::ConsecutiveDots { position: 3 }),
+            ("al ice@example.com", EmailError::InvalidLocalChar { ch: ' ', position: 2 }),
+            ("a\"b@example.com", EmailError::InvalidLocalChar { ch: '"', position: 1 }),
+            ("\"open@example.com", EmailError::UnterminatedQuote),
+            ("\"quoted\"x@example.com", EmailError::InvalidLocalChar { ch: 'x', position: 8 }),
+            ("\"quoted\"", EmailError::MissingAt),
+            ("a@b@example.com", EmailError::InvalidDomainChar { ch: '@', position: 3 }),
+            ("alice@exa mple.com", EmailError::InvalidDomainChar { ch: ' ', position: 9 }),
+            ("alice@example..com", EmailError::EmptyLabel { position: 14 }),
+            ("alice@.example.com", EmailError::EmptyLabel { position: 6 }),
+            ("alice@example.com.", EmailError::EmptyLabel { position: 18 }),
+            ("alice@-example.com", EmailError::LabelHyphen { position: 6 }),
+            ("alice@example-.com", EmailError::LabelHyphen { position: 6 }),
+            ("alice@example.123", EmailError::NumericTopLevelDomain),
+            ("alice@[192.0.2.1]", EmailError::DomainLiteralNotSupported),
+            ("josé@example.com", EmailError::InvalidLocalChar { ch: 'é', position: 3 }),
+        ];
+
+        for (address, expected) in test_cases {
+            assert_eq!(
+                EmailAddress::parse(address),
+                Err(expected),
+                "Email '{}' validation failed",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_length_limits() {
+        let local = "a".repeat(MAX_LOCAL_PART_LEN);
+        assert!(EmailAddress::is_valid(&format!("{}@example.com", local)));
+        assert_eq!(
+            EmailAddress::parse(&format!("{}a@example.com", local)),
+            Err(EmailError::LocalPartTooLong { len: 65 })
+        );
+
+        let label = "b".repeat(MAX_LABEL_LEN);
+        assert!(EmailAddress::is_valid(&format!("a@{}.com", label)));
+        assert_eq!(
+            EmailAddress::parse(&format!("a@{}b.com", label)),
+            Err(EmailError::LabelTooLong { position: 2, len: 64 })
+        );
+
+        let long_domain = format!("{}.{}.{}.{}.com", label, label, label, label);
+        assert!(matches!(
+            EmailAddress::parse(&format!("a@{}", long_domain)),
+            Err(EmailError::TooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let email: EmailAddress = "Alice.Smith@Mail.Example.COM".parse().unwrap();
+        assert_eq!(email.local_part(), "Alice.Smith");
+        assert_eq!(email.domain(), "Mail.Example.COM");
+        assert_eq!(email.normalized_domain(), "mail.example.com");
+        assert_eq!(email.labels().len(), 3);
+    }
+
+    #[test]
+    fn test_error_messages_mention_position() {
+        let error = EmailAddress::parse("al..ice@example.com").unwrap_err();
+        assert_eq!(error.to_string(), "two dots in a row at position 3");
+    }
+}
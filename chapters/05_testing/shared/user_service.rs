@@ -0,0 +1,452 @@
+// User Service
+//
+// The single user store shared by the testing chapter. It replaces the two
+// stores that used to live side by side: `user_manager::UserManager` from
+// 02_integration_testing (users keyed by id) and `UserDatabase` from
+// 04_test_organization (users keyed by name).
+//
+// This file is a library module, not a program. Chapter files load it with:
+//
+//     #[path = "../shared/user_service.rs"]
+//     mod user_service;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+// Email addresses are checked by the shared parser in email.rs
+#[path = "email.rs"]
+pub mod email;
+
+pub use email::{EmailAddress, EmailError};
+
+//------------------------------------------------------
+// USER
+//------------------------------------------------------
+
+/// A user of the system
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+    pub age: u32,
+    pub email: String,
+    active: bool, // Note: private field, only the service can deactivate a user
+}
+
+impl User {
+    pub fn new(id: u64, name: &str, email: &str) -> Self {
+        User {
+            id,
+            name: name.to_string(),
+            age: 0,
+            email: email.to_string(),
+            active: true,
+        }
+    }
+
+    /// Sets the user's age
+    pub fn with_age(mut self, age: u32) -> Self {
+        self.age = age;
+        self
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    // Private helper method
+    fn validate_email(email: &str) -> Result<EmailAddress, UserError> {
+        EmailAddress::parse(email).map_err(|reason| UserError::InvalidEmail {
+            email: email.to_string(),
+            reason,
+        })
+    }
+
+    /// The parsed email address.
+    /// Every stored user has passed validation, so this always succeeds
+    /// for users returned by the service.
+    pub fn email_address(&self) -> Result<EmailAddress, EmailError> {
+        EmailAddress::parse(&self.email)
+    }
+}
+
+//------------------------------------------------------
+// EVENTS AND ERRORS
+//------------------------------------------------------
+
+/// Something that happened to a user.
+/// Each event carries a snapshot of the user at that moment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserEvent {
+    UserCreated(User),
+    UserDeactivated(User),
+    UserDeleted(User),
+}
+
+/// Why a user could not be stored
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserError {
+    DuplicateId(u64),
+    DuplicateName(String),
+    InvalidName,
+    InvalidEmail { email: String, reason: EmailError },
+    NotFound(u64),
+    /// The id is the largest `u64`, which would leave no id for the next user
+    IdOutOfRange(u64),
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserError::DuplicateId(id) => write!(f, "User with ID {} already exists", id),
+            UserError::DuplicateName(name) => write!(f, "User '{}' already exists", name),
+            UserError::InvalidName => write!(f, "User name must not be empty"),
+            UserError::InvalidEmail { email, reason } => {
+                write!(f, "Invalid email format: '{}' ({})", email, reason)
+            }
+            UserError::NotFound(id) => write!(f, "User with ID {} not found", id),
+            UserError::IdOutOfRange(id) => write!(f, "User ID {} is too large", id),
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
+
+//------------------------------------------------------
+// USER SERVICE
+//------------------------------------------------------
+
+/// Stores users by id, with a secondary lookup by name.
+/// Names are unique, like ids.
+pub struct UserService {
+    users: BTreeMap<u64, User>,
+    ids_by_name: HashMap<String, u64>,
+    next_id: u64,
+    subscribers: Vec<Sender<UserEvent>>,
+}
+
+impl UserService {
+    pub fn new() -> Self {
+        UserService {
+            users: BTreeMap::new(),
+            ids_by_name: HashMap::new(),
+            next_id: 1,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Returns a receiver that gets every event from now on.
+    /// Dropping the receiver unsubscribes.
+    pub fn subscribe(&mut self) -> Receiver<UserEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    fn publish(&mut self, event: UserEvent) {
+        // Subscribers whose receiver was dropped are forgotten
+        self.subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    fn validate(name: &str, email: &str) -> Result<(), UserError> {
+        if name.trim().is_empty() {
+            return Err(UserError::InvalidName);
+        }
+        User::validate_email(email)?;
+        Ok(())
+    }
+
+    /// Adds a user with the id it already has
+    pub fn add_user(&mut self, user: User) -> Result<u64, UserError> {
+        // Check if user with this ID or name already exists
+        if self.users.contains_key(&user.id) {
+            return Err(UserError::DuplicateId(user.id));
+        }
+        if self.ids_by_name.contains_key(&user.name) {
+            return Err(UserError::DuplicateName(user.name));
+        }
+        UserService::validate(&user.name, &user.email)?;
+
+        let id = user.id;
+        let after = id.checked_add(1).ok_or(UserError::IdOutOfRange(id))?;
+        self.next_id = self.next_id.max(after);
+        self.ids_by_name.insert(user.name.clone(), id);
+        self.users.insert(id, user.clone());
+        self.publish(UserEvent::UserCreated(user));
+        Ok(id)
+    }
+
+    /// Creates a user with the next free id and returns that id
+    pub fn create_user(&mut self, name: &str, age: u32, email: &str) -> Result<u64, UserError> {
+        let user = User::new(self.next_id, name, email).with_age(age);
+        self.add_user(user)
+    }
+
+    pub fn get_user(&self, id: u64) -> Option<&User> {
+        self.users.get(&id)
+    }
+
+    pub fn get_user_by_name(&self, name: &str) -> Option<&User> {
+        self.ids_by_name.get(name).and_then(|id| self.users.get(id))
+    }
+
+    /// Changes a user's age and email
+    pub fn update_user(&mut self, id: u64, age: u32, email: &str) -> Result<(), UserError> {
+        User::validate_email(email)?;
+        let user = self.users.get_mut(&id).ok_or(UserError::NotFound(id))?;
+        user.age = age;
+        user.email = email.to_string();
+        Ok(())
+    }
+
+    /// Marks a user as inactive.
+    /// Returns false if there is no such user or it was already inactive.
+    pub fn deactivate_user(&mut self, id: u64) -> bool {
+        let user = match self.users.get_mut(&id) {
+            Some(user) if user.active => user,
+            _ => return false,
+        };
+        user.active = false;
+        let snapshot = user.clone();
+        self.publish(UserEvent::UserDeactivated(snapshot));
+        true
+    }
+
+    /// Removes a user and returns it
+    pub fn remove_user(&mut self, id: u64) -> Option<User> {
+        let user = self.users.remove(&id)?;
+        self.ids_by_name.remove(&user.name);
+        self.publish(UserEvent::UserDeleted(user.clone()));
+        Some(user)
+    }
+
+    pub fn delete_user(&mut self, id: u64) -> bool {
+        self.remove_user(id).is_some()
+    }
+
+    pub fn count(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Lists all users, ordered by id
+    pub fn list_users(&self) -> Vec<&User> {
+        self.users.values().collect()
+    }
+}
+
+impl Default for UserService {
+    fn default() -> Self {
+        UserService::new()
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+// These started out as the example integration tests for `UserManager`
+// in 02_integration_testing and now run against the shared service.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_creation() {
+        let user = User::new(1, "Alice", "alice@example.com");
+        assert_eq!(user.id, 1);
+        assert_eq!(user.name, "Alice");
+        assert_eq!(user.email, "alice@example.com");
+        assert!(user.is_active());
+    }
+
+    #[test]
+    fn test_add_user() {
+        let mut service = UserService::new();
+        let user = User::new(1, "Alice", "alice@example.com");
+
+        assert_eq!(service.count(), 0);
+        assert_eq!(service.add_user(user), Ok(1));
+        assert_eq!(service.count(), 1);
+    }
+
+    #[test]
+    fn test_add_duplicate_user() {
+        let mut service = UserService::new();
+        let user1 = User::new(1, "Alice", "alice@example.com");
+        let user2 = User::new(1, "Bob", "bob@example.com"); // Same ID
+        let user3 = User::new(2, "Alice", "alice2@example.com"); // Same name
+
+        assert!(service.add_user(user1).is_ok());
+        assert_eq!(service.add_user(user2), Err(UserError::DuplicateId(1)));
+        assert_eq!(
+            service.add_user(user3),
+            Err(UserError::DuplicateName("Alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_largest_id_is_rejected() {
+        let mut service = UserService::new();
+        let user = User::new(u64::MAX, "Alice", "alice@example.com");
+        assert_eq!(service.add_user(user), Err(UserError::IdOutOfRange(u64::MAX)));
+        assert_eq!(service.count(), 0);
+
+        let user = User::new(u64::MAX - 1, "Alice", "alice@example.com");
+        assert_eq!(service.add_user(user), Ok(u64::MAX - 1));
+    }
+
+    #[test]
+    fn test_add_invalid_user() {
+        let mut service = UserService::new();
+
+        let user = User::new(1, "Alice", "invalid-email"); // Invalid email
+        assert!(matches!(
+            service.add_user(user),
+            Err(UserError::InvalidEmail { reason: EmailError::MissingAt, .. })
+        ));
+
+        // A domain without a dot is rejected too
+        let user = User::new(1, "Bob", "bob@example");
+        assert!(matches!(
+            service.add_user(user),
+            Err(UserError::InvalidEmail { reason: EmailError::SingleLabelDomain, .. })
+        ));
+
+        let user = User::new(2, "  ", "blank@example.com");
+        assert_eq!(service.add_user(user), Err(UserError::InvalidName));
+        assert_eq!(service.count(), 0);
+    }
+
+    #[test]
+    fn test_get_user_by_id_and_name() {
+        let mut service = UserService::new();
+        service.add_user(User::new(7, "Alice", "alice@example.com")).unwrap();
+
+        let by_id = service.get_user(7).unwrap();
+        assert_eq!(by_id.name, "Alice");
+        assert_eq!(by_id.email, "alice@example.com");
+        assert_eq!(by_id.email_address().unwrap().domain(), "example.com");
+
+        assert_eq!(service.get_user_by_name("Alice"), Some(by_id));
+        assert!(service.get_user_by_name("alice").is_none());
+        assert!(service.get_user(8).is_none());
+    }
+
+    #[test]
+    fn test_create_user_generates_ids() {
+        let mut service = UserService::new();
+        assert_eq!(service.create_user("alice", 28, "alice@example.com"), Ok(1));
+        service.add_user(User::new(10, "bob", "bob@example.com")).unwrap();
+        // Generated ids never collide with ids that were added explicitly
+        assert_eq!(service.create_user("carol", 40, "carol@example.com"), Ok(11));
+        assert_eq!(service.get_user(11).unwrap().age, 40);
+    }
+
+    #[test]
+    fn test_update_user() {
+        let mut service = UserService::new();
+        let id = service.create_user("alice", 28, "alice@example.com").unwrap();
+
+        assert!(service.update_user(id, 29, "alice@new.example.com").is_ok());
+        assert!(service.update_user(id, 30, "invalid").is_err());
+        assert!(service.update_user(id, 30, "alice@@example.com").is_err());
+        assert_eq!(service.update_user(99, 30, "x@example.com"), Err(UserError::NotFound(99)));
+
+        let user = service.get_user(id).unwrap();
+        assert_eq!(user.age, 29);
+        assert_eq!(user.email, "alice@new.example.com");
+    }
+
+    #[test]
+    fn test_user_deactivation() {
+        let mut service = UserService::new();
+        let id = service.create_user("alice", 28, "alice@example.com").unwrap();
+
+        assert!(service.deactivate_user(id));
+        assert!(!service.get_user(id).unwrap().is_active());
+        // Deactivating twice, or a missing user, does nothing
+        assert!(!service.deactivate_user(id));
+        assert!(!service.deactivate_user(42));
+    }
+
+    #[test]
+    fn test_delete_user() {
+        let mut service = UserService::new();
+        service.add_user(User::new(1, "Alice", "alice@example.com")).unwrap();
+        assert_eq!(service.count(), 1);
+
+        assert!(service.delete_user(1));
+        assert_eq!(service.count(), 0);
+        assert!(service.get_user_by_name("Alice").is_none());
+
+        // Try to delete non-existent user
+        assert!(!service.delete_user(1));
+
+        // The name is free again
+        assert!(service.add_user(User::new(2, "Alice", "alice@example.com")).is_ok());
+    }
+
+    #[test]
+    fn test_list_users_ordered_by_id() {
+        let mut service = UserService::new();
+        for id in [3, 1, 2] {
+            let user = User::new(id, &format!("User {}", id), &format!("user{}@example.com", id));
+            service.add_user(user).unwrap();
+        }
+        let ids: Vec<u64> = service.list_users().iter().map(|u| u.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_events_are_published_in_order() {
+        let mut service = UserService::new();
+        let events = service.subscribe();
+
+        let id = service.create_user("alice", 28, "alice@example.com").unwrap();
+        service.deactivate_user(id);
+        service.delete_user(id);
+
+        let received: Vec<UserEvent> = events.try_iter().collect();
+        assert_eq!(received.len(), 3);
+        assert!(matches!(&received[0], UserEvent::UserCreated(u) if u.name == "alice" && u.is_active()));
+        assert!(matches!(&received[1], UserEvent::UserDeactivated(u) if !u.is_active()));
+        assert!(matches!(&received[2], UserEvent::UserDeleted(u) if u.id == id));
+    }
+
+    #[test]
+    fn test_failed_operations_publish_nothing() {
+        let mut service = UserService::new();
+        let events = service.subscribe();
+
+        let _ = service.create_user("alice", 28, "invalid");
+        service.deactivate_user(1);
+        service.delete_user(1);
+
+        assert_eq!(events.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_multiple_subscribers() {
+        let mut service = UserService::new();
+        let first = service.subscribe();
+        service.create_user("alice", 28, "alice@example.com").unwrap();
+
+        // A late subscriber only sees what happens after it subscribed
+        let second = service.subscribe();
+        service.create_user("bob", 35, "bob@example.com").unwrap();
+
+        assert_eq!(first.try_iter().count(), 2);
+        assert_eq!(second.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_dropped_subscriber_is_removed() {
+        let mut service = UserService::new();
+        let events = service.subscribe();
+        drop(events);
+
+        service.create_user("alice", 28, "alice@example.com").unwrap();
+        assert!(service.subscribers.is_empty());
+    }
+}
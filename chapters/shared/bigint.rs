@@ -0,0 +1,909 @@
+// Big Integers
+//
+// Integers with no upper limit. Every primitive integer type has a fixed
+// size, so arithmetic on it can overflow: `u64` holds factorials only up
+// to 20!, and `u32` can't hold the sum 1 + 2 + ... + 100,000. These types
+// grow as needed instead:
+//
+// - `BigUint` is a non-negative integer stored as a vector of 32-bit
+//   "limbs", least significant first, so it's a number in base 2^32. The
+//   arithmetic is the pencil-and-paper kind: add and subtract limb by limb
+//   with a carry, multiply every limb by every limb, and long division.
+// - `BigInt` is a `BigUint` plus a sign. Division truncates toward zero
+//   and the remainder takes the sign of the dividend, as with `i64`.
+//
+// Both support `+ - * / %` (on owned values and references), `pow`,
+// `div_rem`, comparisons, `Sum`/`Product` over iterators, and conversion
+// to and from text in any radix from 2 to 36. Like the primitive types,
+// dividing by zero panics and subtracting a larger `BigUint` from a
+// smaller one panics; `checked_sub` and `checked_div_rem` return `None`
+// instead. The num-bigint crate is the full-featured, much faster version.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+const LIMB_BITS: u32 = 32;
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,
+    /// A character that isn't a digit in the radix; `position` counts
+    /// characters, not bytes
+    InvalidDigit { character: char, position: usize },
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "no digits given"),
+            ParseBigIntError::InvalidDigit { character, position } => {
+                write!(f, "'{}' at position {} is not a valid digit", character, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+//------------------------------------------------------
+// BIGUINT
+//------------------------------------------------------
+
+/// A non-negative integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    // Least significant limb first, with no zero limbs at the end, so
+    // every number has exactly one representation and zero is empty
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number of bits needed to write the number; 0 for zero
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => {
+                (self.limbs.len() as u64 - 1) * LIMB_BITS as u64 + (LIMB_BITS - top.leading_zeros()) as u64
+            }
+            None => 0,
+        }
+    }
+
+    fn bit(&self, index: u64) -> bool {
+        let limb = (index / LIMB_BITS as u64) as usize;
+        let shift = index % LIMB_BITS as u64;
+        self.limbs.get(limb).is_some_and(|l| (l >> shift) & 1 == 1)
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|value| u64::try_from(value).ok())
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0u128, |value, &limb| (value << LIMB_BITS) | limb as u128))
+    }
+
+    fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+        let mut result = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0u64;
+        for (i, &limb) in long.iter().enumerate() {
+            let sum = limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+            result.push(sum as u32);
+            carry = sum >> LIMB_BITS;
+        }
+        if carry > 0 {
+            result.push(carry as u32);
+        }
+        result
+    }
+
+    // a - b, which must not go below zero
+    fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in a.iter().enumerate() {
+            let mut difference = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if difference < 0 {
+                difference += 1 << LIMB_BITS;
+                borrow = 1;
+            }
+            result.push(difference as u32);
+        }
+        debug_assert_eq!(borrow, 0, "subtraction went below zero");
+        result
+    }
+
+    /// `self - other`, or `None` if `other` is larger
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            return None;
+        }
+        Some(BigUint::from_limbs(BigUint::sub_magnitudes(&self.limbs, &other.limbs)))
+    }
+
+    fn mul_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+        let mut result = vec![0u32; a.len() + b.len()];
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &y) in b.iter().enumerate() {
+                // Can't overflow: (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1
+                let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+                result[i + j] = product as u32;
+                carry = product >> LIMB_BITS;
+            }
+            result[i + b.len()] = carry as u32;
+        }
+        result
+    }
+
+    /// Divides by a single limb, which is all converting to text needs
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        assert!(divisor != 0, "attempt to divide by zero");
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << LIMB_BITS) | limb as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (BigUint::from_limbs(quotient), remainder as u32)
+    }
+
+    /// The quotient and remainder, or `None` when dividing by zero
+    pub fn checked_div_rem(&self, divisor: &BigUint) -> Option<(BigUint, BigUint)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if self < divisor {
+            return Some((BigUint::zero(), self.clone()));
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = self.div_rem_small(divisor.limbs[0]);
+            return Some((quotient, BigUint::from(remainder)));
+        }
+
+        // Long division in base 2: bring down one bit at a time, and
+        // subtract the divisor whenever the remainder is big enough
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+        for index in (0..self.bits()).rev() {
+            remainder = &remainder + &remainder;
+            if self.bit(index) {
+                remainder += BigUint::one();
+            }
+            if remainder >= *divisor {
+                remainder -= divisor;
+                quotient[(index / LIMB_BITS as u64) as usize] |= 1 << (index % LIMB_BITS as u64);
+            }
+        }
+        Some((BigUint::from_limbs(quotient), remainder))
+    }
+
+    /// The quotient and remainder. Panics when dividing by zero.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        self.checked_div_rem(divisor).expect("attempt to divide by zero")
+    }
+
+    /// Raises to a power by repeated squaring: 2^100 takes 7 squarings
+    /// and 2 multiplications instead of 99 multiplications
+    pub fn pow(&self, mut exponent: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut result = BigUint::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Writes the number in a radix from 2 to 36, using lowercase letters
+    /// for digits above 9. Panics for any other radix.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36, got {}", radix);
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // Peel off as many digits as fit in one limb per division
+        let mut chunk_digits = 1;
+        let mut chunk = radix;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            chunk_digits += 1;
+        }
+
+        let mut digits = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, mut remainder) = rest.div_rem_small(chunk);
+            for _ in 0..chunk_digits {
+                digits.push(std::char::from_digit(remainder % radix, radix).unwrap_or('?'));
+                remainder /= radix;
+                // The leading chunk stops at its last non-zero digit
+                if quotient.is_zero() && remainder == 0 {
+                    break;
+                }
+            }
+            rest = quotient;
+        }
+        digits.iter().rev().collect()
+    }
+
+    /// Reads a number in a radix from 2 to 36; letters may be either case.
+    /// Panics for any other radix.
+    pub fn from_str_radix(text: &str, radix: u32) -> Result<BigUint, ParseBigIntError> {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36, got {}", radix);
+        if text.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        let mut value = BigUint::zero();
+        let radix_big = BigUint::from(radix);
+        for (position, character) in text.chars().enumerate() {
+            let digit = character
+                .to_digit(radix)
+                .ok_or(ParseBigIntError::InvalidDigit { character, position })?;
+            value = &value * &radix_big + BigUint::from(digit);
+        }
+        Ok(value)
+    }
+}
+
+macro_rules! biguint_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigUint {
+            fn from(value: $t) -> Self {
+                let mut value = value as u128;
+                let mut limbs = Vec::new();
+                while value > 0 {
+                    limbs.push(value as u32);
+                    value >>= LIMB_BITS;
+                }
+                BigUint { limbs }
+            }
+        }
+    )*};
+}
+
+biguint_from!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // No zero limbs at the end, so more limbs means a larger number
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl fmt::Binary for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &self.to_str_radix(2))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        BigUint::from_str_radix(text, 10)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(BigUint::add_magnitudes(&self.limbs, &other.limbs))
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(BigUint::mul_magnitudes(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+// The operators above take references, so that `&a + &b` doesn't use up
+// `a` and `b`. This fills in the owned combinations (`a + b`, `a + &b`,
+// `&a + b`) and the assigning forms (`a += b`, `a += &b`) from them.
+macro_rules! forward_ops {
+    ($t:ty; $($op:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl $op<$t> for $t {
+            type Output = $t;
+            fn $method(self, other: $t) -> $t {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $op<&$t> for $t {
+            type Output = $t;
+            fn $method(self, other: &$t) -> $t {
+                (&self).$method(other)
+            }
+        }
+
+        impl $op<$t> for &$t {
+            type Output = $t;
+            fn $method(self, other: $t) -> $t {
+                self.$method(&other)
+            }
+        }
+
+        impl $assign<$t> for $t {
+            fn $assign_method(&mut self, other: $t) {
+                *self = (&*self).$method(&other);
+            }
+        }
+
+        impl $assign<&$t> for $t {
+            fn $assign_method(&mut self, other: &$t) {
+                *self = (&*self).$method(other);
+            }
+        }
+    )*};
+}
+
+forward_ops!(BigUint; Add add AddAssign add_assign, Sub sub SubAssign sub_assign, Mul mul MulAssign mul_assign);
+forward_ops!(BigInt; Add add AddAssign add_assign, Sub sub SubAssign sub_assign, Mul mul MulAssign mul_assign);
+
+macro_rules! forward_div_rem {
+    ($($t:ty),*) => {$(
+        impl Div<$t> for $t {
+            type Output = $t;
+            fn div(self, other: $t) -> $t {
+                &self / &other
+            }
+        }
+
+        impl Rem<$t> for $t {
+            type Output = $t;
+            fn rem(self, other: $t) -> $t {
+                &self % &other
+            }
+        }
+    )*};
+}
+
+forward_div_rem!(BigUint, BigInt);
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |total, value| total + value)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |total, value| total * value)
+    }
+}
+
+//------------------------------------------------------
+// BIGINT
+//------------------------------------------------------
+
+/// A signed integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    // Zero is never negative, so it has one representation
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(BigUint::one())
+    }
+
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        BigInt { negative: negative && !magnitude.is_zero(), magnitude }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The absolute value as a `BigUint`
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from(self.magnitude.clone())
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        self.to_i128().and_then(|value| i64::try_from(value).ok())
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            // i128::MIN has no positive counterpart, so go through 0 - x
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// Quotient rounded toward zero, and a remainder with the sign of
+    /// `self`: -7 divided by 2 is -3 remainder -1. Panics when dividing
+    /// by zero.
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        let (quotient, remainder) = self.magnitude.div_rem(&divisor.magnitude);
+        (
+            BigInt::from_parts(self.negative != divisor.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        )
+    }
+
+    pub fn pow(&self, exponent: u32) -> BigInt {
+        BigInt::from_parts(self.negative && exponent % 2 == 1, self.magnitude.pow(exponent))
+    }
+
+    /// Like `BigUint::to_str_radix`, with a leading '-' when negative
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.magnitude.to_str_radix(radix);
+        if self.negative {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    /// Like `BigUint::from_str_radix`, allowing a leading '+' or '-'
+    pub fn from_str_radix(text: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        // Positions in errors count from the start of `text`, sign included
+        let offset = text.len() - digits.len();
+        let magnitude = BigUint::from_str_radix(digits, radix).map_err(|error| match error {
+            ParseBigIntError::InvalidDigit { character, position } => {
+                ParseBigIntError::InvalidDigit { character, position: position + offset }
+            }
+            other => other,
+        })?;
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt { negative: false, magnitude }
+    }
+}
+
+macro_rules! bigint_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                BigInt::from_parts(value < 0, BigUint::from(value.unsigned_abs()))
+            }
+        }
+    )*};
+}
+
+bigint_from!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! bigint_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                BigInt::from(BigUint::from(value))
+            }
+        }
+    )*};
+}
+
+bigint_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_str_radix(10))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        BigInt::from_str_radix(text, 10)
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &other.magnitude);
+        }
+        // Different signs: subtract the smaller magnitude from the larger,
+        // and the result takes the sign of the larger
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => BigInt::from_parts(other.negative, &other.magnitude - &self.magnitude),
+            _ => BigInt::from_parts(self.negative, &self.magnitude - &other.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, &self.magnitude * &other.magnitude)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |total, value| total + value)
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::one(), |total, value| total * value)
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 100!, from OEIS A000142
+    const FACTORIAL_100: &str = "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000";
+
+    fn big(text: &str) -> BigUint {
+        text.parse().unwrap()
+    }
+
+    // Values around the limb boundaries, where carries and borrows happen
+    fn sample_values() -> Vec<u128> {
+        let mut values = vec![0, 1, 2, 3, 7, 10, 255, 1000, 65_535, 123_456_789];
+        for bits in [31, 32, 33, 63, 64, 65, 95, 96, 97, 126, 127] {
+            let power = 1u128 << bits;
+            values.extend([power - 1, power, power + 1]);
+        }
+        values.push(u128::MAX);
+        values
+    }
+
+    #[test]
+    fn test_arithmetic_matches_u128() {
+        let values = sample_values();
+        for &a in &values {
+            for &b in &values {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(&x + &y, BigUint::from(sum), "{} + {}", a, b);
+                }
+                assert_eq!(x.checked_sub(&y), a.checked_sub(b).map(BigUint::from), "{} - {}", a, b);
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(&x * &y, BigUint::from(product), "{} * {}", a, b);
+                }
+                if let Some(quotient) = a.checked_div(b) {
+                    assert_eq!(x.div_rem(&y), (BigUint::from(quotient), BigUint::from(a % b)), "{} / {}", a, b);
+                }
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                assert_eq!(x.to_u128(), Some(a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_values() {
+        let factorial: BigUint = (1..=100u32).map(BigUint::from).product();
+        assert_eq!(factorial.to_string(), FACTORIAL_100);
+        assert_eq!(factorial.to_string().len(), 158);
+
+        // 1 + 2 + ... + u32::MAX = n(n + 1) / 2
+        let n = BigUint::from(u32::MAX);
+        let sum = &n * &(&n + &BigUint::one()) / BigUint::from(2u32);
+        assert_eq!(sum.to_string(), "9223372034707292160");
+
+        assert_eq!(BigUint::from(2u32).pow(128).to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(BigUint::from(2u32).pow(128), BigUint::from(u128::MAX) + BigUint::one());
+        assert_eq!(BigUint::from(10u32).pow(0), BigUint::one());
+        assert_eq!(BigUint::zero().pow(0), BigUint::one());
+
+        // Fibonacci(100)
+        let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+        for _ in 0..100 {
+            let next = &a + &b;
+            a = std::mem::replace(&mut b, next);
+        }
+        assert_eq!(a.to_string(), "354224848179261915075");
+    }
+
+    #[test]
+    fn test_long_division() {
+        let factorial_100 = big(FACTORIAL_100);
+        let factorial_98: BigUint = (1..=98u32).map(BigUint::from).product();
+        assert_eq!(factorial_100.div_rem(&factorial_98), (BigUint::from(9900u32), BigUint::zero()));
+
+        let ten_20 = BigUint::from(10u32).pow(20);
+        let value = BigUint::from(10u32).pow(40) + BigUint::from(7u32);
+        assert_eq!(value.div_rem(&ten_20), (ten_20.clone(), BigUint::from(7u32)));
+
+        // Dividing and multiplying back gives the original number
+        let divisor = big("987654321987654321987");
+        let (quotient, remainder) = factorial_100.div_rem(&divisor);
+        assert!(remainder < divisor);
+        assert_eq!(quotient * &divisor + remainder, factorial_100);
+
+        assert_eq!(BigUint::one().checked_div_rem(&BigUint::zero()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_division_by_zero_panics() {
+        let _ = BigUint::one() / BigUint::zero();
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn test_negative_biguint_panics() {
+        let _ = BigUint::one() - BigUint::from(2u32);
+    }
+
+    #[test]
+    fn test_radix_conversion() {
+        // Test cases: (value, radix, text)
+        let cases = vec![
+            (0u128, 10, "0"),
+            (255, 16, "ff"),
+            (255, 2, "11111111"),
+            (1295, 36, "zz"),
+            (8, 8, "10"),
+            (u32::MAX as u128 + 1, 16, "100000000"),
+            (u64::MAX as u128, 10, "18446744073709551615"),
+            (u128::MAX, 36, "f5lxx1zz5pnorynqglhzmsp33"),
+        ];
+        for (value, radix, text) in cases {
+            let number = BigUint::from(value);
+            assert_eq!(number.to_str_radix(radix), text, "{} in base {}", value, radix);
+            assert_eq!(BigUint::from_str_radix(text, radix), Ok(number.clone()));
+            assert_eq!(BigUint::from_str_radix(&text.to_uppercase(), radix), Ok(number));
+        }
+
+        // Every radix round-trips, checked against u128's own formatting
+        for radix in 2..=36 {
+            let value = 0xDEAD_BEEF_CAFE_F00D_1234_5678u128;
+            let text = BigUint::from(value).to_str_radix(radix);
+            assert_eq!(u128::from_str_radix(&text, radix), Ok(value), "base {}", radix);
+        }
+
+        assert_eq!(format!("{:x}", BigUint::from(255u32)), "ff");
+        assert_eq!(format!("{:#b}", BigUint::from(5u32)), "0b101");
+        assert_eq!(format!("{:>6}", BigUint::from(42u32)), "    42");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+        assert_eq!(
+            "12a4".parse::<BigUint>(),
+            Err(ParseBigIntError::InvalidDigit { character: 'a', position: 2 })
+        );
+        assert_eq!(
+            BigUint::from_str_radix("102", 2),
+            Err(ParseBigIntError::InvalidDigit { character: '2', position: 2 })
+        );
+        assert_eq!(
+            "-12".parse::<BigUint>(),
+            Err(ParseBigIntError::InvalidDigit { character: '-', position: 0 })
+        );
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!(
+            "-1x".parse::<BigInt>(),
+            Err(ParseBigIntError::InvalidDigit { character: 'x', position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_signed_arithmetic_matches_i128() {
+        let values: Vec<i128> = vec![
+            0, 1, -1, 2, -2, 7, -7, 100, -100,
+            u32::MAX as i128, -(u32::MAX as i128), 1 << 40, -(1 << 40),
+            i64::MAX as i128, i64::MIN as i128, 1 << 100, -(1 << 100),
+        ];
+        for &a in &values {
+            for &b in &values {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!((&x + &y).to_i128(), Some(a + b), "{} + {}", a, b);
+                assert_eq!((&x - &y).to_i128(), Some(a - b), "{} - {}", a, b);
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!((&x * &y).to_i128(), Some(product), "{} * {}", a, b);
+                }
+                if b != 0 {
+                    let (quotient, remainder) = x.div_rem(&y);
+                    assert_eq!((quotient.to_i128(), remainder.to_i128()), (Some(a / b), Some(a % b)), "{} / {}", a, b);
+                }
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_values() {
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+        assert_eq!((-BigInt::from(i128::MIN)).to_i128(), None);
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+
+        // Zero has no sign
+        assert_eq!(-BigInt::zero(), BigInt::zero());
+        assert_eq!(BigInt::from(5) - BigInt::from(5), BigInt::zero());
+        assert!(!(BigInt::from(-5) + BigInt::from(5)).is_negative());
+        assert_eq!("-0".parse::<BigInt>(), Ok(BigInt::zero()));
+
+        assert_eq!(BigInt::from(-2).pow(3), BigInt::from(-8));
+        assert_eq!(BigInt::from(-2).pow(4), BigInt::from(16));
+        assert_eq!(BigInt::from(-3).pow(101).to_string(), format!("-{}", BigUint::from(3u32).pow(101)));
+
+        assert_eq!(BigInt::from_str_radix("-ff", 16), Ok(BigInt::from(-255)));
+        assert_eq!(BigInt::from_str_radix("+101", 2), Ok(BigInt::from(5)));
+        assert_eq!(BigInt::from(-255).to_str_radix(16), "-ff");
+        assert_eq!(format!("{:+}", BigInt::from(42)), "+42");
+        assert_eq!(BigInt::from(-42).abs(), BigInt::from(42));
+
+        let total: BigInt = [-5, 10, -20].into_iter().map(BigInt::from).sum();
+        assert_eq!(total, BigInt::from(-15));
+    }
+}
@@ -0,0 +1,735 @@
+// Calculator
+//
+// Evaluating arithmetic expressions written as text, like "2 * (3 + 4)".
+// It works in three steps, the same way a compiler does:
+//
+// - `tokenize` splits the text into numbers, operators and parentheses,
+//   remembering where each one starts.
+// - `parse` turns the tokens into an `Expr` tree that captures precedence:
+//   `^` binds tightest (and groups right to left, so 2^3^2 is 2^9), then
+//   unary minus, then `*`, `/` and `%`, then `+` and `-`. Parentheses
+//   override all of these.
+// - `Expr::eval` walks the tree. It works over any `Number`: `i32` and
+//   `i64` use checked arithmetic and report overflow instead of wrapping
+//   or panicking, and `f64` reports division by zero and results too big
+//   to represent instead of returning infinity or NaN.
+//
+// Every `MathError` except `Empty` carries the position (in characters) of
+// the part of the input that caused it, so a caller can point at it.
+//
+// Parsing and evaluating are recursive, so an expression may nest at most
+// `MAX_DEPTH` levels: "((((1))))" or "----1" or a long "1 + 1 + ... + 1"
+// past that is a `TooDeep` error instead of overflowing the stack.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::fmt;
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MathError {
+    Empty,
+    /// `position` is the `/` or `%` whose right side is zero
+    DivisionByZero { position: usize },
+    /// The result of the operator at `position`, or the number there, is
+    /// too big for the number type
+    Overflow { position: usize },
+    /// Integers can't be raised to a negative power
+    NegativeExponent { position: usize },
+    UnexpectedCharacter { character: char, position: usize },
+    /// A number that doesn't fit the number type's syntax, such as "1.5"
+    /// when evaluating integers
+    InvalidNumber { text: String, position: usize },
+    /// A token where something else was needed, such as "* 3" or "(2 3)"
+    UnexpectedToken { found: String, position: usize },
+    /// The input stopped where a number or `(` was needed
+    UnexpectedEnd { position: usize },
+    /// `position` is the `(` that is never closed
+    UnclosedParenthesis { position: usize },
+    /// The expression nests more than `MAX_DEPTH` levels deep at `position`
+    TooDeep { position: usize },
+}
+
+impl MathError {
+    /// Where in the input the error is, counted in characters
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            MathError::Empty => None,
+            MathError::DivisionByZero { position }
+            | MathError::Overflow { position }
+            | MathError::NegativeExponent { position }
+            | MathError::UnexpectedCharacter { position, .. }
+            | MathError::InvalidNumber { position, .. }
+            | MathError::UnexpectedToken { position, .. }
+            | MathError::UnexpectedEnd { position }
+            | MathError::UnclosedParenthesis { position }
+            | MathError::TooDeep { position } => Some(*position),
+        }
+    }
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::Empty => write!(f, "no expression given"),
+            MathError::DivisionByZero { position } => {
+                write!(f, "division by zero at position {}", position)
+            }
+            MathError::Overflow { position } => {
+                write!(f, "the result at position {} is too large", position)
+            }
+            MathError::NegativeExponent { position } => {
+                write!(f, "negative exponent at position {} (integers only have whole powers)", position)
+            }
+            MathError::UnexpectedCharacter { character, position } => {
+                write!(f, "unexpected character '{}' at position {}", character, position)
+            }
+            MathError::InvalidNumber { text, position } => {
+                write!(f, "'{}' at position {} is not a valid number here", text, position)
+            }
+            MathError::UnexpectedToken { found, position } => {
+                write!(f, "unexpected '{}' at position {}", found, position)
+            }
+            MathError::UnexpectedEnd { position } => {
+                write!(f, "expression ends early at position {}; expected a number or '('", position)
+            }
+            MathError::UnclosedParenthesis { position } => {
+                write!(f, "the '(' at position {} is never closed", position)
+            }
+            MathError::TooDeep { position } => {
+                write!(f, "the expression nests more than {} levels deep at position {}", MAX_DEPTH, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+//------------------------------------------------------
+// TOKENS
+//------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Operator {
+    pub fn symbol(self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+            Operator::Remainder => '%',
+            Operator::Power => '^',
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<Operator> {
+        match c {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Subtract),
+            '*' | '×' => Some(Operator::Multiply),
+            '/' | '÷' => Some(Operator::Divide),
+            '%' => Some(Operator::Remainder),
+            '^' => Some(Operator::Power),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// The number as written; it's converted when evaluating, because
+    /// whether "1.5" is valid depends on the number type
+    Number(String),
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number(text) => write!(f, "{}", text),
+            TokenKind::Operator(op) => write!(f, "{}", op.symbol()),
+            TokenKind::OpenParen => write!(f, "("),
+            TokenKind::CloseParen => write!(f, ")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Where the token starts, in characters
+    pub position: usize,
+}
+
+/// Splits an expression into tokens. Numbers are digits with an optional
+/// fraction and exponent ("42", "0.5", ".5", "1e-3"); spaces are skipped.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, MathError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let kind = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // An exponent only counts if digits follow it, so "2e" is a
+            // number and an unexpected 'e'
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let mut end = i + 1;
+                if end < chars.len() && matches!(chars[end], '+' | '-') {
+                    end += 1;
+                }
+                if end < chars.len() && chars[end].is_ascii_digit() {
+                    while end < chars.len() && chars[end].is_ascii_digit() {
+                        end += 1;
+                    }
+                    i = end;
+                }
+            }
+            TokenKind::Number(chars[start..i].iter().collect())
+        } else if c == '(' {
+            i += 1;
+            TokenKind::OpenParen
+        } else if c == ')' {
+            i += 1;
+            TokenKind::CloseParen
+        } else if let Some(op) = Operator::from_symbol(c) {
+            i += 1;
+            TokenKind::Operator(op)
+        } else {
+            return Err(MathError::UnexpectedCharacter { character: c, position: start });
+        };
+        tokens.push(Token { kind, position: start });
+    }
+    Ok(tokens)
+}
+
+//------------------------------------------------------
+// PARSING
+//------------------------------------------------------
+
+/// A parsed expression. Each node keeps the position of its number or
+/// operator for error messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number { text: String, position: usize },
+    Negate { operand: Box<Expr>, position: usize },
+    Binary { op: Operator, left: Box<Expr>, right: Box<Expr>, position: usize },
+}
+
+// Fully parenthesized, which shows how the expression was grouped
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number { text, .. } => write!(f, "{}", text),
+            Expr::Negate { operand, .. } => write!(f, "(-{})", operand),
+            Expr::Binary { op, left, right, .. } => {
+                write!(f, "({} {} {})", left, op.symbol(), right)
+            }
+        }
+    }
+}
+
+/// How many levels an expression may nest: parentheses, unary operators,
+/// powers and chains of binary operators all count
+pub const MAX_DEPTH: usize = 256;
+
+// A recursive descent parser, one method per precedence level:
+//
+//   expression := term (("+" | "-") term)*
+//   term       := unary (("*" | "/" | "%") unary)*
+//   unary      := ("-" | "+") unary | power
+//   power      := atom ("^" unary)?
+//   atom       := number | "(" expression ")"
+//
+// Each method returns the expression and its depth, so trees too deep to
+// evaluate are refused. `nesting` counts the methods currently running
+// inside one another, so the parser itself can't recurse too deeply either.
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    // Where the input ends, for `UnexpectedEnd`
+    end: usize,
+    nesting: usize,
+}
+
+type Parsed = (Expr, usize);
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn peek_operator(&self, wanted: &[Operator]) -> Option<(Operator, usize)> {
+        match self.peek() {
+            Some(Token { kind: TokenKind::Operator(op), position }) if wanted.contains(op) => {
+                Some((*op, *position))
+            }
+            _ => None,
+        }
+    }
+
+    // Runs `parse` one level deeper, refusing to go past `MAX_DEPTH`
+    fn nested(
+        &mut self,
+        position: usize,
+        parse: fn(&mut Parser) -> Result<Parsed, MathError>,
+    ) -> Result<Parsed, MathError> {
+        if self.nesting >= MAX_DEPTH {
+            return Err(MathError::TooDeep { position });
+        }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
+    }
+
+    // A node one level above its children
+    fn node(expr: Expr, child_depth: usize, position: usize) -> Result<Parsed, MathError> {
+        if child_depth >= MAX_DEPTH {
+            return Err(MathError::TooDeep { position });
+        }
+        Ok((expr, child_depth + 1))
+    }
+
+    fn binary(op: Operator, left: Parsed, right: Parsed, position: usize) -> Result<Parsed, MathError> {
+        let depth = left.1.max(right.1);
+        let expr = Expr::Binary { op, left: Box::new(left.0), right: Box::new(right.0), position };
+        Parser::node(expr, depth, position)
+    }
+
+    fn expression(&mut self) -> Result<Parsed, MathError> {
+        let mut left = self.term()?;
+        while let Some((op, position)) = self.peek_operator(&[Operator::Add, Operator::Subtract]) {
+            self.next += 1;
+            let right = self.term()?;
+            left = Parser::binary(op, left, right, position)?;
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Parsed, MathError> {
+        let mut left = self.unary()?;
+        let operators = [Operator::Multiply, Operator::Divide, Operator::Remainder];
+        while let Some((op, position)) = self.peek_operator(&operators) {
+            self.next += 1;
+            let right = self.unary()?;
+            left = Parser::binary(op, left, right, position)?;
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Parsed, MathError> {
+        match self.peek_operator(&[Operator::Add, Operator::Subtract]) {
+            Some((Operator::Subtract, position)) => {
+                self.next += 1;
+                let (operand, depth) = self.nested(position, Parser::unary)?;
+                Parser::node(Expr::Negate { operand: Box::new(operand), position }, depth, position)
+            }
+            Some((_, position)) => {
+                self.next += 1;
+                self.nested(position, Parser::unary)
+            }
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Parsed, MathError> {
+        let base = self.atom()?;
+        match self.peek_operator(&[Operator::Power]) {
+            Some((op, position)) => {
+                self.next += 1;
+                // The exponent may itself be negative or another power
+                let exponent = self.nested(position, Parser::unary)?;
+                Parser::binary(op, base, exponent, position)
+            }
+            None => Ok(base),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Parsed, MathError> {
+        let token = match self.peek() {
+            Some(token) => token.clone(),
+            None => return Err(MathError::UnexpectedEnd { position: self.end }),
+        };
+        self.next += 1;
+        match token.kind {
+            TokenKind::Number(text) => Ok((Expr::Number { text, position: token.position }, 1)),
+            TokenKind::OpenParen => {
+                let inner = self.nested(token.position, Parser::expression)?;
+                match self.peek() {
+                    Some(Token { kind: TokenKind::CloseParen, .. }) => {
+                        self.next += 1;
+                        Ok(inner)
+                    }
+                    Some(other) => Err(MathError::UnexpectedToken {
+                        found: other.kind.to_string(),
+                        position: other.position,
+                    }),
+                    None => Err(MathError::UnclosedParenthesis { position: token.position }),
+                }
+            }
+            other => Err(MathError::UnexpectedToken { found: other.to_string(), position: token.position }),
+        }
+    }
+}
+
+/// Parses an expression into a tree without evaluating it
+pub fn parse(input: &str) -> Result<Expr, MathError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(MathError::Empty);
+    }
+    let mut parser = Parser { tokens, next: 0, end: input.chars().count(), nesting: 0 };
+    let (expr, _) = parser.expression()?;
+    // Anything left over, like the ")" in "1 + 2)" or the "3" in "2 3"
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(MathError::UnexpectedToken {
+            found: token.kind.to_string(),
+            position: token.position,
+        }),
+    }
+}
+
+//------------------------------------------------------
+// EVALUATION
+//------------------------------------------------------
+
+/// A number type expressions can be evaluated over
+pub trait Number: Copy + fmt::Display {
+    /// Converts a number token; `position` is where it starts
+    fn from_literal(text: &str, position: usize) -> Result<Self, MathError>;
+
+    /// Converts a number token written directly after a unary minus. For
+    /// integers this is how the minimum is written: the digits of
+    /// "-2147483648" don't fit in an i32 on their own.
+    fn from_negated_literal(text: &str, position: usize) -> Result<Self, MathError> {
+        Self::from_literal(text, position)?.negate(position)
+    }
+
+    fn negate(self, position: usize) -> Result<Self, MathError>;
+
+    /// Applies `op`; `position` is where the operator is
+    fn apply(op: Operator, left: Self, right: Self, position: usize) -> Result<Self, MathError>;
+}
+
+macro_rules! integer_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_literal(text: &str, position: usize) -> Result<Self, MathError> {
+                if !text.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(MathError::InvalidNumber { text: text.to_string(), position });
+                }
+                // All digits, so the only way to fail is being too big
+                text.parse().map_err(|_| MathError::Overflow { position })
+            }
+
+            fn from_negated_literal(text: &str, position: usize) -> Result<Self, MathError> {
+                if !text.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(MathError::InvalidNumber { text: text.to_string(), position });
+                }
+                format!("-{}", text).parse().map_err(|_| MathError::Overflow { position })
+            }
+
+            fn negate(self, position: usize) -> Result<Self, MathError> {
+                self.checked_neg().ok_or(MathError::Overflow { position })
+            }
+
+            fn apply(op: Operator, left: Self, right: Self, position: usize) -> Result<Self, MathError> {
+                let overflow = MathError::Overflow { position };
+                match op {
+                    Operator::Add => left.checked_add(right).ok_or(overflow),
+                    Operator::Subtract => left.checked_sub(right).ok_or(overflow),
+                    Operator::Multiply => left.checked_mul(right).ok_or(overflow),
+                    Operator::Divide | Operator::Remainder if right == 0 => {
+                        Err(MathError::DivisionByZero { position })
+                    }
+                    // MIN / -1 is the one division that overflows
+                    Operator::Divide => left.checked_div(right).ok_or(overflow),
+                    Operator::Remainder => left.checked_rem(right).ok_or(overflow),
+                    Operator::Power => {
+                        if right < 0 {
+                            return Err(MathError::NegativeExponent { position });
+                        }
+                        match left {
+                            // These never overflow, however large the exponent
+                            0 | 1 => Ok(if right == 0 { 1 } else { left }),
+                            -1 => Ok(if right % 2 == 0 { 1 } else { -1 }),
+                            _ => {
+                                let exponent = u32::try_from(right).map_err(|_| overflow.clone())?;
+                                left.checked_pow(exponent).ok_or(overflow)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+integer_number!(i32, i64);
+
+impl Number for f64 {
+    fn from_literal(text: &str, position: usize) -> Result<Self, MathError> {
+        let value: f64 = text
+            .parse()
+            .map_err(|_| MathError::InvalidNumber { text: text.to_string(), position })?;
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(MathError::Overflow { position })
+        }
+    }
+
+    fn negate(self, _position: usize) -> Result<Self, MathError> {
+        Ok(-self)
+    }
+
+    fn apply(op: Operator, left: Self, right: Self, position: usize) -> Result<Self, MathError> {
+        let result = match op {
+            Operator::Add => left + right,
+            Operator::Subtract => left - right,
+            Operator::Multiply => left * right,
+            Operator::Divide | Operator::Remainder if right == 0.0 => {
+                return Err(MathError::DivisionByZero { position });
+            }
+            Operator::Divide => left / right,
+            Operator::Remainder => left % right,
+            Operator::Power => left.powf(right),
+        };
+        if result.is_finite() {
+            Ok(result)
+        } else if result.is_nan() {
+            // Only powers get here, like (-8)^0.5: there's no real answer
+            Err(MathError::InvalidNumber { text: format!("{}^{}", left, right), position })
+        } else {
+            Err(MathError::Overflow { position })
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval<T: Number>(&self) -> Result<T, MathError> {
+        match self {
+            Expr::Number { text, position } => T::from_literal(text, *position),
+            Expr::Negate { operand, position } => match &**operand {
+                // "-2147483648" is the i32 minimum, not a negated overflow
+                Expr::Number { text, position } => T::from_negated_literal(text, *position),
+                _ => operand.eval::<T>()?.negate(*position),
+            },
+            Expr::Binary { op, left, right, position } => {
+                T::apply(*op, left.eval()?, right.eval()?, *position)
+            }
+        }
+    }
+}
+
+/// Parses and evaluates an expression: `evaluate::<i64>("2 * (3 + 4)")`
+/// is `Ok(14)`
+pub fn evaluate<T: Number>(input: &str) -> Result<T, MathError> {
+    parse(input)?.eval()
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_expressions() {
+        // Test cases: (expression, value)
+        let cases = vec![
+            ("42", 42),
+            ("1 + 2", 3),
+            ("2 + 3 * 4", 14),
+            ("(2 + 3) * 4", 20),
+            ("2*(3+4)*5", 70),
+            // Left to right within a level
+            ("8 - 3 - 2", 3),
+            ("100 / 10 / 5", 2),
+            ("7 / 2", 3),
+            ("-7 / 2", -3),
+            ("7 % 3", 1),
+            ("-7 % 3", -1),
+            // Powers group right to left and bind tighter than minus
+            ("2 ^ 10", 1024),
+            ("2 ^ 3 ^ 2", 512),
+            ("-2 ^ 2", -4),
+            ("(-2) ^ 2", 4),
+            ("2 ^ 0", 1),
+            // Unary minus and plus
+            ("-3", -3),
+            ("--3", 3),
+            ("2 * -3", -6),
+            ("+5 - -5", 10),
+            ("-(2 + 3)", -5),
+            ("((((1))))", 1),
+            ("  12\t*  2 ", 24),
+            ("6 × 7 ÷ 2", 21),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(evaluate::<i64>(expression), Ok(expected), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn test_float_expressions() {
+        // Test cases: (expression, value)
+        let cases = vec![
+            ("1.5 + 2.25", 3.75),
+            ("7 / 2", 3.5),
+            (".5 * 4", 2.0),
+            ("1e3 + 1", 1001.0),
+            ("2.5e-1", 0.25),
+            ("2 ^ -1", 0.5),
+            ("4 ^ 0.5", 2.0),
+            ("7.5 % 2", 1.5),
+            ("-(1.5)", -1.5),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(evaluate::<f64>(expression), Ok(expected), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn test_grouping() {
+        // Test cases: (expression, how it groups)
+        let cases = vec![
+            ("1 + 2 * 3", "(1 + (2 * 3))"),
+            ("1 - 2 - 3", "((1 - 2) - 3)"),
+            ("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))"),
+            ("-2 ^ 2", "(-(2 ^ 2))"),
+            ("2 ^ -1", "(2 ^ (-1))"),
+            ("(1 + 2) * 3", "((1 + 2) * 3)"),
+        ];
+        for (expression, grouped) in cases {
+            assert_eq!(parse(expression).unwrap().to_string(), grouped, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use MathError::*;
+        // Test cases: (expression, error)
+        let cases = vec![
+            ("", Empty),
+            ("   ", Empty),
+            ("1 +", UnexpectedEnd { position: 3 }),
+            ("2 * (3 + 4", UnclosedParenthesis { position: 4 }),
+            ("1 + 2)", UnexpectedToken { found: ")".to_string(), position: 5 }),
+            ("(2 3)", UnexpectedToken { found: "3".to_string(), position: 3 }),
+            ("2 3", UnexpectedToken { found: "3".to_string(), position: 2 }),
+            ("* 3", UnexpectedToken { found: "*".to_string(), position: 0 }),
+            ("()", UnexpectedToken { found: ")".to_string(), position: 1 }),
+            ("2 $ 3", UnexpectedCharacter { character: '$', position: 2 }),
+            // Positions count characters, not bytes
+            ("é + 1", UnexpectedCharacter { character: 'é', position: 0 }),
+            ("1 ÷ x", UnexpectedCharacter { character: 'x', position: 4 }),
+            ("1..2", InvalidNumber { text: "1..2".to_string(), position: 0 }),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(evaluate::<f64>(expression), Err(expected), "{:?}", expression);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_errors() {
+        use MathError::*;
+        assert_eq!(evaluate::<i64>("1 / 0"), Err(DivisionByZero { position: 2 }));
+        assert_eq!(evaluate::<i64>("10 % (5 - 5)"), Err(DivisionByZero { position: 3 }));
+        assert_eq!(evaluate::<f64>("1 / 0"), Err(DivisionByZero { position: 2 }));
+        assert_eq!(evaluate::<f64>("0 / 0.0"), Err(DivisionByZero { position: 2 }));
+
+        assert_eq!(evaluate::<i32>("2147483647 + 1"), Err(Overflow { position: 11 }));
+        assert_eq!(evaluate::<i32>("-2147483648"), Ok(i32::MIN));
+        assert_eq!(evaluate::<i32>("-2147483648 - 1"), Err(Overflow { position: 12 }));
+        assert_eq!(evaluate::<i32>("--2147483648"), Err(Overflow { position: 0 }));
+        assert_eq!(evaluate::<i64>("-9223372036854775808"), Ok(i64::MIN));
+        assert_eq!(evaluate::<i64>("2147483647 + 1"), Ok(2_147_483_648));
+        assert_eq!(evaluate::<i64>("2 ^ 63"), Err(Overflow { position: 2 }));
+        assert_eq!(evaluate::<i64>("-2 ^ 62 * 2"), Ok(i64::MIN));
+        assert_eq!(evaluate::<i64>("(-2 ^ 62 * 2) / -1"), Err(Overflow { position: 14 }));
+        assert_eq!(evaluate::<i64>("99999999999999999999"), Err(Overflow { position: 0 }));
+        assert_eq!(evaluate::<f64>("10 ^ 400"), Err(Overflow { position: 3 }));
+        assert_eq!(evaluate::<f64>("1e999"), Err(Overflow { position: 0 }));
+
+        assert_eq!(evaluate::<i64>("2 ^ -1"), Err(NegativeExponent { position: 2 }));
+        assert_eq!(evaluate::<i64>("2 ^ 5000000000"), Err(Overflow { position: 2 }));
+        assert_eq!(evaluate::<i64>("1.5 + 1"), Err(InvalidNumber { text: "1.5".to_string(), position: 0 }));
+        assert!(matches!(evaluate::<f64>("(-8) ^ 0.5"), Err(InvalidNumber { position: 5, .. })));
+    }
+
+    #[test]
+    fn test_huge_exponents_of_small_bases() {
+        // Test cases: (expression, value)
+        let cases = vec![
+            ("1 ^ 5000000000", 1),
+            ("0 ^ 5000000000", 0),
+            ("0 ^ 0", 1),
+            ("(-1) ^ 5000000000", 1),
+            ("(-1) ^ 5000000001", -1),
+            ("-1 ^ 5000000000", -1),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(evaluate::<i64>(expression), Ok(expected), "{}", expression);
+        }
+        assert_eq!(evaluate::<i32>("1 ^ 2147483647"), Ok(1));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        use MathError::TooDeep;
+        let parens = |n: usize| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert_eq!(evaluate::<i64>(&parens(MAX_DEPTH)), Ok(1));
+        assert_eq!(evaluate::<i64>(&parens(MAX_DEPTH + 1)), Err(TooDeep { position: MAX_DEPTH }));
+        // Far past the limit is an error too, not a stack overflow
+        assert!(matches!(evaluate::<i64>(&parens(100_000)), Err(TooDeep { .. })));
+        assert!(matches!(evaluate::<i64>(&"-".repeat(100_000)), Err(TooDeep { .. })));
+        assert!(matches!(evaluate::<i64>(&"2 ^ ".repeat(100_000)), Err(TooDeep { .. })));
+
+        // Long chains of operators build deep trees as well
+        let sum = |n: usize| vec!["1"; n].join(" + ");
+        assert_eq!(evaluate::<i64>(&sum(MAX_DEPTH)), Ok(MAX_DEPTH as i64));
+        assert!(matches!(evaluate::<i64>(&sum(100_000)), Err(TooDeep { .. })));
+        assert_eq!(parse(&sum(MAX_DEPTH + 1)), Err(TooDeep { position: 4 * MAX_DEPTH - 2 }));
+    }
+
+    #[test]
+    fn test_parse_once_evaluate_twice() {
+        let expr = parse("7 / 2").unwrap();
+        assert_eq!(expr.eval::<i32>(), Ok(3));
+        assert_eq!(expr.eval::<f64>(), Ok(3.5));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let error = evaluate::<i64>("4 / (2 - 2)").unwrap_err();
+        assert_eq!(error.to_string(), "division by zero at position 2");
+        assert_eq!(error.position(), Some(2));
+        assert_eq!(MathError::Empty.position(), None);
+    }
+}
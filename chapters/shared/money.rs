@@ -0,0 +1,508 @@
+// Money
+//
+// A fixed-point money type. Amounts are stored as a whole number of minor
+// units (cents for USD, pence for GBP, yen for JPY) together with their
+// currency, so sums are exact:
+//
+//     0.10 + 0.20 as f64   = 0.30000000000000004
+//     10 ¢ + 20 ¢ as Money = 30 ¢
+//
+// All arithmetic is checked: adding different currencies or overflowing an
+// i64 returns an error instead of silently producing a wrong amount. When a
+// result has to be rounded (percentages, ratios) it uses banker's rounding
+// (round half to even), which does not drift upwards over many operations.
+//
+// This file is a library module, not a program. Chapter files load it with
+// a `#[path]` attribute pointing at this file, for example:
+//
+//     #[path = "../../../shared/money.rs"]
+//     mod money;
+
+use std::cmp::Ordering;
+use std::fmt;
+
+//------------------------------------------------------
+// CURRENCY
+//------------------------------------------------------
+
+/// An ISO 4217 currency: a three-letter code and the number of minor-unit digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency {
+    code: [u8; 3],
+    exponent: u8,
+}
+
+impl Currency {
+    pub const USD: Currency = Currency { code: *b"USD", exponent: 2 };
+    pub const EUR: Currency = Currency { code: *b"EUR", exponent: 2 };
+    pub const GBP: Currency = Currency { code: *b"GBP", exponent: 2 };
+    pub const JPY: Currency = Currency { code: *b"JPY", exponent: 0 };
+
+    /// Creates a currency from a three-letter uppercase code and the number
+    /// of digits after the decimal point (at most 4)
+    pub fn new(code: &str, exponent: u8) -> Result<Currency, MoneyError> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 3 || !bytes.iter().all(|b| b.is_ascii_uppercase()) || exponent > 4 {
+            return Err(MoneyError::InvalidCurrency(code.to_string()));
+        }
+        Ok(Currency { code: [bytes[0], bytes[1], bytes[2]], exponent })
+    }
+
+    /// Looks up one of the built-in currencies by code
+    pub fn from_code(code: &str) -> Option<Currency> {
+        [Currency::USD, Currency::EUR, Currency::GBP, Currency::JPY]
+            .into_iter()
+            .find(|c| c.code() == code)
+    }
+
+    pub fn code(&self) -> &str {
+        // The constructor only accepts ASCII letters
+        std::str::from_utf8(&self.code).unwrap_or("???")
+    }
+
+    /// Number of digits after the decimal point
+    pub fn exponent(&self) -> u8 {
+        self.exponent
+    }
+
+    /// How many minor units make one major unit (100 for USD)
+    pub fn minor_per_major(&self) -> i64 {
+        10_i64.pow(self.exponent as u32)
+    }
+
+    fn symbol(&self) -> Option<&'static str> {
+        match &self.code {
+            b"USD" => Some("$"),
+            b"EUR" => Some("€"),
+            b"GBP" => Some("£"),
+            b"JPY" => Some("¥"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+/// Why a money operation failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    /// The two amounts are in different currencies
+    CurrencyMismatch(Currency, Currency),
+    /// The result does not fit in an i64 of minor units
+    Overflow,
+    /// Division or ratio with a zero denominator
+    DivisionByZero,
+    /// A currency code or exponent is not valid
+    InvalidCurrency(String),
+    /// A decimal amount could not be parsed
+    InvalidAmount(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch(a, b) => write!(f, "cannot combine {} with {}", a, b),
+            MoneyError::Overflow => write!(f, "amount is too large"),
+            MoneyError::DivisionByZero => write!(f, "division by zero"),
+            MoneyError::InvalidCurrency(code) => write!(f, "invalid currency {:?}", code),
+            MoneyError::InvalidAmount(text) => write!(f, "invalid amount {:?}", text),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+//------------------------------------------------------
+// ROUNDING
+//------------------------------------------------------
+
+/// Divides with banker's rounding: exact halves go to the even neighbour.
+///
+/// 25 / 10 = 2.5 rounds to 2, 35 / 10 = 3.5 rounds to 4,
+/// -25 / 10 = -2.5 rounds to -2.
+pub fn div_round_half_even(numerator: i128, denominator: i128) -> Result<i128, MoneyError> {
+    if denominator == 0 {
+        return Err(MoneyError::DivisionByZero);
+    }
+    // Work with a positive denominator so the remainder has the sign of the numerator
+    let (numerator, denominator) = if denominator < 0 {
+        (numerator.checked_neg().ok_or(MoneyError::Overflow)?, -denominator)
+    } else {
+        (numerator, denominator)
+    };
+
+    let quotient = numerator.div_euclid(denominator);
+    let remainder = numerator.rem_euclid(denominator);
+    // quotient is the floor; decide whether to round up
+    let twice = remainder * 2;
+    let round_up = match twice.cmp(&denominator) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => quotient % 2 != 0,
+    };
+    Ok(if round_up { quotient + 1 } else { quotient })
+}
+
+//------------------------------------------------------
+// MONEY
+//------------------------------------------------------
+
+/// An exact amount of money in a single currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// An amount given in minor units: `Money::new(2999, Currency::USD)` is $29.99
+    pub fn new(minor: i64, currency: Currency) -> Money {
+        Money { minor, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::new(0, currency)
+    }
+
+    /// An amount in whole major units: `Money::from_major(5, Currency::USD)` is $5.00
+    pub fn from_major(major: i64, currency: Currency) -> Result<Money, MoneyError> {
+        major
+            .checked_mul(currency.minor_per_major())
+            .map(|minor| Money::new(minor, currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Shorthand for US dollars given in cents
+    pub fn usd(cents: i64) -> Money {
+        Money::new(cents, Currency::USD)
+    }
+
+    /// Parses a decimal amount like "29.99", "-0.5" or "1000" without going
+    /// through floating point. More decimals than the currency allows is an error.
+    pub fn parse(text: &str, currency: Currency) -> Result<Money, MoneyError> {
+        let invalid = || MoneyError::InvalidAmount(text.to_string());
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let exponent = currency.exponent() as usize;
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) || fraction.len() > exponent {
+            return Err(invalid());
+        }
+        if digits.contains('.') && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut minor: i64 = 0;
+        let padded = format!("{}{:0<width$}", whole, fraction, width = exponent);
+        for b in padded.bytes() {
+            minor = minor
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((b - b'0') as i64))
+                .ok_or(MoneyError::Overflow)?;
+        }
+        Ok(Money::new(if negative { -minor } else { minor }, currency))
+    }
+
+    /// Converts a floating-point amount, rounding half to even.
+    /// Only meant for importing legacy `f64` values.
+    pub fn from_f64(amount: f64, currency: Currency) -> Result<Money, MoneyError> {
+        let scaled = amount * currency.minor_per_major() as f64;
+        if !scaled.is_finite() || scaled.abs() >= i64::MAX as f64 {
+            return Err(MoneyError::Overflow);
+        }
+        Ok(Money::new(scaled.round_ties_even() as i64, currency))
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor > 0
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch(self.currency, other.currency))
+        }
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        self.minor
+            .checked_add(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        self.minor
+            .checked_sub(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_neg(self) -> Result<Money, MoneyError> {
+        self.minor
+            .checked_neg()
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Multiplies by a whole number, such as a quantity
+    pub fn checked_mul(self, factor: i64) -> Result<Money, MoneyError> {
+        self.minor
+            .checked_mul(factor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// Multiplies by `numerator / denominator`, rounding half to even.
+    /// `price.mul_ratio(15, 100)` is 15% of the price.
+    pub fn mul_ratio(self, numerator: i64, denominator: i64) -> Result<Money, MoneyError> {
+        let product = self.minor as i128 * numerator as i128;
+        let minor = div_round_half_even(product, denominator as i128)?;
+        i64::try_from(minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .map_err(|_| MoneyError::Overflow)
+    }
+
+    /// A percentage given in basis points (1/100 of a percent): 1250 is 12.5%
+    pub fn percent_bp(self, basis_points: i64) -> Result<Money, MoneyError> {
+        self.mul_ratio(basis_points, 10_000)
+    }
+
+    /// Splits the amount into `parts` shares that add up exactly to the
+    /// original. The first shares get the leftover minor units.
+    pub fn allocate(self, parts: usize) -> Result<Vec<Money>, MoneyError> {
+        if parts == 0 {
+            return Err(MoneyError::DivisionByZero);
+        }
+        let count = parts as i64;
+        let base = self.minor.div_euclid(count);
+        let leftover = self.minor.rem_euclid(count) as usize;
+        Ok((0..parts)
+            .map(|i| Money::new(base + if i < leftover { 1 } else { 0 }, self.currency))
+            .collect())
+    }
+
+    /// Adds up amounts that must all be in `currency`
+    pub fn sum<I: IntoIterator<Item = Money>>(amounts: I, currency: Currency) -> Result<Money, MoneyError> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, amount| total.checked_add(amount))
+    }
+}
+
+impl PartialOrd for Money {
+    /// Amounts in different currencies cannot be compared
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        if self.currency == other.currency {
+            Some(self.minor.cmp(&other.minor))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    /// "$29.99", "-€5.00", "¥1500", or "12.50 CHF" for currencies without a symbol
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let abs = self.minor.unsigned_abs();
+        let scale = self.currency.minor_per_major() as u64;
+        let number = if self.currency.exponent() == 0 {
+            format!("{}", abs)
+        } else {
+            format!(
+                "{}.{:0width$}",
+                abs / scale,
+                abs % scale,
+                width = self.currency.exponent() as usize
+            )
+        };
+
+        let text = match self.currency.symbol() {
+            Some(symbol) => format!("{}{}{}", sign, symbol, number),
+            None => format!("{}{} {}", sign, number, self.currency),
+        };
+        // Honour width and alignment, e.g. "{:>10}"
+        f.pad(&text)
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(text: &str) -> Money {
+        Money::parse(text, Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        // Test cases: (input, minor units, displayed)
+        let test_cases = vec![
+            ("29.99", 2999, "$29.99"),
+            ("0.5", 50, "$0.50"),
+            ("1000", 100_000, "$1000.00"),
+            ("-0.05", -5, "-$0.05"),
+            ("007.10", 710, "$7.10"),
+        ];
+        for (input, minor, displayed) in test_cases {
+            let money = usd(input);
+            assert_eq!(money.minor_units(), minor, "parsing {}", input);
+            assert_eq!(money.to_string(), displayed);
+        }
+
+        assert_eq!(Money::new(1500, Currency::JPY).to_string(), "¥1500");
+        let chf = Currency::new("CHF", 2).unwrap();
+        assert_eq!(Money::new(1250, chf).to_string(), "12.50 CHF");
+        assert_eq!(format!("[{:>8}]", Money::usd(199)), "[   $1.99]");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        for input in ["", "abc", "1.234", "1.", ".5", "1,00", "--1", "1e3", " 1"] {
+            assert!(Money::parse(input, Currency::USD).is_err(), "{:?} should be rejected", input);
+        }
+        assert!(Money::parse("1.5", Currency::JPY).is_err());
+        assert_eq!(
+            Money::parse("99999999999999999999", Currency::USD),
+            Err(MoneyError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_cent_totals_are_exact() {
+        // Ten dimes are exactly a dollar
+        let dime = usd("0.10");
+        let total = Money::sum(std::iter::repeat_n(dime, 10), Currency::USD).unwrap();
+        assert_eq!(total, usd("1.00"));
+
+        // ...while the same sum in f64 drifts
+        let float_total: f64 = std::iter::repeat_n(0.10, 10).sum();
+        assert_ne!(float_total, 1.0);
+
+        assert_eq!(usd("0.10").checked_add(usd("0.20")), Ok(usd("0.30")));
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(usd("5.00").checked_sub(usd("7.50")), Ok(usd("-2.50")));
+        assert_eq!(usd("19.99").checked_mul(3), Ok(usd("59.97")));
+        assert_eq!(usd("1.00").checked_neg(), Ok(usd("-1.00")));
+
+        let euros = Money::new(100, Currency::EUR);
+        assert_eq!(
+            usd("1.00").checked_add(euros),
+            Err(MoneyError::CurrencyMismatch(Currency::USD, Currency::EUR))
+        );
+        assert_eq!(Money::usd(i64::MAX).checked_add(Money::usd(1)), Err(MoneyError::Overflow));
+        assert_eq!(Money::usd(i64::MIN).checked_neg(), Err(MoneyError::Overflow));
+        assert_eq!(Money::usd(i64::MAX).checked_mul(2), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn test_bankers_rounding() {
+        // Test cases: (numerator, denominator, expected)
+        let test_cases = vec![
+            (25, 10, 2),   // 2.5 -> 2 (even)
+            (35, 10, 4),   // 3.5 -> 4 (even)
+            (26, 10, 3),   // 2.6 -> 3
+            (24, 10, 2),   // 2.4 -> 2
+            (-25, 10, -2), // -2.5 -> -2 (even)
+            (-35, 10, -4), // -3.5 -> -4 (even)
+            (-26, 10, -3),
+            (25, -10, -2),
+            (7, 7, 1),
+        ];
+        for (numerator, denominator, expected) in test_cases {
+            assert_eq!(
+                div_round_half_even(numerator, denominator),
+                Ok(expected),
+                "{} / {}",
+                numerator,
+                denominator
+            );
+        }
+        assert_eq!(div_round_half_even(1, 0), Err(MoneyError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_percentages_round_half_even() {
+        // 10% of $0.25 is 2.5 cents, which rounds to 2
+        assert_eq!(usd("0.25").mul_ratio(10, 100), Ok(usd("0.02")));
+        // 10% of $0.35 is 3.5 cents, which rounds to 4
+        assert_eq!(usd("0.35").mul_ratio(10, 100), Ok(usd("0.04")));
+        // 8.25% sales tax on $19.99 is 164.9175 cents
+        assert_eq!(usd("19.99").percent_bp(825), Ok(usd("1.65")));
+        assert_eq!(usd("1.00").mul_ratio(1, 0), Err(MoneyError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_allocate_keeps_every_cent() {
+        let shares = usd("100.00").allocate(3).unwrap();
+        assert_eq!(shares, vec![usd("33.34"), usd("33.33"), usd("33.33")]);
+        assert_eq!(Money::sum(shares, Currency::USD), Ok(usd("100.00")));
+
+        let shares = usd("-0.05").allocate(2).unwrap();
+        assert_eq!(Money::sum(shares, Currency::USD), Ok(usd("-0.05")));
+        assert!(usd("1.00").allocate(0).is_err());
+    }
+
+    #[test]
+    fn test_comparison_needs_same_currency() {
+        assert!(usd("24.99") < usd("29.99"));
+        assert_eq!(usd("1.00").partial_cmp(&Money::new(100, Currency::EUR)), None);
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(Money::from_f64(29.99, Currency::USD), Ok(usd("29.99")));
+        assert_eq!(Money::from_f64(0.125, Currency::USD), Ok(usd("0.12")));
+        assert_eq!(Money::from_f64(f64::NAN, Currency::USD), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn test_currency() {
+        assert_eq!(Currency::from_code("EUR"), Some(Currency::EUR));
+        assert_eq!(Currency::from_code("XYZ"), None);
+        assert!(Currency::new("usd", 2).is_err());
+        assert!(Currency::new("US", 2).is_err());
+        assert!(Currency::new("BTC", 8).is_err());
+        assert_eq!(Currency::USD.minor_per_major(), 100);
+        assert_eq!(Money::from_major(5, Currency::USD), Ok(usd("5.00")));
+    }
+}
@@ -0,0 +1,470 @@
+// Password Policy
+//
+// A `PasswordPolicy` is built from the rules a service wants to enforce:
+//
+// - a minimum and maximum length (counted in characters, not bytes)
+// - required character classes: lowercase, uppercase, digits, and special
+//   characters from a configurable set
+// - banned words, found case-insensitively and through common look-alike
+//   substitutions ("P@ssw0rd" contains "password")
+// - a limit on the same character repeated in a row ("aaaa")
+// - a minimum estimated entropy, in bits
+//
+// `check` returns every rule the password breaks as a `Violation`, not a
+// single bool, so a sign-up form can tell the user exactly what to fix.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::fmt;
+
+/// The special characters from the testing chapter's password exercise
+pub const DEFAULT_SPECIAL_CHARACTERS: &str = "!@#$%^&*()_+";
+
+//------------------------------------------------------
+// RULES AND VIOLATIONS
+//------------------------------------------------------
+
+/// A kind of character a policy can require
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    /// One of the policy's special characters
+    Special,
+}
+
+impl CharClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CharClass::Lowercase => "a lowercase letter",
+            CharClass::Uppercase => "an uppercase letter",
+            CharClass::Digit => "a digit",
+            CharClass::Special => "a special character",
+        }
+    }
+}
+
+/// One rule a password breaks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    TooShort { min: usize, actual: usize },
+    TooLong { max: usize, actual: usize },
+    MissingClass(CharClass),
+    BannedWord(String),
+    TooManyRepeats { character: char, run: usize, max: usize },
+    TooPredictable { bits: f64, min_bits: f64 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TooShort { min, actual } => {
+                write!(f, "must be at least {} characters long (it has {})", min, actual)
+            }
+            Violation::TooLong { max, actual } => {
+                write!(f, "must be at most {} characters long (it has {})", max, actual)
+            }
+            Violation::MissingClass(class) => write!(f, "must contain {}", class.name()),
+            Violation::BannedWord(word) => write!(f, "must not contain the word '{}'", word),
+            Violation::TooManyRepeats { character, run, max } => write!(
+                f,
+                "must not repeat a character more than {} times in a row ('{}' appears {} times)",
+                max, character, run
+            ),
+            Violation::TooPredictable { bits, min_bits } => write!(
+                f,
+                "is too easy to guess (about {:.0} bits of entropy, needs {:.0})",
+                bits, min_bits
+            ),
+        }
+    }
+}
+
+//------------------------------------------------------
+// POLICY
+//------------------------------------------------------
+
+/// The rules a password must meet. Start from `PasswordPolicy::new()`,
+/// which accepts anything, and add rules:
+///
+/// ```ignore
+/// let policy = PasswordPolicy::new()
+///     .min_length(12)
+///     .require(CharClass::Digit)
+///     .ban_words(&["password", "admin"])
+///     .max_repeated(2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicy {
+    min_length: usize,
+    max_length: Option<usize>,
+    required: Vec<CharClass>,
+    special_characters: String,
+    // (word as given but lowercased, word normalized for matching)
+    banned_words: Vec<(String, String)>,
+    max_repeated: Option<usize>,
+    min_entropy_bits: Option<f64>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy::new()
+    }
+}
+
+impl PasswordPolicy {
+    /// A policy with no rules
+    pub fn new() -> Self {
+        PasswordPolicy {
+            min_length: 0,
+            max_length: None,
+            required: Vec::new(),
+            special_characters: DEFAULT_SPECIAL_CHARACTERS.to_string(),
+            banned_words: Vec::new(),
+            max_repeated: None,
+            min_entropy_bits: None,
+        }
+    }
+
+    pub fn min_length(mut self, min: usize) -> Self {
+        self.min_length = min;
+        self
+    }
+
+    pub fn max_length(mut self, max: usize) -> Self {
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Requires at least one character of a class
+    pub fn require(mut self, class: CharClass) -> Self {
+        if !self.required.contains(&class) {
+            self.required.push(class);
+        }
+        self
+    }
+
+    /// Which characters count as `CharClass::Special`
+    pub fn special_characters(mut self, characters: &str) -> Self {
+        self.special_characters = characters.to_string();
+        self
+    }
+
+    pub fn ban_word(mut self, word: &str) -> Self {
+        let normalized = normalize(word);
+        if !normalized.is_empty() && !self.banned_words.iter().any(|(_, banned)| *banned == normalized) {
+            self.banned_words.push((word.to_lowercase(), normalized));
+        }
+        self
+    }
+
+    pub fn ban_words(self, words: &[&str]) -> Self {
+        words.iter().fold(self, |policy, word| policy.ban_word(word))
+    }
+
+    /// The longest run of one character allowed, e.g. 2 allows "aa" but not "aaa"
+    pub fn max_repeated(mut self, max: usize) -> Self {
+        self.max_repeated = Some(max.max(1));
+        self
+    }
+
+    pub fn min_entropy_bits(mut self, bits: f64) -> Self {
+        self.min_entropy_bits = Some(bits);
+        self
+    }
+
+    pub fn is_special(&self, c: char) -> bool {
+        self.special_characters.contains(c)
+    }
+
+    fn has_class(&self, password: &str, class: CharClass) -> bool {
+        password.chars().any(|c| match class {
+            CharClass::Lowercase => c.is_lowercase(),
+            CharClass::Uppercase => c.is_uppercase(),
+            CharClass::Digit => c.is_ascii_digit(),
+            CharClass::Special => self.is_special(c),
+        })
+    }
+
+    /// Every rule the password breaks, in the order the rules are listed
+    /// above. An empty list means the password is accepted.
+    pub fn violations(&self, password: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            violations.push(Violation::TooShort { min: self.min_length, actual: length });
+        }
+        if let Some(max) = self.max_length {
+            if length > max {
+                violations.push(Violation::TooLong { max, actual: length });
+            }
+        }
+
+        for &class in &self.required {
+            if !self.has_class(password, class) {
+                violations.push(Violation::MissingClass(class));
+            }
+        }
+
+        let normalized = normalize(password);
+        for (word, banned) in &self.banned_words {
+            if normalized.contains(banned.as_str()) {
+                violations.push(Violation::BannedWord(word.clone()));
+            }
+        }
+
+        if let Some(max) = self.max_repeated {
+            if let Some((character, run)) = longest_run(password) {
+                if run > max {
+                    violations.push(Violation::TooManyRepeats { character, run, max });
+                }
+            }
+        }
+
+        if let Some(min_bits) = self.min_entropy_bits {
+            let bits = estimate_entropy_bits(password);
+            if bits < min_bits {
+                violations.push(Violation::TooPredictable { bits, min_bits });
+            }
+        }
+
+        violations
+    }
+
+    pub fn check(&self, password: &str) -> Result<(), Vec<Violation>> {
+        let violations = self.violations(password);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn is_valid(&self, password: &str) -> bool {
+        self.violations(password).is_empty()
+    }
+}
+
+//------------------------------------------------------
+// HELPERS
+//------------------------------------------------------
+
+// Lowercases and undoes common look-alike substitutions, so banned words
+// are found however they are dressed up
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '@' | '4' => 'a',
+            '3' => 'e',
+            '1' | '!' => 'i',
+            '0' => 'o',
+            '$' | '5' => 's',
+            '7' => 't',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// The character repeated most times in a row, and how many times
+fn longest_run(password: &str) -> Option<(char, usize)> {
+    let mut best: Option<(char, usize)> = None;
+    let mut run = 0;
+    let mut previous = None;
+    for c in password.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+        if best.map(|(_, longest)| run > longest).unwrap_or(true) {
+            best = Some((c, run));
+        }
+    }
+    best
+}
+
+/// A rough estimate of how many guesses a password would take, in bits,
+/// in the style of simple strength meters: each character is worth
+/// log2 of the size of the character pool the password draws from, except
+/// that a character repeating the previous one or continuing a run like
+/// "abc" or "321" is worth only one bit. Real crackers also try dictionary
+/// words, which is what banned words are for.
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let mut pool = 0u32;
+    let chars: Vec<char> = password.chars().collect();
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') {
+        pool += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100;
+    }
+    if pool == 0 {
+        return 0.0;
+    }
+
+    let per_char = f64::from(pool).log2();
+    let mut bits = 0.0;
+    for (i, &c) in chars.iter().enumerate() {
+        let predictable = i > 0 && {
+            let step = c as i64 - chars[i - 1] as i64;
+            step == 0 || (i > 1 && step.abs() == 1 && chars[i - 1] as i64 - chars[i - 2] as i64 == step)
+        };
+        bits += if predictable { 1.0 } else { per_char };
+    }
+    bits
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The rules from exercise 4 in 05_testing/01_unit_testing
+    fn exercise_policy() -> PasswordPolicy {
+        PasswordPolicy::new()
+            .min_length(8)
+            .require(CharClass::Uppercase)
+            .require(CharClass::Lowercase)
+            .require(CharClass::Digit)
+            .require(CharClass::Special)
+    }
+
+    #[test]
+    fn test_empty_policy_accepts_anything() {
+        let policy = PasswordPolicy::new();
+        assert!(policy.is_valid(""));
+        assert!(policy.is_valid("a"));
+        assert_eq!(policy.check("anything"), Ok(()));
+    }
+
+    #[test]
+    fn test_exercise_rules() {
+        // Test cases: (password, expected violations)
+        let cases = vec![
+            ("ValidP@ssw0rd", vec![]),
+            ("An0ther!Valid1", vec![]),
+            ("nouppercase123!", vec![Violation::MissingClass(CharClass::Uppercase)]),
+            ("NOLOWERCASE123!", vec![Violation::MissingClass(CharClass::Lowercase)]),
+            ("NoDigits!", vec![Violation::MissingClass(CharClass::Digit)]),
+            ("NoSpecialChar123", vec![Violation::MissingClass(CharClass::Special)]),
+            (
+                "short",
+                vec![
+                    Violation::TooShort { min: 8, actual: 5 },
+                    Violation::MissingClass(CharClass::Uppercase),
+                    Violation::MissingClass(CharClass::Digit),
+                    Violation::MissingClass(CharClass::Special),
+                ],
+            ),
+        ];
+        let policy = exercise_policy();
+        for (password, expected) in cases {
+            assert_eq!(policy.violations(password), expected, "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_lengths_count_characters() {
+        let policy = PasswordPolicy::new().min_length(4).max_length(6);
+        assert!(policy.is_valid("ñáéí"));
+        assert_eq!(policy.violations("abc"), vec![Violation::TooShort { min: 4, actual: 3 }]);
+        assert_eq!(policy.violations("abcdefg"), vec![Violation::TooLong { max: 6, actual: 7 }]);
+    }
+
+    #[test]
+    fn test_custom_special_characters() {
+        let policy = PasswordPolicy::new().require(CharClass::Special).special_characters("-~");
+        assert!(policy.is_valid("a-b"));
+        assert!(!policy.is_valid("a!b"));
+    }
+
+    #[test]
+    fn test_banned_words() {
+        let policy = PasswordPolicy::new().ban_words(&["password", "Admin", "p4ssword", "123456"]);
+        // Test cases: (password, banned words found)
+        let cases = vec![
+            ("correct horse", vec![]),
+            ("mypassword1", vec!["password"]),
+            ("P@ssw0rd!", vec!["password"]),
+            ("ADMIN-password", vec!["password", "admin"]),
+            ("4dm1n", vec!["admin"]),
+            ("x123456x", vec!["123456"]),
+        ];
+        for (password, words) in cases {
+            let expected: Vec<Violation> = words.iter().map(|w| Violation::BannedWord(w.to_string())).collect();
+            assert_eq!(policy.violations(password), expected, "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_max_repeated() {
+        let policy = PasswordPolicy::new().max_repeated(2);
+        assert!(policy.is_valid("aabbcc"));
+        assert!(policy.is_valid(""));
+        assert_eq!(
+            policy.violations("abbbcaaaa"),
+            vec![Violation::TooManyRepeats { character: 'a', run: 4, max: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_entropy_estimate() {
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+        // 8 characters from 26 letters
+        assert!((estimate_entropy_bits("qwhvtmzk") - 8.0 * 26f64.log2()).abs() < 1e-9);
+        // Repeats and runs are worth far less than random characters
+        assert!(estimate_entropy_bits("aaaaaaaa") < estimate_entropy_bits("qwhvtmzk") / 2.0);
+        assert!(estimate_entropy_bits("abcdefgh") < estimate_entropy_bits("qwhvtmzk") / 2.0);
+        assert!(estimate_entropy_bits("12345678") < estimate_entropy_bits("83920571") / 2.0);
+        // More kinds of characters, more bits per character
+        assert!(estimate_entropy_bits("Qw3!tmZk") > estimate_entropy_bits("qwhvtmzk"));
+
+        let policy = PasswordPolicy::new().min_entropy_bits(40.0);
+        assert!(policy.is_valid("Qw3!tmZk"));
+        match policy.violations("abcdefgh").as_slice() {
+            [Violation::TooPredictable { bits, min_bits }] => {
+                assert!(*bits < 40.0);
+                assert_eq!(*min_bits, 40.0);
+            }
+            other => panic!("expected TooPredictable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_violations_explain_themselves() {
+        let policy = PasswordPolicy::new()
+            .min_length(12)
+            .require(CharClass::Digit)
+            .ban_word("password")
+            .max_repeated(2);
+        let messages: Vec<String> = policy.violations("passsword").iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "must be at least 12 characters long (it has 9)",
+                "must contain a digit",
+                "must not repeat a character more than 2 times in a row ('s' appears 3 times)",
+            ]
+        );
+        // "passsword" isn't "password", but "Password1" is
+        assert_eq!(
+            policy.violations("Password1"),
+            vec![
+                Violation::TooShort { min: 12, actual: 9 },
+                Violation::BannedWord("password".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,450 @@
+// Roman Numerals
+//
+// Converting numbers to Roman numerals and back.
+//
+// - `Roman` is a number that is known to fit: `Roman::standard` accepts
+//   1-3999 (MMMCMXCIX), the largest value plain numerals can write, and
+//   `Roman::new` accepts up to 3,999,999 using the overline (vinculum)
+//   extension, where a bar over a numeral multiplies it by 1000: V̅ is 5000
+//   and M̅ is 1,000,000. The bar is written as U+0305 COMBINING OVERLINE
+//   after the character.
+// - Parsing is strict by default: only the one canonical spelling of each
+//   number is accepted, so "IIII", "VX", "IC" and "iv" are rejected, and
+//   the error says what the canonical spelling would be.
+// - `ParseMode::Lenient` reads the numerals people actually write, such as
+//   "IIII" on clock faces, "IIX", lowercase letters and surrounding spaces,
+//   by adding each numeral and subtracting it when a larger one follows.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The largest number plain numerals can write
+pub const MAX_STANDARD: u32 = 3999;
+
+/// The largest number with overlined thousands
+pub const MAX_EXTENDED: u32 = 3_999_999;
+
+/// Written after a numeral to multiply it by 1000
+pub const OVERLINE: char = '\u{0305}';
+
+// Values and their numerals, largest first, including the subtractive pairs
+const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomanError {
+    /// Roman numerals have no zero, and each form has a largest value
+    OutOfRange { value: u64, max: u32 },
+    Empty,
+    /// `position` counts characters, not bytes
+    InvalidCharacter { character: char, position: usize },
+    /// A valid-looking numeral that isn't the standard way to write its
+    /// value, such as "IIII" for "IV"
+    NonCanonical { input: String, canonical: String },
+}
+
+impl fmt::Display for RomanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RomanError::OutOfRange { value, max } => {
+                write!(f, "{} can't be written in Roman numerals (1 to {})", value, max)
+            }
+            RomanError::Empty => write!(f, "no numerals given"),
+            RomanError::InvalidCharacter { character, position } => {
+                write!(f, "'{}' at position {} is not a Roman numeral", character, position)
+            }
+            RomanError::NonCanonical { input, canonical } => {
+                write!(f, "'{}' is not the standard spelling; did you mean '{}'?", input, canonical)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RomanError {}
+
+//------------------------------------------------------
+// THE ROMAN TYPE
+//------------------------------------------------------
+
+/// How strictly to read numerals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Only the canonical spelling, in uppercase
+    Strict,
+    /// Any additive or subtractive spelling, in either case
+    Lenient,
+}
+
+/// A number from 1 to `MAX_EXTENDED`, printed as Roman numerals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Roman(u32);
+
+impl Roman {
+    pub const MIN: Roman = Roman(1);
+    pub const MAX: Roman = Roman(MAX_EXTENDED);
+
+    /// 1 to `MAX_EXTENDED`; values over 3999 are written with overlines
+    pub fn new(value: u32) -> Result<Roman, RomanError> {
+        Roman::in_range(value, MAX_EXTENDED)
+    }
+
+    /// 1 to `MAX_STANDARD`, for when overlines can't be printed
+    pub fn standard(value: u32) -> Result<Roman, RomanError> {
+        Roman::in_range(value, MAX_STANDARD)
+    }
+
+    fn in_range(value: u32, max: u32) -> Result<Roman, RomanError> {
+        if value == 0 || value > max {
+            return Err(RomanError::OutOfRange { value: u64::from(value), max });
+        }
+        Ok(Roman(value))
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// True when no overlines are needed
+    pub fn is_standard(&self) -> bool {
+        self.0 <= MAX_STANDARD
+    }
+
+    pub fn parse(text: &str, mode: ParseMode) -> Result<Roman, RomanError> {
+        let value = Roman::new(read_numerals(text, mode)?)?;
+        if mode == ParseMode::Strict {
+            let canonical = value.to_string();
+            if canonical != text {
+                return Err(RomanError::NonCanonical { input: text.to_string(), canonical });
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Roman {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (thousands, rest) = if self.is_standard() {
+            (0, self.0)
+        } else {
+            (self.0 / 1000, self.0 % 1000)
+        };
+        for c in plain_numerals(thousands).chars() {
+            write!(f, "{}{}", c, OVERLINE)?;
+        }
+        write!(f, "{}", plain_numerals(rest))
+    }
+}
+
+/// Strict parsing
+impl FromStr for Roman {
+    type Err = RomanError;
+
+    fn from_str(text: &str) -> Result<Roman, RomanError> {
+        Roman::parse(text, ParseMode::Strict)
+    }
+}
+
+impl TryFrom<u32> for Roman {
+    type Error = RomanError;
+
+    fn try_from(value: u32) -> Result<Roman, RomanError> {
+        Roman::new(value)
+    }
+}
+
+impl From<Roman> for u32 {
+    fn from(roman: Roman) -> u32 {
+        roman.0
+    }
+}
+
+//------------------------------------------------------
+// CONVERSIONS
+//------------------------------------------------------
+
+/// Plain numerals for 1-3999, e.g. `to_roman(1994)` is "MCMXCIV"
+pub fn to_roman(value: u32) -> Result<String, RomanError> {
+    Roman::standard(value).map(|roman| roman.to_string())
+}
+
+/// Reads canonical uppercase numerals, with overlines for large values
+pub fn from_roman(text: &str) -> Result<u32, RomanError> {
+    Roman::parse(text, ParseMode::Strict).map(|roman| roman.value())
+}
+
+/// Reads any additive or subtractive spelling, e.g. "IIII" or " xiv "
+pub fn from_roman_lenient(text: &str) -> Result<u32, RomanError> {
+    Roman::parse(text, ParseMode::Lenient).map(|roman| roman.value())
+}
+
+// 0-3999 without overlines; 0 is the empty string
+fn plain_numerals(mut value: u32) -> String {
+    let mut numerals = String::new();
+    for &(amount, symbol) in NUMERALS.iter() {
+        while value >= amount {
+            numerals.push_str(symbol);
+            value -= amount;
+        }
+    }
+    numerals
+}
+
+fn numeral_value(c: char) -> Option<u64> {
+    match c.to_ascii_uppercase() {
+        'I' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        'D' => Some(500),
+        'M' => Some(1000),
+        _ => None,
+    }
+}
+
+// The value of any additive or subtractive spelling: reading right to
+// left, a numeral smaller than the largest one seen so far is subtracted.
+// Strict parsing checks the spelling separately.
+fn read_numerals(text: &str, mode: ParseMode) -> Result<u32, RomanError> {
+    let text = match mode {
+        ParseMode::Strict => text,
+        ParseMode::Lenient => text.trim(),
+    };
+
+    let mut values = Vec::new();
+    for (position, c) in text.chars().enumerate() {
+        if c == OVERLINE {
+            match values.last_mut() {
+                Some((value, overlined @ false)) => {
+                    *value *= 1000;
+                    *overlined = true;
+                }
+                _ => return Err(RomanError::InvalidCharacter { character: c, position }),
+            }
+            continue;
+        }
+        let value = numeral_value(c)
+            .filter(|_| mode == ParseMode::Lenient || c.is_ascii_uppercase())
+            .ok_or(RomanError::InvalidCharacter { character: c, position })?;
+        values.push((value, false));
+    }
+    if values.is_empty() {
+        return Err(RomanError::Empty);
+    }
+
+    let mut total: i64 = 0;
+    let mut largest = 0;
+    for &(value, _) in values.iter().rev() {
+        if value < largest {
+            total -= value as i64;
+        } else {
+            total += value as i64;
+            largest = value;
+        }
+    }
+    if total <= 0 || total > i64::from(MAX_EXTENDED) {
+        return Err(RomanError::OutOfRange { value: total.max(0) as u64, max: MAX_EXTENDED });
+    }
+    Ok(total as u32)
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_values() {
+        // Test cases: (value, numerals)
+        let cases = vec![
+            (1, "I"),
+            (4, "IV"),
+            (9, "IX"),
+            (14, "XIV"),
+            (40, "XL"),
+            (90, "XC"),
+            (400, "CD"),
+            (1994, "MCMXCIV"),
+            (2023, "MMXXIII"),
+            (3888, "MMMDCCCLXXXVIII"),
+            (3999, "MMMCMXCIX"),
+        ];
+        for (value, numerals) in cases {
+            assert_eq!(to_roman(value).unwrap(), numerals);
+            assert_eq!(from_roman(numerals), Ok(value), "{}", numerals);
+        }
+    }
+
+    #[test]
+    fn test_range_checks() {
+        assert_eq!(Roman::new(0), Err(RomanError::OutOfRange { value: 0, max: MAX_EXTENDED }));
+        assert_eq!(to_roman(4000), Err(RomanError::OutOfRange { value: 4000, max: MAX_STANDARD }));
+        assert!(Roman::new(MAX_EXTENDED + 1).is_err());
+        assert!(Roman::try_from(4000).is_ok());
+        assert_eq!(u32::from(Roman::MAX), 3_999_999);
+        assert!(Roman::standard(3999).unwrap().is_standard());
+        assert!(!Roman::new(4000).unwrap().is_standard());
+    }
+
+    #[test]
+    fn test_strict_parsing_rejects_non_canonical_spellings() {
+        // Test cases: (input, canonical spelling)
+        let cases = vec![
+            ("IIII", "IV"),
+            ("VX", "V"),
+            ("IC", "XCIX"),
+            ("IIX", "VIII"),
+            ("VV", "X"),
+            ("XM", "CMXC"),
+            ("MCMC", "MM"),
+            ("MMMM", "I\u{305}V\u{305}"),
+        ];
+        for (input, canonical) in cases {
+            assert_eq!(
+                from_roman(input),
+                Err(RomanError::NonCanonical { input: input.to_string(), canonical: canonical.to_string() })
+            );
+        }
+
+        assert_eq!(from_roman(""), Err(RomanError::Empty));
+        assert_eq!(from_roman("iv"), Err(RomanError::InvalidCharacter { character: 'i', position: 0 }));
+        assert_eq!(from_roman("XIV "), Err(RomanError::InvalidCharacter { character: ' ', position: 3 }));
+        assert_eq!(from_roman("X2"), Err(RomanError::InvalidCharacter { character: '2', position: 1 }));
+        assert_eq!("MCMXCIV".parse::<Roman>().map(u32::from), Ok(1994));
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        // Test cases: (input, value)
+        let cases = vec![
+            ("IIII", 4),
+            ("VX", 5),
+            ("IIX", 8),
+            ("IC", 99),
+            (" xiv ", 14),
+            ("mcmxcIV", 1994),
+            ("MMMM", 4000),
+        ];
+        for (input, value) in cases {
+            assert_eq!(from_roman_lenient(input), Ok(value), "{}", input);
+        }
+
+        assert_eq!(from_roman_lenient("   "), Err(RomanError::Empty));
+        assert_eq!(from_roman_lenient("IIIIIV"), Err(RomanError::OutOfRange { value: 0, max: MAX_EXTENDED }));
+        assert!(from_roman_lenient("XIIJ").is_err());
+    }
+
+    #[test]
+    fn test_overline_extension() {
+        // Test cases: (value, numerals with ASCII '_' standing for the overline)
+        let cases = vec![
+            (4000, "I_V_"),
+            (5000, "V_"),
+            (10_001, "X_I"),
+            (1_994_000, "M_C_M_X_C_I_V_"),
+            (3_999_999, "M_M_M_C_M_X_C_I_X_CMXCIX"),
+        ];
+        for (value, numerals) in cases {
+            let numerals = numerals.replace('_', "\u{305}");
+            assert_eq!(Roman::new(value).unwrap().to_string(), numerals);
+            assert_eq!(from_roman(&numerals), Ok(value));
+        }
+
+        // Overlined thousands below 4000 are written with plain M's
+        assert_eq!(
+            from_roman("I\u{305}"),
+            Err(RomanError::NonCanonical { input: "I\u{305}".to_string(), canonical: "M".to_string() })
+        );
+        assert_eq!(from_roman_lenient("I\u{305}"), Ok(1000));
+        // An overline needs a numeral under it, and only one
+        assert_eq!(from_roman("\u{305}V"), Err(RomanError::InvalidCharacter { character: '\u{305}', position: 0 }));
+        assert_eq!(
+            from_roman("V\u{305}\u{305}"),
+            Err(RomanError::InvalidCharacter { character: '\u{305}', position: 2 })
+        );
+    }
+
+    //--------------------------------------------------
+    // Properties, checked for every standard value
+    //--------------------------------------------------
+
+    #[test]
+    fn test_round_trip_every_standard_value() {
+        for value in 1..=MAX_STANDARD {
+            let numerals = to_roman(value).unwrap();
+            assert_eq!(from_roman(&numerals), Ok(value), "{}", numerals);
+            assert_eq!(from_roman_lenient(&numerals), Ok(value), "{}", numerals);
+            assert_eq!(from_roman_lenient(&numerals.to_lowercase()), Ok(value), "{}", numerals);
+        }
+    }
+
+    #[test]
+    fn test_canonical_spellings_are_well_formed() {
+        for value in 1..=MAX_STANDARD {
+            let numerals = to_roman(value).unwrap();
+            // No numeral repeats more than three times in a row, and V, L
+            // and D never repeat
+            for symbol in ["IIII", "XXXX", "CCCC", "MMMM", "VV", "LL", "DD"] {
+                assert!(!numerals.contains(symbol), "{} = {}", value, numerals);
+            }
+            // Only the six subtractive pairs put a smaller numeral first
+            let chars: Vec<char> = numerals.chars().collect();
+            for pair in chars.windows(2) {
+                if numeral_value(pair[0]) < numeral_value(pair[1]) {
+                    let pair: String = pair.iter().collect();
+                    assert!(["IV", "IX", "XL", "XC", "CD", "CM"].contains(&pair.as_str()), "{}", numerals);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_non_canonical_spelling_is_rejected() {
+        // Appending a numeral to a canonical spelling gives either another
+        // canonical spelling of a different value, or one strict parsing
+        // rejects
+        for value in 1..=MAX_STANDARD {
+            let numerals = to_roman(value).unwrap();
+            for extra in ["I", "V", "X", "L", "C", "D", "M"] {
+                let candidate = format!("{}{}", numerals, extra);
+                match from_roman(&candidate) {
+                    Ok(parsed) => assert_eq!(to_roman(parsed).unwrap(), candidate),
+                    Err(RomanError::NonCanonical { canonical, .. }) => {
+                        assert_eq!(from_roman(&canonical), from_roman_lenient(&candidate))
+                    }
+                    Err(e) => panic!("{}: {}", candidate, e),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_extended_values() {
+        for value in (MAX_STANDARD + 1..=MAX_EXTENDED).step_by(997).chain([MAX_EXTENDED]) {
+            let roman = Roman::new(value).unwrap();
+            assert_eq!(roman.to_string().parse::<Roman>(), Ok(roman));
+        }
+    }
+}
@@ -0,0 +1,980 @@
+// Shopping Cart and Pricing Engine
+//
+// A `ShoppingCart` holds items (one line per item name) and coupon codes.
+// It knows nothing about prices beyond its plain sum: a `PricingEngine`
+// turns a cart into an itemized `Receipt` by applying, in this order:
+//
+// 1. Item rules (`PricingRule`), line by line: buy-N-get-M deals and
+//    percentage discounts on one item or on everything.
+// 2. Coupons entered on the cart, on the order subtotal.
+// 3. Tax, per `TaxCategory`, on what the customer actually pays. Coupon
+//    discounts are spread over the lines in proportion to their amounts
+//    before tax is computed.
+//
+// Every adjustment ends up on the receipt with a description, and all
+// amounts are exact `Money`, rounded half to even where a percentage is taken.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+#[path = "money.rs"]
+pub mod money;
+
+pub use money::{Currency, Money, MoneyError};
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+//------------------------------------------------------
+// CART
+//------------------------------------------------------
+
+/// How an item is taxed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaxCategory {
+    Standard,
+    Reduced,
+    /// Never taxed
+    Exempt,
+}
+
+impl TaxCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaxCategory::Standard => "standard",
+            TaxCategory::Reduced => "reduced",
+            TaxCategory::Exempt => "exempt",
+        }
+    }
+}
+
+/// A shopping cart item
+#[derive(Debug, Clone, PartialEq)]
+pub struct CartItem {
+    pub name: String,
+    pub price: Money,
+    pub quantity: u32,
+    pub tax_category: TaxCategory,
+}
+
+/// Why an item could not be added to the cart
+#[derive(Debug, Clone, PartialEq)]
+pub enum CartError {
+    /// Price in another currency than the cart, or an amount overflow
+    Money(MoneyError),
+    /// A negative price; refunds are not entered as cart lines
+    InvalidPrice(Money),
+    InvalidQuantity(u32),
+    /// The item is already in the cart with a different price or tax category
+    ItemConflict { name: String },
+}
+
+impl fmt::Display for CartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartError::Money(e) => write!(f, "{}", e),
+            CartError::InvalidPrice(price) => write!(f, "invalid price {}", price),
+            CartError::InvalidQuantity(quantity) => write!(f, "invalid quantity {}", quantity),
+            CartError::ItemConflict { name } => {
+                write!(f, "'{}' is already in the cart with a different price or tax category", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CartError {}
+
+impl From<MoneyError> for CartError {
+    fn from(e: MoneyError) -> Self {
+        CartError::Money(e)
+    }
+}
+
+/// A shopping cart whose items are all priced in one currency
+#[derive(Debug, Clone)]
+pub struct ShoppingCart {
+    currency: Currency,
+    items: Vec<CartItem>,
+    coupons: Vec<String>,
+}
+
+impl ShoppingCart {
+    /// An empty cart priced in US dollars
+    pub fn new() -> Self {
+        ShoppingCart::with_currency(Currency::USD)
+    }
+
+    pub fn with_currency(currency: Currency) -> Self {
+        ShoppingCart { currency, items: Vec::new(), coupons: Vec::new() }
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Adds a standard-rate item. Adding a name that is already in the cart
+    /// increases its quantity instead of creating a second line.
+    pub fn add_item(&mut self, name: &str, price: Money, quantity: u32) -> Result<(), CartError> {
+        self.add_taxed_item(name, price, quantity, TaxCategory::Standard)
+    }
+
+    /// Adds an item with an explicit tax category
+    pub fn add_taxed_item(
+        &mut self,
+        name: &str,
+        price: Money,
+        quantity: u32,
+        tax_category: TaxCategory,
+    ) -> Result<(), CartError> {
+        if price.currency() != self.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, price.currency()).into());
+        }
+        if price.is_negative() {
+            return Err(CartError::InvalidPrice(price));
+        }
+        if quantity == 0 {
+            return Err(CartError::InvalidQuantity(quantity));
+        }
+
+        if let Some(item) = self.items.iter_mut().find(|item| item.name == name) {
+            if item.price != price || item.tax_category != tax_category {
+                return Err(CartError::ItemConflict { name: name.to_string() });
+            }
+            item.quantity = item.quantity.checked_add(quantity).ok_or(CartError::InvalidQuantity(quantity))?;
+            return Ok(());
+        }
+
+        self.items.push(CartItem {
+            name: name.to_string(),
+            price,
+            quantity,
+            tax_category,
+        });
+        Ok(())
+    }
+
+    pub fn get_items(&self) -> &[CartItem] {
+        &self.items
+    }
+
+    pub fn get_item(&self, name: &str) -> Option<&CartItem> {
+        self.items.iter().find(|item| item.name == name)
+    }
+
+    pub fn remove_item(&mut self, name: &str) -> bool {
+        if let Some(pos) = self.items.iter().position(|item| item.name == name) {
+            self.items.remove(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Enters a coupon code (case-insensitive). It is checked when the cart
+    /// is priced. Returns false if the code was already entered.
+    pub fn apply_coupon(&mut self, code: &str) -> bool {
+        let code = code.trim().to_uppercase();
+        if self.coupons.contains(&code) {
+            return false;
+        }
+        self.coupons.push(code);
+        true
+    }
+
+    pub fn coupons(&self) -> &[String] {
+        &self.coupons
+    }
+
+    /// The exact sum of price × quantity, before any discount or tax;
+    /// only fails if the total overflows. Use a `PricingEngine` for the
+    /// amount the customer pays.
+    pub fn total(&self) -> Result<Money, MoneyError> {
+        let line_totals = self
+            .items
+            .iter()
+            .map(|item| item.price.checked_mul(item.quantity as i64))
+            .collect::<Result<Vec<_>, _>>()?;
+        Money::sum(line_totals, self.currency)
+    }
+}
+
+impl Default for ShoppingCart {
+    fn default() -> Self {
+        ShoppingCart::new()
+    }
+}
+
+//------------------------------------------------------
+// PRICING RULES
+//------------------------------------------------------
+
+/// Which cart lines a percentage discount applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    AllItems,
+    Item(String),
+}
+
+impl Target {
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Target::AllItems => true,
+            Target::Item(item) => item == name,
+        }
+    }
+}
+
+/// A rule applied to each matching cart line
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingRule {
+    /// A percentage off, in basis points: 1000 is 10%
+    PercentOff { target: Target, basis_points: i64 },
+    /// For every `buy` units paid for, `free` more are free
+    BuyNGetM { item: String, buy: u32, free: u32 },
+}
+
+/// What a coupon takes off the order
+#[derive(Debug, Clone, PartialEq)]
+pub enum CouponKind {
+    PercentOff(i64),
+    AmountOff(Money),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coupon {
+    pub code: String,
+    pub kind: CouponKind,
+    /// The order subtotal (after item rules) needed to use the coupon
+    pub minimum_subtotal: Option<Money>,
+}
+
+/// Why a cart could not be priced, or a rule could not be added
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingError {
+    InvalidRule(String),
+    UnknownCoupon(String),
+    CouponNotEligible { code: String, minimum: Money, subtotal: Money },
+    Money(MoneyError),
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PricingError::InvalidRule(message) => write!(f, "invalid pricing rule: {}", message),
+            PricingError::UnknownCoupon(code) => write!(f, "unknown coupon '{}'", code),
+            PricingError::CouponNotEligible { code, minimum, subtotal } => write!(
+                f,
+                "coupon '{}' needs a subtotal of at least {} (cart is {})",
+                code, minimum, subtotal
+            ),
+            PricingError::Money(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PricingError {}
+
+impl From<MoneyError> for PricingError {
+    fn from(e: MoneyError) -> Self {
+        PricingError::Money(e)
+    }
+}
+
+// 825 -> "8.25%", 1250 -> "12.5%", 1000 -> "10%"
+fn format_percent(basis_points: i64) -> String {
+    let whole = basis_points / 100;
+    let fraction = basis_points % 100;
+    if fraction == 0 {
+        format!("{}%", whole)
+    } else {
+        let digits = format!("{:02}", fraction);
+        format!("{}.{}%", whole, digits.trim_end_matches('0'))
+    }
+}
+
+//------------------------------------------------------
+// RECEIPT
+//------------------------------------------------------
+
+/// A discount (negative amount) with the reason it was given
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjustment {
+    pub description: String,
+    pub amount: Money,
+}
+
+/// One cart line after item rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptLine {
+    pub name: String,
+    pub quantity: u32,
+    pub unit_price: Money,
+    pub tax_category: TaxCategory,
+    /// unit price × quantity
+    pub gross: Money,
+    pub adjustments: Vec<Adjustment>,
+    /// gross plus adjustments
+    pub net: Money,
+}
+
+/// Tax charged for one category
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxLine {
+    pub category: TaxCategory,
+    pub basis_points: i64,
+    pub taxable: Money,
+    pub tax: Money,
+}
+
+/// The itemized result of pricing a cart.
+/// `total = subtotal + coupon adjustments + taxes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub lines: Vec<ReceiptLine>,
+    /// Sum of the line nets
+    pub subtotal: Money,
+    /// Coupon discounts on the whole order
+    pub order_adjustments: Vec<Adjustment>,
+    pub taxes: Vec<TaxLine>,
+    pub total: Money,
+}
+
+impl Receipt {
+    /// Everything the customer saved, as a positive amount
+    pub fn savings(&self) -> Result<Money, MoneyError> {
+        let currency = self.total.currency();
+        let line_adjustments = self.lines.iter().flat_map(|line| line.adjustments.iter());
+        let all = line_adjustments.chain(self.order_adjustments.iter()).map(|a| a.amount);
+        Money::sum(all, currency)?.checked_neg()
+    }
+
+    pub fn tax_total(&self) -> Result<Money, MoneyError> {
+        Money::sum(self.taxes.iter().map(|t| t.tax), self.total.currency())
+    }
+}
+
+impl fmt::Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            let label = format!("{} {} x {}", line.name, line.quantity, line.unit_price);
+            writeln!(f, "{:<32}{:>12}", label, line.gross)?;
+            for adjustment in &line.adjustments {
+                writeln!(f, "  {:<30}{:>12}", adjustment.description, adjustment.amount)?;
+            }
+        }
+        writeln!(f, "{:<32}{:>12}", "Subtotal", self.subtotal)?;
+        for adjustment in &self.order_adjustments {
+            writeln!(f, "  {:<30}{:>12}", adjustment.description, adjustment.amount)?;
+        }
+        for tax in &self.taxes {
+            let label = format!("Tax {} {} on {}", tax.category.name(), format_percent(tax.basis_points), tax.taxable);
+            writeln!(f, "{:<32}{:>12}", label, tax.tax)?;
+        }
+        write!(f, "{:<32}{:>12}", "Total", self.total)
+    }
+}
+
+//------------------------------------------------------
+// PRICING ENGINE
+//------------------------------------------------------
+
+/// Item rules, coupons and tax rates for one currency
+#[derive(Debug, Clone)]
+pub struct PricingEngine {
+    currency: Currency,
+    rules: Vec<PricingRule>,
+    coupons: BTreeMap<String, Coupon>,
+    tax_rates: BTreeMap<TaxCategory, i64>,
+}
+
+impl PricingEngine {
+    /// An engine with no rules, no coupons and no tax
+    pub fn new(currency: Currency) -> Self {
+        PricingEngine {
+            currency,
+            rules: Vec::new(),
+            coupons: BTreeMap::new(),
+            tax_rates: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an item rule. Rules apply in the order they were added.
+    pub fn add_rule(&mut self, rule: PricingRule) -> Result<&mut Self, PricingError> {
+        match &rule {
+            PricingRule::PercentOff { basis_points, .. } if !(0..=10_000).contains(basis_points) => {
+                return Err(PricingError::InvalidRule(format!("{} is not a valid percentage", format_percent(*basis_points))));
+            }
+            PricingRule::BuyNGetM { buy, free, .. } if *buy == 0 || *free == 0 => {
+                return Err(PricingError::InvalidRule("buy and free counts must be positive".to_string()));
+            }
+            PricingRule::BuyNGetM { buy, free, .. } if buy.checked_add(*free).is_none() => {
+                return Err(PricingError::InvalidRule(format!("buy {} get {} free is too large a bundle", buy, free)));
+            }
+            _ => {}
+        }
+        self.rules.push(rule);
+        Ok(self)
+    }
+
+    /// Registers a coupon; codes are case-insensitive
+    pub fn add_coupon(&mut self, coupon: Coupon) -> Result<&mut Self, PricingError> {
+        let valid = match &coupon.kind {
+            CouponKind::PercentOff(bp) => (0..=10_000).contains(bp),
+            CouponKind::AmountOff(amount) => amount.currency() == self.currency && !amount.is_negative(),
+        };
+        if !valid || coupon.code.trim().is_empty() {
+            return Err(PricingError::InvalidRule(format!("invalid coupon '{}'", coupon.code)));
+        }
+        // Money in different currencies doesn't compare, so a foreign minimum
+        // would never be enforced
+        if let Some(minimum) = coupon.minimum_subtotal {
+            if minimum.currency() != self.currency {
+                return Err(MoneyError::CurrencyMismatch(self.currency, minimum.currency()).into());
+            }
+        }
+        let code = coupon.code.trim().to_uppercase();
+        self.coupons.insert(code.clone(), Coupon { code, ..coupon });
+        Ok(self)
+    }
+
+    /// Sets the tax rate of a category, in basis points (825 is 8.25%)
+    pub fn set_tax_rate(&mut self, category: TaxCategory, basis_points: i64) -> Result<&mut Self, PricingError> {
+        if category == TaxCategory::Exempt || basis_points < 0 {
+            return Err(PricingError::InvalidRule(format!(
+                "cannot tax the {} category at {}",
+                category.name(),
+                format_percent(basis_points)
+            )));
+        }
+        self.tax_rates.insert(category, basis_points);
+        Ok(self)
+    }
+
+    fn tax_rate(&self, category: TaxCategory) -> i64 {
+        self.tax_rates.get(&category).copied().unwrap_or(0)
+    }
+
+    // Applies the item rules to one cart line
+    fn price_line(&self, item: &CartItem) -> Result<ReceiptLine, PricingError> {
+        let gross = item.price.checked_mul(item.quantity as i64)?;
+        let mut net = gross;
+        let mut adjustments = Vec::new();
+
+        for rule in &self.rules {
+            let (description, discount) = match rule {
+                PricingRule::BuyNGetM { item: name, buy, free } if *name == item.name => {
+                    // `add_rule` checked that the bundle size fits in a u32
+                    let free_units = (item.quantity / (buy + free)) * free;
+                    let description = format!("Buy {} get {} free ({} free)", buy, free, free_units);
+                    (description, item.price.checked_mul(free_units as i64)?)
+                }
+                PricingRule::PercentOff { target, basis_points } if target.matches(&item.name) => {
+                    (format!("{} off", format_percent(*basis_points)), net.percent_bp(*basis_points)?)
+                }
+                _ => continue,
+            };
+            // A line never goes below zero
+            let discount = if discount > net { net } else { discount };
+            if discount.is_zero() {
+                continue;
+            }
+            net = net.checked_sub(discount)?;
+            adjustments.push(Adjustment { description, amount: discount.checked_neg()? });
+        }
+
+        Ok(ReceiptLine {
+            name: item.name.clone(),
+            quantity: item.quantity,
+            unit_price: item.price,
+            tax_category: item.tax_category,
+            gross,
+            adjustments,
+            net,
+        })
+    }
+
+    // Applies the cart's coupons to the subtotal, in the order they were entered
+    fn apply_coupons(&self, cart: &ShoppingCart, subtotal: Money) -> Result<Vec<Adjustment>, PricingError> {
+        let mut remaining = subtotal;
+        let mut adjustments = Vec::new();
+        for code in cart.coupons() {
+            let coupon = self
+                .coupons
+                .get(code)
+                .ok_or_else(|| PricingError::UnknownCoupon(code.clone()))?;
+            if let Some(minimum) = coupon.minimum_subtotal {
+                if subtotal < minimum {
+                    return Err(PricingError::CouponNotEligible { code: code.clone(), minimum, subtotal });
+                }
+            }
+
+            let (description, discount) = match &coupon.kind {
+                CouponKind::PercentOff(bp) => {
+                    (format!("Coupon {} ({} off)", code, format_percent(*bp)), remaining.percent_bp(*bp)?)
+                }
+                CouponKind::AmountOff(amount) => (format!("Coupon {} ({} off)", code, amount), *amount),
+            };
+            let discount = if discount > remaining { remaining } else { discount };
+            remaining = remaining.checked_sub(discount)?;
+            adjustments.push(Adjustment { description, amount: discount.checked_neg()? });
+        }
+        Ok(adjustments)
+    }
+
+    // Spreads the order discount over the lines in proportion to their net
+    // amounts and returns what remains taxable per category
+    fn taxable_amounts(
+        &self,
+        lines: &[ReceiptLine],
+        subtotal: Money,
+        order_discount: Money,
+    ) -> Result<BTreeMap<TaxCategory, Money>, MoneyError> {
+        let mut shares = lines
+            .iter()
+            .map(|line| {
+                if subtotal.is_zero() {
+                    return Ok(Money::zero(self.currency));
+                }
+                let share = order_discount.mul_ratio(line.net.minor_units(), subtotal.minor_units())?;
+                Ok(if share > line.net { line.net } else { share })
+            })
+            .collect::<Result<Vec<_>, MoneyError>>()?;
+
+        // Rounding each share can leave a few cents over (or take a few too
+        // many). Settle them from the last line back, keeping every share
+        // between zero and its line's net so no taxable amount goes negative.
+        let mut discount_left = order_discount.checked_sub(Money::sum(shares.iter().copied(), self.currency)?)?;
+        for (line, share) in lines.iter().zip(shares.iter_mut()).rev() {
+            if discount_left.is_zero() {
+                break;
+            }
+            let change = if discount_left.is_positive() {
+                let room = line.net.checked_sub(*share)?;
+                if discount_left > room { room } else { discount_left }
+            } else {
+                let floor = share.checked_neg()?;
+                if discount_left < floor { floor } else { discount_left }
+            };
+            *share = share.checked_add(change)?;
+            discount_left = discount_left.checked_sub(change)?;
+        }
+
+        let mut taxable = BTreeMap::new();
+        for (line, share) in lines.iter().zip(shares) {
+            let entry = taxable.entry(line.tax_category).or_insert(Money::zero(self.currency));
+            *entry = entry.checked_add(line.net.checked_sub(share)?)?;
+        }
+        Ok(taxable)
+    }
+
+    /// Prices a cart and explains every adjustment in the receipt
+    pub fn price(&self, cart: &ShoppingCart) -> Result<Receipt, PricingError> {
+        if cart.currency() != self.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, cart.currency()).into());
+        }
+
+        let lines = cart
+            .get_items()
+            .iter()
+            .map(|item| self.price_line(item))
+            .collect::<Result<Vec<_>, _>>()?;
+        let subtotal = Money::sum(lines.iter().map(|line| line.net), self.currency)?;
+
+        let order_adjustments = self.apply_coupons(cart, subtotal)?;
+        let order_discount = Money::sum(order_adjustments.iter().map(|a| a.amount), self.currency)?.checked_neg()?;
+
+        let mut taxes = Vec::new();
+        for (category, taxable) in self.taxable_amounts(&lines, subtotal, order_discount)? {
+            let basis_points = self.tax_rate(category);
+            if basis_points > 0 && !taxable.is_zero() {
+                taxes.push(TaxLine { category, basis_points, taxable, tax: taxable.percent_bp(basis_points)? });
+            }
+        }
+
+        let tax_total = Money::sum(taxes.iter().map(|t| t.tax), self.currency)?;
+        let total = subtotal.checked_sub(order_discount)?.checked_add(tax_total)?;
+        Ok(Receipt { lines, subtotal, order_adjustments, taxes, total })
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(text: &str) -> Money {
+        Money::parse(text, Currency::USD).unwrap()
+    }
+
+    fn coupon(code: &str, kind: CouponKind) -> Coupon {
+        Coupon { code: code.to_string(), kind, minimum_subtotal: None }
+    }
+
+    #[test]
+    fn test_cent_totals_are_exact() {
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Candy", usd("0.10"), 1).unwrap();
+        cart.add_item("Gum", usd("0.20"), 1).unwrap();
+        // 0.1 + 0.2 in f64 is 0.30000000000000004
+        assert_eq!(cart.total(), Ok(usd("0.30")));
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Sticker", usd("0.01"), 100).unwrap();
+        cart.add_item("Book", usd("19.99"), 3).unwrap();
+        cart.add_item("Pen", usd("1.15"), 7).unwrap();
+        assert_eq!(cart.total(), Ok(usd("69.02")));
+        assert_eq!(ShoppingCart::new().total(), Ok(usd("0")));
+    }
+
+    #[test]
+    fn test_same_name_merges_quantities() {
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Apple", usd("0.50"), 2).unwrap();
+        cart.add_item("Bread", usd("2.49"), 1).unwrap();
+        cart.add_item("Apple", usd("0.50"), 3).unwrap();
+
+        assert_eq!(cart.get_items().len(), 2);
+        assert_eq!(cart.get_item("Apple").unwrap().quantity, 5);
+        assert_eq!(cart.total(), Ok(usd("4.99")));
+
+        // A different price for the same name is a mistake, not a new line
+        assert_eq!(
+            cart.add_item("Apple", usd("0.45"), 1),
+            Err(CartError::ItemConflict { name: "Apple".to_string() })
+        );
+        assert_eq!(cart.add_item("Pear", usd("0.45"), 0), Err(CartError::InvalidQuantity(0)));
+        assert!(cart.add_item("Apple", usd("0.50"), u32::MAX).is_err());
+        assert_eq!(cart.get_item("Apple").unwrap().quantity, 5);
+    }
+
+    #[test]
+    fn test_prices_must_match_cart_currency() {
+        let mut cart = ShoppingCart::new();
+        let result = cart.add_item("Croissant", Money::new(150, Currency::EUR), 1);
+
+        assert_eq!(result, Err(CartError::Money(MoneyError::CurrencyMismatch(Currency::USD, Currency::EUR))));
+        assert!(cart.is_empty());
+        assert!(PricingEngine::new(Currency::EUR).price(&cart).is_err());
+    }
+
+    #[test]
+    fn test_negative_prices_are_rejected() {
+        let mut cart = ShoppingCart::new();
+        assert_eq!(cart.add_item("Refund", usd("-5.00"), 1), Err(CartError::InvalidPrice(usd("-5.00"))));
+        assert!(cart.is_empty());
+
+        // Free items are fine
+        cart.add_item("Sample", usd("0"), 1).unwrap();
+        assert_eq!(cart.total(), Ok(usd("0")));
+    }
+
+    #[test]
+    fn test_total_after_removing_items() {
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Apple", usd("0.33"), 3).unwrap();
+        cart.add_item("Bread", usd("2.49"), 1).unwrap();
+        assert!(cart.remove_item("Bread"));
+        assert!(!cart.remove_item("Bread"));
+        assert_eq!(cart.total(), Ok(usd("0.99")));
+    }
+
+    #[test]
+    fn test_overflowing_total_is_an_error() {
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Yacht", Money::usd(i64::MAX / 2), 3).unwrap();
+        assert_eq!(cart.total(), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn test_buy_n_get_m() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .add_rule(PricingRule::BuyNGetM { item: "Seed packet".to_string(), buy: 2, free: 1 })
+            .unwrap();
+
+        // Test cases: (quantity, expected net)
+        let test_cases = vec![(1, "3.00"), (2, "6.00"), (3, "6.00"), (5, "12.00"), (6, "12.00"), (7, "15.00")];
+        for (quantity, expected) in test_cases {
+            let mut cart = ShoppingCart::new();
+            cart.add_item("Seed packet", usd("3.00"), quantity).unwrap();
+            let receipt = engine.price(&cart).unwrap();
+            assert_eq!(receipt.total, usd(expected), "quantity {}", quantity);
+        }
+
+        assert!(engine.add_rule(PricingRule::BuyNGetM { item: "x".to_string(), buy: 0, free: 1 }).is_err());
+        let huge = PricingRule::BuyNGetM { item: "x".to_string(), buy: u32::MAX, free: 1 };
+        assert_eq!(
+            engine.add_rule(huge).err(),
+            Some(PricingError::InvalidRule("buy 4294967295 get 1 free is too large a bundle".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_percent_discounts_stack_in_order() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .add_rule(PricingRule::PercentOff { target: Target::Item("Shovel".to_string()), basis_points: 2000 })
+            .unwrap()
+            .add_rule(PricingRule::PercentOff { target: Target::AllItems, basis_points: 1000 })
+            .unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Shovel", usd("25.00"), 1).unwrap();
+        cart.add_item("Gloves", usd("8.99"), 1).unwrap();
+        let receipt = engine.price(&cart).unwrap();
+
+        // $25.00 - 20% = $20.00, then - 10% = $18.00
+        let shovel = &receipt.lines[0];
+        assert_eq!(shovel.adjustments.len(), 2);
+        assert_eq!(shovel.adjustments[0].amount, usd("-5.00"));
+        assert_eq!(shovel.adjustments[1].amount, usd("-2.00"));
+        assert_eq!(shovel.net, usd("18.00"));
+        // 10% of $8.99 is 89.9 cents
+        assert_eq!(receipt.lines[1].net, usd("8.09"));
+        assert_eq!(receipt.total, usd("26.09"));
+        assert_eq!(receipt.savings(), Ok(usd("7.90")));
+
+        let too_much = PricingRule::PercentOff { target: Target::AllItems, basis_points: 12_000 };
+        assert!(engine.add_rule(too_much).is_err());
+    }
+
+    #[test]
+    fn test_percent_discount_rounds_half_to_even() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine.add_rule(PricingRule::PercentOff { target: Target::AllItems, basis_points: 1000 }).unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Washer", usd("0.25"), 1).unwrap();
+        cart.add_item("Nut", usd("0.35"), 1).unwrap();
+        let receipt = engine.price(&cart).unwrap();
+
+        // 2.5 cents rounds down to 2, 3.5 cents rounds up to 4
+        assert_eq!(receipt.lines[0].adjustments[0].amount, usd("-0.02"));
+        assert_eq!(receipt.lines[1].adjustments[0].amount, usd("-0.04"));
+    }
+
+    #[test]
+    fn test_coupons() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .add_coupon(coupon("save10", CouponKind::PercentOff(1000)))
+            .unwrap()
+            .add_coupon(Coupon {
+                code: "FIVEOFF".to_string(),
+                kind: CouponKind::AmountOff(usd("5.00")),
+                minimum_subtotal: Some(usd("30.00")),
+            })
+            .unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Hose", usd("40.00"), 1).unwrap();
+        assert!(cart.apply_coupon("Save10"));
+        assert!(!cart.apply_coupon("SAVE10 "));
+        cart.apply_coupon("fiveoff");
+
+        let receipt = engine.price(&cart).unwrap();
+        assert_eq!(receipt.subtotal, usd("40.00"));
+        assert_eq!(receipt.order_adjustments[0].description, "Coupon SAVE10 (10% off)");
+        assert_eq!(receipt.order_adjustments[0].amount, usd("-4.00"));
+        assert_eq!(receipt.order_adjustments[1].amount, usd("-5.00"));
+        assert_eq!(receipt.total, usd("31.00"));
+
+        // Too small for FIVEOFF
+        let mut small = ShoppingCart::new();
+        small.add_item("Twine", usd("3.00"), 1).unwrap();
+        small.apply_coupon("FIVEOFF");
+        assert_eq!(
+            engine.price(&small),
+            Err(PricingError::CouponNotEligible {
+                code: "FIVEOFF".to_string(),
+                minimum: usd("30.00"),
+                subtotal: usd("3.00"),
+            })
+        );
+
+        let mut unknown = ShoppingCart::new();
+        unknown.apply_coupon("FREESTUFF");
+        assert_eq!(engine.price(&unknown), Err(PricingError::UnknownCoupon("FREESTUFF".to_string())));
+    }
+
+    #[test]
+    fn test_amount_coupon_never_goes_below_zero() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine.add_coupon(coupon("TENOFF", CouponKind::AmountOff(usd("10.00")))).unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Label", usd("0.75"), 2).unwrap();
+        cart.apply_coupon("TENOFF");
+
+        let receipt = engine.price(&cart).unwrap();
+        assert_eq!(receipt.order_adjustments[0].amount, usd("-1.50"));
+        assert_eq!(receipt.total, usd("0"));
+    }
+
+    #[test]
+    fn test_tax_per_category() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .set_tax_rate(TaxCategory::Standard, 825)
+            .unwrap()
+            .set_tax_rate(TaxCategory::Reduced, 200)
+            .unwrap();
+        assert!(engine.set_tax_rate(TaxCategory::Exempt, 500).is_err());
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Pot", usd("19.99"), 1).unwrap();
+        cart.add_taxed_item("Bread", usd("3.00"), 2, TaxCategory::Reduced).unwrap();
+        cart.add_taxed_item("Seeds", usd("4.00"), 1, TaxCategory::Exempt).unwrap();
+        let receipt = engine.price(&cart).unwrap();
+
+        // 8.25% of $19.99 = 164.9175 cents, 2% of $6.00 = 12 cents
+        let taxes: Vec<(TaxCategory, Money)> = receipt.taxes.iter().map(|t| (t.category, t.tax)).collect();
+        assert_eq!(taxes, vec![(TaxCategory::Standard, usd("1.65")), (TaxCategory::Reduced, usd("0.12"))]);
+        assert_eq!(receipt.tax_total(), Ok(usd("1.77")));
+        assert_eq!(receipt.total, usd("31.76"));
+    }
+
+    #[test]
+    fn test_coupon_reduces_taxable_amount_proportionally() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine.set_tax_rate(TaxCategory::Standard, 1000).unwrap();
+        engine.add_coupon(coupon("TENOFF", CouponKind::AmountOff(usd("10.00")))).unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Rake", usd("30.00"), 1).unwrap();
+        cart.add_taxed_item("Seeds", usd("10.00"), 1, TaxCategory::Exempt).unwrap();
+        cart.apply_coupon("TENOFF");
+        let receipt = engine.price(&cart).unwrap();
+
+        // The rake is 3/4 of the order, so it carries $7.50 of the coupon
+        assert_eq!(receipt.taxes.len(), 1);
+        assert_eq!(receipt.taxes[0].taxable, usd("22.50"));
+        assert_eq!(receipt.taxes[0].tax, usd("2.25"));
+        assert_eq!(receipt.total, usd("32.25"));
+    }
+
+    #[test]
+    fn test_coupon_never_makes_a_taxable_amount_negative() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .set_tax_rate(TaxCategory::Standard, 10_000)
+            .unwrap()
+            .set_tax_rate(TaxCategory::Reduced, 10_000)
+            .unwrap()
+            .add_coupon(coupon("QUARTER", CouponKind::AmountOff(usd("0.25"))))
+            .unwrap();
+
+        // Each $0.03 line's share rounds down to $0.02, which used to leave
+        // $0.05 of the coupon for the last line, a $0.01 one
+        let mut cart = ShoppingCart::new();
+        for index in 0..10 {
+            cart.add_taxed_item(&format!("Seed packet {}", index), usd("0.03"), 1, TaxCategory::Reduced).unwrap();
+        }
+        cart.add_item("Tag", usd("0.01"), 1).unwrap();
+        cart.apply_coupon("QUARTER");
+        let receipt = engine.price(&cart).unwrap();
+
+        assert!(receipt.taxes.iter().all(|t| !t.taxable.is_negative() && !t.tax.is_negative()));
+        let taxable = Money::sum(receipt.taxes.iter().map(|t| t.taxable), Currency::USD).unwrap();
+        assert_eq!(taxable, usd("0.06"));
+        assert_eq!(receipt.total, usd("0.12"));
+    }
+
+    #[test]
+    fn test_coupon_minimum_must_match_engine_currency() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        let result = engine.add_coupon(Coupon {
+            code: "HALF".to_string(),
+            kind: CouponKind::PercentOff(5000),
+            minimum_subtotal: Some(Money::new(5000, Currency::EUR)),
+        });
+        assert_eq!(
+            result.err(),
+            Some(PricingError::Money(MoneyError::CurrencyMismatch(Currency::USD, Currency::EUR)))
+        );
+
+        // So it can't sneak past the minimum on a $1.00 cart
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Twine", usd("1.00"), 1).unwrap();
+        cart.apply_coupon("HALF");
+        assert_eq!(engine.price(&cart), Err(PricingError::UnknownCoupon("HALF".to_string())));
+    }
+
+    #[test]
+    fn test_receipt_adds_up() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .add_rule(PricingRule::BuyNGetM { item: "Bulb".to_string(), buy: 3, free: 1 })
+            .unwrap()
+            .add_rule(PricingRule::PercentOff { target: Target::AllItems, basis_points: 750 })
+            .unwrap()
+            .set_tax_rate(TaxCategory::Standard, 825)
+            .unwrap()
+            .add_coupon(coupon("SPRING", CouponKind::PercentOff(1500)))
+            .unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Bulb", usd("1.33"), 9).unwrap();
+        cart.add_item("Trowel", usd("7.77"), 1).unwrap();
+        cart.add_item("Compost", usd("12.49"), 3).unwrap();
+        cart.apply_coupon("spring");
+        let receipt = engine.price(&cart).unwrap();
+
+        for line in &receipt.lines {
+            let adjusted = Money::sum(line.adjustments.iter().map(|a| a.amount), Currency::USD).unwrap();
+            assert_eq!(line.gross.checked_add(adjusted), Ok(line.net));
+        }
+        let nets = Money::sum(receipt.lines.iter().map(|l| l.net), Currency::USD).unwrap();
+        assert_eq!(nets, receipt.subtotal);
+
+        let coupons = Money::sum(receipt.order_adjustments.iter().map(|a| a.amount), Currency::USD).unwrap();
+        let expected = receipt.subtotal.checked_add(coupons).unwrap().checked_add(receipt.tax_total().unwrap());
+        assert_eq!(expected, Ok(receipt.total));
+
+        // Gross minus savings plus tax is also the total
+        let gross = cart.total().unwrap();
+        let from_gross = gross.checked_sub(receipt.savings().unwrap()).unwrap().checked_add(receipt.tax_total().unwrap());
+        assert_eq!(from_gross, Ok(receipt.total));
+    }
+
+    #[test]
+    fn test_receipt_display() {
+        let mut engine = PricingEngine::new(Currency::USD);
+        engine
+            .add_rule(PricingRule::BuyNGetM { item: "Rust Book".to_string(), buy: 2, free: 1 })
+            .unwrap()
+            .set_tax_rate(TaxCategory::Standard, 825)
+            .unwrap()
+            .add_coupon(coupon("SAVE10", CouponKind::PercentOff(1000)))
+            .unwrap();
+
+        let mut cart = ShoppingCart::new();
+        cart.add_item("Rust Book", usd("29.99"), 3).unwrap();
+        cart.add_item("Candy", usd("0.10"), 1).unwrap();
+        cart.apply_coupon("SAVE10");
+
+        let expected = "\
+Rust Book 3 x $29.99                  $89.97
+  Buy 2 get 1 free (1 free)          -$29.99
+Candy 1 x $0.10                        $0.10
+Subtotal                              $60.08
+  Coupon SAVE10 (10% off)             -$6.01
+Tax standard 8.25% on $54.07           $4.46
+Total                                 $58.53";
+        assert_eq!(engine.price(&cart).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn test_format_percent() {
+        assert_eq!(format_percent(1000), "10%");
+        assert_eq!(format_percent(825), "8.25%");
+        assert_eq!(format_percent(1250), "12.5%");
+        assert_eq!(format_percent(5), "0.05%");
+    }
+}
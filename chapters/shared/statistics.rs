@@ -0,0 +1,680 @@
+// Statistics
+//
+// Descriptive statistics over slices of numbers (`&[f64]`, `&[i32]`, ...):
+// mean, median, modes, variance, standard deviation, percentiles,
+// a one-call `Summary`, and histograms.
+//
+// There are also streaming (online) versions that see one value at a time
+// and keep only a few numbers of state, for data that does not fit in a Vec:
+//
+// - `RunningStats`: count, sum, min, max, mean and variance (Welford's algorithm)
+// - `StreamingQuantile`: an estimate of a percentile such as the median (the P² algorithm)
+// - `Histogram::add`: bins filled as values arrive
+//
+// NaN values carry no information and are skipped everywhere.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::fmt;
+
+//------------------------------------------------------
+// SAMPLES
+//------------------------------------------------------
+
+/// A number that can be used as a statistical sample
+pub trait Sample: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_sample {
+    ($($t:ty),*) => {
+        $(impl Sample for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_sample!(f64, f32, i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+// The values as f64, without NaNs
+fn to_f64s<T: Sample>(values: &[T]) -> Vec<f64> {
+    values.iter().map(|v| v.to_f64()).filter(|v| !v.is_nan()).collect()
+}
+
+// The values as f64, without NaNs, in ascending order
+fn sorted<T: Sample>(values: &[T]) -> Vec<f64> {
+    let mut values = to_f64s(values);
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+//------------------------------------------------------
+// SLICE STATISTICS
+//------------------------------------------------------
+
+pub fn mean<T: Sample>(values: &[T]) -> Option<f64> {
+    let values = to_f64s(values);
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// The middle value; for an even count, the mean of the two middle values
+pub fn median<T: Sample>(values: &[T]) -> Option<f64> {
+    percentile(values, 50.0)
+}
+
+/// The most frequent values, in ascending order. Every value is a mode
+/// when they all appear equally often.
+pub fn modes<T: Sample>(values: &[T]) -> Vec<f64> {
+    let values = sorted(values);
+    let mut modes = Vec::new();
+    let mut best = 0;
+    let mut start = 0;
+    while start < values.len() {
+        let run = values[start..].iter().take_while(|v| **v == values[start]).count();
+        if run > best {
+            best = run;
+            modes.clear();
+        }
+        if run == best {
+            modes.push(values[start]);
+        }
+        start += run;
+    }
+    modes
+}
+
+/// Whether the variance describes the whole population, or estimates it from a sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variance {
+    /// Divide by n
+    Population,
+    /// Divide by n - 1 (Bessel's correction); needs at least two values
+    Sample,
+}
+
+impl Variance {
+    fn divisor(&self, count: usize) -> Option<f64> {
+        match self {
+            Variance::Population if count >= 1 => Some(count as f64),
+            Variance::Sample if count >= 2 => Some((count - 1) as f64),
+            _ => None,
+        }
+    }
+}
+
+pub fn variance<T: Sample>(values: &[T], kind: Variance) -> Option<f64> {
+    let values = to_f64s(values);
+    let divisor = kind.divisor(values.len())?;
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+    Some(squares / divisor)
+}
+
+pub fn std_dev<T: Sample>(values: &[T], kind: Variance) -> Option<f64> {
+    variance(values, kind).map(f64::sqrt)
+}
+
+/// The value below which `p` percent of the values fall, for `p` in 0..=100.
+/// Interpolates linearly between neighbours, like spreadsheets' PERCENTILE.
+pub fn percentile<T: Sample>(values: &[T], p: f64) -> Option<f64> {
+    if !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+    let values = sorted(values);
+    if values.is_empty() {
+        return None;
+    }
+    let position = p / 100.0 * (values.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    Some(values[lower] + (values[upper] - values[lower]) * fraction)
+}
+
+/// The 25th, 50th and 75th percentiles
+pub fn quartiles<T: Sample>(values: &[T]) -> Option<(f64, f64, f64)> {
+    Some((percentile(values, 25.0)?, percentile(values, 50.0)?, percentile(values, 75.0)?))
+}
+
+/// The usual numbers describing a data set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    /// Population variance
+    pub variance: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+}
+
+pub fn summarize<T: Sample>(values: &[T]) -> Option<Summary> {
+    let sorted = sorted(values);
+    let (&min, &max) = (sorted.first()?, sorted.last()?);
+    let sum: f64 = sorted.iter().sum();
+    let variance = variance(&sorted, Variance::Population)?;
+    Some(Summary {
+        count: sorted.len(),
+        sum,
+        min,
+        max,
+        mean: sum / sorted.len() as f64,
+        median: median(&sorted)?,
+        variance,
+        std_dev: variance.sqrt(),
+    })
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n={} min={:.2} max={:.2} mean={:.2} median={:.2} sd={:.2}",
+            self.count, self.min, self.max, self.mean, self.median, self.std_dev
+        )
+    }
+}
+
+//------------------------------------------------------
+// HISTOGRAMS
+//------------------------------------------------------
+
+/// Counts of values in equal-width bins between `start` and `end`.
+/// Each bin includes its lower edge; the last bin also includes `end`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    start: f64,
+    end: f64,
+    counts: Vec<usize>,
+    /// Values below `start`
+    pub below: usize,
+    /// Values above `end`
+    pub above: usize,
+}
+
+impl Histogram {
+    /// An empty histogram; None unless `start < end` and `bins > 0`
+    pub fn with_range(start: f64, end: f64, bins: usize) -> Option<Histogram> {
+        if bins == 0 || !start.is_finite() || !end.is_finite() || start >= end {
+            return None;
+        }
+        Some(Histogram { start, end, counts: vec![0; bins], below: 0, above: 0 })
+    }
+
+    /// A histogram spanning the values' minimum to maximum
+    pub fn from_values<T: Sample>(values: &[T], bins: usize) -> Option<Histogram> {
+        let values = sorted(values);
+        let (&min, &max) = (values.first()?, values.last()?);
+        // A single distinct value still gets a bin of width 1
+        let end = if max > min { max } else { min + 1.0 };
+        let mut histogram = Histogram::with_range(min, end, bins)?;
+        for value in values {
+            histogram.add(value);
+        }
+        Some(histogram)
+    }
+
+    /// Counts one value (the streaming way to fill a histogram)
+    pub fn add<T: Sample>(&mut self, value: T) {
+        let value = value.to_f64();
+        if value.is_nan() {
+            return;
+        }
+        if value < self.start {
+            self.below += 1;
+        } else if value > self.end {
+            self.above += 1;
+        } else {
+            let bins = self.counts.len();
+            let index = ((value - self.start) / self.bin_width()) as usize;
+            self.counts[index.min(bins - 1)] += 1;
+        }
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        (self.end - self.start) / self.counts.len() as f64
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Each bin's range and count
+    pub fn bins(&self) -> Vec<(f64, f64, usize)> {
+        let width = self.bin_width();
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (self.start + width * i as f64, self.start + width * (i + 1) as f64, count))
+            .collect()
+    }
+
+    /// Values counted in a bin (not `below` or `above`)
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+impl fmt::Display for Histogram {
+    /// One line per bin, with its count and a bar of `#`s:
+    ///
+    ///     [  0.0,   5.0)   3 ###
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bins = self.bins();
+        for (i, (low, high, count)) in bins.iter().enumerate() {
+            let close = if i + 1 == bins.len() { ']' } else { ')' };
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let line = format!("[{:>5.1}, {:>5.1}{} {:>3} {}", low, high, close, count, "#".repeat(*count));
+            write!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------
+// STREAMING STATISTICS
+//------------------------------------------------------
+
+/// Count, sum, min, max, mean and variance of values seen one at a time,
+/// in constant memory. Uses Welford's algorithm, which stays accurate
+/// where the naive "sum of squares" formula loses precision.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    // Sum of squared differences from the current mean
+    m2: f64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        RunningStats::default()
+    }
+
+    pub fn push<T: Sample>(&mut self, value: T) {
+        let value = value.to_f64();
+        if value.is_nan() {
+            return;
+        }
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Combines the statistics of two streams, as if all values had gone
+    /// through one (Chan et al.'s parallel formula)
+    pub fn merge(&self, other: &RunningStats) -> RunningStats {
+        if self.count == 0 {
+            return *other;
+        }
+        if other.count == 0 {
+            return *self;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let mean = self.mean + delta * other.count as f64 / count as f64;
+        let m2 = self.m2 + other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64;
+        RunningStats {
+            count,
+            mean,
+            m2,
+            sum: self.sum + other.sum,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    pub fn variance(&self, kind: Variance) -> Option<f64> {
+        kind.divisor(self.count).map(|divisor| self.m2 / divisor)
+    }
+
+    pub fn std_dev(&self, kind: Variance) -> Option<f64> {
+        self.variance(kind).map(f64::sqrt)
+    }
+}
+
+impl<T: Sample> Extend<T> for RunningStats {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Sample> FromIterator<T> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut stats = RunningStats::new();
+        stats.extend(values);
+        stats
+    }
+}
+
+/// An estimate of one percentile over a stream, in constant memory.
+///
+/// Uses the P² algorithm (Jain and Chlamtac, 1985): five markers track the
+/// minimum, the maximum, the wanted percentile and two points around it,
+/// and are nudged along a parabola as values arrive. The estimate is exact
+/// for up to five values and usually within a few percent afterwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingQuantile {
+    p: f64,
+    count: usize,
+    // Marker heights, actual positions and desired positions
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl StreamingQuantile {
+    /// Tracks percentile `p` (0..=100); None if `p` is out of range
+    pub fn new(p: f64) -> Option<Self> {
+        if !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let q = p / 100.0;
+        Some(StreamingQuantile {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * q, 1.0 + 4.0 * q, 3.0 + 2.0 * q, 5.0],
+            increments: [0.0, q / 2.0, q, (1.0 + q) / 2.0, 1.0],
+        })
+    }
+
+    /// A streaming median
+    pub fn median() -> Self {
+        StreamingQuantile::new(50.0).unwrap()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn push<T: Sample>(&mut self, value: T) {
+        let x = value.to_f64();
+        if x.is_nan() {
+            return;
+        }
+        // The first five values are kept as they are
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Find the cell the value falls in, stretching the extremes if needed
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < self.heights[i + 1]).unwrap_or(3)
+        };
+        for position in &mut self.positions[k + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        // Move the three middle markers towards their desired positions
+        for i in 1..4 {
+            let offset = self.desired[i] - self.positions[i];
+            let room_right = self.positions[i + 1] - self.positions[i] > 1.0;
+            let room_left = self.positions[i - 1] - self.positions[i] < -1.0;
+            if (offset >= 1.0 && room_right) || (offset <= -1.0 && room_left) {
+                let step = offset.signum();
+                let candidate = self.parabolic(i, step);
+                self.heights[i] = if self.heights[i - 1] < candidate && candidate < self.heights[i + 1] {
+                    candidate
+                } else {
+                    self.linear(i, step)
+                };
+                self.positions[i] += step;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, step: f64) -> f64 {
+        let (n, q) = (&self.positions, &self.heights);
+        q[i] + step / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + step) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - step) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, step: f64) -> f64 {
+        let j = if step > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i] + step * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// The current estimate; None before the first value
+    pub fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            // Too few values for the markers: compute it exactly
+            1..=5 => percentile(&self.heights[..self.count], self.p),
+            _ => Some(self.heights[2]),
+        }
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_mean_and_median() {
+        assert_eq!(mean(&[5, 2, 9, 1, 7, 6, 3, 8, 4]), Some(5.0));
+        assert_eq!(mean(&[1.5, 2.5]), Some(2.0));
+        assert_eq!(mean::<i32>(&[]), None);
+
+        assert_eq!(median(&[3, 1, 2]), Some(2.0));
+        assert_eq!(median(&[4, 1, 3, 2]), Some(2.5));
+        assert_eq!(median(&[7.0]), Some(7.0));
+        assert_eq!(median::<f64>(&[]), None);
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!(modes(&[1, 2, 2, 3, 3, 3]), vec![3.0]);
+        assert_eq!(modes(&[4, 1, 4, 1, 2]), vec![1.0, 4.0]);
+        assert_eq!(modes(&[1, 2, 3]), vec![1.0, 2.0, 3.0]);
+        assert!(modes::<i32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_variance_and_std_dev() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(variance(&values, Variance::Population), Some(4.0));
+        assert_eq!(std_dev(&values, Variance::Population), Some(2.0));
+        assert!(close(variance(&values, Variance::Sample).unwrap(), 32.0 / 7.0));
+
+        assert_eq!(variance(&[3], Variance::Population), Some(0.0));
+        assert_eq!(variance(&[3], Variance::Sample), None);
+        assert_eq!(variance::<i32>(&[], Variance::Population), None);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let values = [15, 20, 35, 40, 50];
+        // Test cases: (percentile, expected)
+        let test_cases = vec![(0.0, 15.0), (25.0, 20.0), (40.0, 29.0), (50.0, 35.0), (100.0, 50.0)];
+        for (p, expected) in test_cases {
+            assert!(close(percentile(&values, p).unwrap(), expected), "percentile {}", p);
+        }
+        assert_eq!(percentile(&values, 101.0), None);
+        assert_eq!(percentile(&values, f64::NAN), None);
+        assert_eq!(quartiles(&[1, 2, 3, 4, 5]), Some((2.0, 3.0, 4.0)));
+    }
+
+    #[test]
+    fn test_nan_is_skipped() {
+        let values = [1.0, f64::NAN, 3.0];
+        assert_eq!(mean(&values), Some(2.0));
+        assert_eq!(median(&values), Some(2.0));
+        assert_eq!(mean(&[f64::NAN]), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = summarize(&[5, 2, 9, 1, 7, 6, 3, 8, 4]).unwrap();
+        assert_eq!(summary.count, 9);
+        assert_eq!((summary.min, summary.max, summary.sum), (1.0, 9.0, 45.0));
+        assert_eq!((summary.mean, summary.median), (5.0, 5.0));
+        assert!(close(summary.variance, 60.0 / 9.0));
+        assert_eq!(summary.to_string(), "n=9 min=1.00 max=9.00 mean=5.00 median=5.00 sd=2.58");
+        assert_eq!(summarize::<u8>(&[]), None);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let histogram = Histogram::from_values(&[1, 2, 2, 3, 5, 9, 10], 3).unwrap();
+        // Bins of width 3: [1, 4) [4, 7) [7, 10]
+        assert_eq!(histogram.counts(), &[4, 1, 2]);
+        assert_eq!(histogram.bin_width(), 3.0);
+        assert_eq!(histogram.total(), 7);
+
+        let mut grades = Histogram::with_range(0.0, 100.0, 10).unwrap();
+        for grade in [100.0, 95.0, 90.0, 89.9, 42.0, -5.0, 120.0] {
+            grades.add(grade);
+        }
+        assert_eq!(grades.counts()[9], 3);
+        assert_eq!(grades.counts()[8], 1);
+        assert_eq!(grades.counts()[4], 1);
+        assert_eq!((grades.below, grades.above), (1, 1));
+
+        let single = Histogram::from_values(&[4, 4], 2).unwrap();
+        assert_eq!(single.counts(), &[2, 0]);
+        assert!(Histogram::with_range(1.0, 1.0, 4).is_none());
+        assert!(Histogram::with_range(0.0, 1.0, 0).is_none());
+        assert!(Histogram::from_values::<i32>(&[], 4).is_none());
+    }
+
+    #[test]
+    fn test_histogram_display() {
+        let histogram = Histogram::from_values(&[0, 1, 1, 4], 2).unwrap();
+        assert_eq!(histogram.to_string(), "[  0.0,   2.0)   3 ###\n[  2.0,   4.0]   1 #");
+    }
+
+    #[test]
+    fn test_running_stats_match_slice_functions() {
+        let values = [2.5, -1.0, 7.25, 3.0, 3.0, 10.0, 0.5];
+        let stats: RunningStats = values.iter().copied().collect();
+
+        assert_eq!(stats.count(), 7);
+        assert_eq!(stats.min(), Some(-1.0));
+        assert_eq!(stats.max(), Some(10.0));
+        assert!(close(stats.sum(), 25.25));
+        assert!(close(stats.mean().unwrap(), mean(&values).unwrap()));
+        for kind in [Variance::Population, Variance::Sample] {
+            assert!(close(stats.variance(kind).unwrap(), variance(&values, kind).unwrap()));
+        }
+
+        let empty = RunningStats::new();
+        assert_eq!((empty.mean(), empty.min(), empty.variance(Variance::Population)), (None, None, None));
+    }
+
+    #[test]
+    fn test_running_stats_merge() {
+        let left: RunningStats = [1, 2, 3, 4].into_iter().collect();
+        let right: RunningStats = [10, 20].into_iter().collect();
+        let all: RunningStats = [1, 2, 3, 4, 10, 20].into_iter().collect();
+
+        let merged = left.merge(&right);
+        assert_eq!(merged.count(), all.count());
+        assert!(close(merged.mean().unwrap(), all.mean().unwrap()));
+        assert!(close(merged.variance(Variance::Sample).unwrap(), all.variance(Variance::Sample).unwrap()));
+        assert_eq!(merged.max(), Some(20.0));
+        assert_eq!(RunningStats::new().merge(&right), right);
+    }
+
+    #[test]
+    fn test_running_stats_stay_accurate_with_large_offsets() {
+        // The naive sum-of-squares formula loses everything here
+        let stats: RunningStats = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0].into_iter().collect();
+        assert!(close(stats.variance(Variance::Sample).unwrap(), 30.0));
+    }
+
+    #[test]
+    fn test_streaming_quantile() {
+        let mut median = StreamingQuantile::median();
+        assert_eq!(median.estimate(), None);
+        for value in [5, 1, 3] {
+            median.push(value);
+        }
+        // Exact while there are five values or fewer
+        assert_eq!(median.estimate(), Some(3.0));
+
+        // A shuffled 1..=1000 (multiplying by 7919 permutes the residues mod 1000)
+        let values: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1000 + 1).collect();
+        let mut median = StreamingQuantile::median();
+        let mut p90 = StreamingQuantile::new(90.0).unwrap();
+        for &value in &values {
+            median.push(value);
+            p90.push(value);
+        }
+        assert_eq!(median.count(), 1000);
+        assert!((median.estimate().unwrap() - 500.5).abs() < 15.0, "{:?}", median.estimate());
+        assert!((p90.estimate().unwrap() - 900.1).abs() < 15.0, "{:?}", p90.estimate());
+
+        assert!(StreamingQuantile::new(-1.0).is_none());
+    }
+}
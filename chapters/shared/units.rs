@@ -0,0 +1,516 @@
+// Units of Measure
+//
+// Numbers that know their unit, so the compiler catches mix-ups that bare
+// `f64`s let through:
+//
+// - `Temperature<Celsius>`, `Temperature<Fahrenheit>` and
+//   `Temperature<Kelvin>`. Temperature scales have different zero points,
+//   so they convert through Kelvin rather than by a factor.
+// - `Quantity<U>` for lengths, masses and times, where `U` is a unit such
+//   as `Kilometer`, `Pound` or `Hour`.
+//
+// The unit is a marker type (a struct with no fields) in the type
+// parameter. It costs nothing at run time: a `Quantity<Meter>` is just an
+// `f64`. But `Quantity<Meter> + Quantity<Foot>` doesn't compile, and
+// neither does converting meters to kilograms: `to` only accepts a unit of
+// the same dimension.
+//
+// Both kinds parse from text such as "98.6°F", "37 C", "3 km" or
+// "2.5 hours", converting to the unit asked for.
+//
+// This file is a library module; chapter files load it with `#[path]`.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+//------------------------------------------------------
+// ERRORS
+//------------------------------------------------------
+
+/// Why text could not be read as a temperature or quantity
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    InvalidNumber(String),
+    MissingUnit(String),
+    UnknownUnit(String),
+    /// A real unit, but not one for what was asked, like "3 kg" as a length
+    WrongDimension { unit: String, expected: &'static str },
+    /// Nothing is colder than 0 K
+    BelowAbsoluteZero(String),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::InvalidNumber(text) => write!(f, "'{}' doesn't start with a number", text),
+            UnitError::MissingUnit(text) => write!(f, "'{}' has no unit", text),
+            UnitError::UnknownUnit(unit) => write!(f, "'{}' is not a known unit", unit),
+            UnitError::WrongDimension { unit, expected } => write!(f, "'{}' is not a unit of {}", unit, expected),
+            UnitError::BelowAbsoluteZero(text) => write!(f, "{} is below absolute zero", text),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+// Splits "98.6°F" into (98.6, "°F"), taking the longest prefix that is
+// a finite number
+fn split_number(text: &str) -> Result<(f64, &str), UnitError> {
+    let text = text.trim();
+    let number = (1..=text.len())
+        .rev()
+        .filter(|&end| text.is_char_boundary(end))
+        .find_map(|end| text[..end].trim().parse::<f64>().ok().map(|value| (value, end)));
+    match number {
+        Some((value, end)) if value.is_finite() => {
+            let unit = text[end..].trim();
+            if unit.is_empty() {
+                Err(UnitError::MissingUnit(text.to_string()))
+            } else {
+                Ok((value, unit))
+            }
+        }
+        _ => Err(UnitError::InvalidNumber(text.to_string())),
+    }
+}
+
+// Finds a unit by its symbol or name ("km", "kilometers", "Kilometers").
+// Symbols must match exactly: "Mm" is not "mm" and "T" is not "t". Only
+// spelled-out names, three letters or more, also match in any case.
+fn lookup<'a, T>(table: &'a [(&'static str, T)], unit: &str) -> Option<&'a (&'static str, T)> {
+    let is_name = |name: &str| name.len() >= 3 && name.chars().all(|c| c.is_ascii_alphabetic());
+    table.iter().find(|(name, _)| *name == unit).or_else(|| {
+        table
+            .iter()
+            .find(|(name, _)| is_name(name) && name.eq_ignore_ascii_case(unit))
+    })
+}
+
+// The reason a unit that isn't of the expected dimension was rejected
+fn unit_error(unit: &str, expected: &'static str) -> UnitError {
+    let known = [Length::UNITS, Mass::UNITS, Time::UNITS]
+        .iter()
+        .any(|table| lookup(table, unit).is_some())
+        || lookup(TEMPERATURE_UNITS, unit).is_some();
+    if known {
+        UnitError::WrongDimension { unit: unit.to_string(), expected }
+    } else {
+        UnitError::UnknownUnit(unit.to_string())
+    }
+}
+
+//------------------------------------------------------
+// TEMPERATURE
+//------------------------------------------------------
+
+/// A temperature scale, defined by how it maps to Kelvin
+pub trait Scale {
+    const SYMBOL: &'static str;
+    fn to_kelvin(value: f64) -> f64;
+    fn from_kelvin(kelvin: f64) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Celsius;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Fahrenheit;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Kelvin;
+
+impl Scale for Celsius {
+    const SYMBOL: &'static str = "°C";
+
+    fn to_kelvin(value: f64) -> f64 {
+        value + 273.15
+    }
+
+    fn from_kelvin(kelvin: f64) -> f64 {
+        kelvin - 273.15
+    }
+}
+
+impl Scale for Fahrenheit {
+    const SYMBOL: &'static str = "°F";
+
+    fn to_kelvin(value: f64) -> f64 {
+        (value - 32.0) * 5.0 / 9.0 + 273.15
+    }
+
+    fn from_kelvin(kelvin: f64) -> f64 {
+        (kelvin - 273.15) * 9.0 / 5.0 + 32.0
+    }
+}
+
+impl Scale for Kelvin {
+    const SYMBOL: &'static str = "K";
+
+    fn to_kelvin(value: f64) -> f64 {
+        value
+    }
+
+    fn from_kelvin(kelvin: f64) -> f64 {
+        kelvin
+    }
+}
+
+// Temperature symbols and names, with how to turn a reading into Kelvin
+type ToKelvin = fn(f64) -> f64;
+
+const TEMPERATURE_UNITS: &[(&str, ToKelvin)] = &[
+    ("°C", Celsius::to_kelvin),
+    ("℃", Celsius::to_kelvin),
+    ("C", Celsius::to_kelvin),
+    ("degC", Celsius::to_kelvin),
+    ("celsius", Celsius::to_kelvin),
+    ("°F", Fahrenheit::to_kelvin),
+    ("℉", Fahrenheit::to_kelvin),
+    ("F", Fahrenheit::to_kelvin),
+    ("degF", Fahrenheit::to_kelvin),
+    ("fahrenheit", Fahrenheit::to_kelvin),
+    ("K", Kelvin::to_kelvin),
+    ("kelvin", Kelvin::to_kelvin),
+];
+
+/// A temperature on scale `S`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Temperature<S> {
+    value: f64,
+    scale: PhantomData<S>,
+}
+
+impl<S: Scale> Temperature<S> {
+    pub fn new(value: f64) -> Self {
+        Temperature { value, scale: PhantomData }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn kelvin(&self) -> f64 {
+        S::to_kelvin(self.value)
+    }
+
+    /// The same temperature on another scale
+    pub fn to<T: Scale>(self) -> Temperature<T> {
+        Temperature::new(T::from_kelvin(self.kelvin()))
+    }
+}
+
+impl<S: Scale> fmt::Display for Temperature<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // "37°C" but "310.15 K"
+        let space = if S::SYMBOL.starts_with('°') { "" } else { " " };
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}{}{}", precision, self.value, space, S::SYMBOL),
+            None => write!(f, "{}{}{}", self.value, space, S::SYMBOL),
+        }
+    }
+}
+
+/// Reads any scale ("98.6°F", "37 C", "310.15 K") and converts to `S`
+impl<S: Scale> FromStr for Temperature<S> {
+    type Err = UnitError;
+
+    fn from_str(text: &str) -> Result<Self, UnitError> {
+        let (value, unit) = split_number(text)?;
+        let (_, to_kelvin) = lookup(TEMPERATURE_UNITS, unit).ok_or_else(|| unit_error(unit, "temperature"))?;
+        let kelvin = to_kelvin(value);
+        // Leave a little room for rounding in inputs like "-459.67°F"
+        if kelvin < -1e-9 {
+            return Err(UnitError::BelowAbsoluteZero(text.trim().to_string()));
+        }
+        Ok(Temperature::new(S::from_kelvin(kelvin.max(0.0))))
+    }
+}
+
+//------------------------------------------------------
+// LENGTH, MASS AND TIME
+//------------------------------------------------------
+
+/// What a unit measures
+pub trait Dimension {
+    const NAME: &'static str;
+    /// Every symbol and name of every unit, with its size in the base unit
+    const UNITS: &'static [(&'static str, f64)];
+}
+
+/// A unit of some dimension, as a multiple of that dimension's base unit
+pub trait Unit {
+    type Dimension: Dimension;
+    const SYMBOL: &'static str;
+    const FACTOR: f64;
+}
+
+// Declares a dimension marker, its units, and the lookup table for parsing
+macro_rules! dimension {
+    ($dimension:ident, $name:expr, { $($unit:ident = $factor:expr, $symbol:expr, [$($alias:expr),*];)* }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $dimension;
+
+        impl Dimension for $dimension {
+            const NAME: &'static str = $name;
+            const UNITS: &'static [(&'static str, f64)] = &[$(($symbol, $factor), $(($alias, $factor),)*)*];
+        }
+
+        $(
+            #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+            pub struct $unit;
+
+            impl Unit for $unit {
+                type Dimension = $dimension;
+                const SYMBOL: &'static str = $symbol;
+                const FACTOR: f64 = $factor;
+            }
+        )*
+    };
+}
+
+dimension!(Length, "length", {
+    Millimeter = 0.001, "mm", ["millimeter", "millimeters", "millimetre", "millimetres"];
+    Centimeter = 0.01, "cm", ["centimeter", "centimeters", "centimetre", "centimetres"];
+    Meter = 1.0, "m", ["meter", "meters", "metre", "metres"];
+    Kilometer = 1000.0, "km", ["kilometer", "kilometers", "kilometre", "kilometres"];
+    Inch = 0.0254, "in", ["inch", "inches", "\""];
+    Foot = 0.3048, "ft", ["foot", "feet", "'"];
+    Yard = 0.9144, "yd", ["yard", "yards"];
+    Mile = 1609.344, "mi", ["mile", "miles"];
+});
+
+dimension!(Mass, "mass", {
+    Milligram = 0.000_001, "mg", ["milligram", "milligrams"];
+    Gram = 0.001, "g", ["gram", "grams"];
+    Kilogram = 1.0, "kg", ["kilogram", "kilograms", "kilo", "kilos"];
+    Tonne = 1000.0, "t", ["tonne", "tonnes"];
+    Ounce = 0.028_349_523_125, "oz", ["ounce", "ounces"];
+    Pound = 0.453_592_37, "lb", ["lbs", "pound", "pounds"];
+});
+
+dimension!(Time, "time", {
+    Millisecond = 0.001, "ms", ["millisecond", "milliseconds"];
+    Second = 1.0, "s", ["sec", "secs", "second", "seconds"];
+    Minute = 60.0, "min", ["mins", "minute", "minutes"];
+    Hour = 3600.0, "h", ["hr", "hrs", "hour", "hours"];
+    Day = 86_400.0, "d", ["day", "days"];
+    Week = 604_800.0, "wk", ["week", "weeks"];
+});
+
+/// An amount in unit `U`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Quantity<U> {
+    value: f64,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit> Quantity<U> {
+    pub fn new(value: f64) -> Self {
+        Quantity { value, unit: PhantomData }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The same amount in another unit of the same dimension. Meters to
+    /// kilograms doesn't compile.
+    pub fn to<V: Unit<Dimension = U::Dimension>>(self) -> Quantity<V> {
+        Quantity::new(self.value * U::FACTOR / V::FACTOR)
+    }
+}
+
+impl<U: Unit> Add for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn add(self, other: Quantity<U>) -> Quantity<U> {
+        Quantity::new(self.value + other.value)
+    }
+}
+
+impl<U: Unit> Sub for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn sub(self, other: Quantity<U>) -> Quantity<U> {
+        Quantity::new(self.value - other.value)
+    }
+}
+
+impl<U: Unit> Mul<f64> for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn mul(self, factor: f64) -> Quantity<U> {
+        Quantity::new(self.value * factor)
+    }
+}
+
+impl<U: Unit> Div<f64> for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn div(self, divisor: f64) -> Quantity<U> {
+        Quantity::new(self.value / divisor)
+    }
+}
+
+/// The ratio of two amounts, e.g. how many laps make up a distance
+impl<U: Unit> Div for Quantity<U> {
+    type Output = f64;
+
+    fn div(self, other: Quantity<U>) -> f64 {
+        self.value / other.value
+    }
+}
+
+impl<U: Unit> fmt::Display for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {}", precision, self.value, U::SYMBOL),
+            None => write!(f, "{} {}", self.value, U::SYMBOL),
+        }
+    }
+}
+
+/// Reads any unit of the same dimension ("3 km", "5280 feet") and
+/// converts to `U`
+impl<U: Unit> FromStr for Quantity<U> {
+    type Err = UnitError;
+
+    fn from_str(text: &str) -> Result<Self, UnitError> {
+        let (value, unit) = split_number(text)?;
+        let (_, factor) = lookup(U::Dimension::UNITS, unit).ok_or_else(|| unit_error(unit, U::Dimension::NAME))?;
+        Ok(Quantity::new(value * factor / U::FACTOR))
+    }
+}
+
+//------------------------------------------------------
+// TESTS
+//------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} should be {}", actual, expected);
+    }
+
+    #[test]
+    fn test_temperature_conversions() {
+        // Test cases: (Celsius, Fahrenheit, Kelvin)
+        let cases = vec![
+            (0.0, 32.0, 273.15),
+            (100.0, 212.0, 373.15),
+            (37.0, 98.6, 310.15),
+            (-40.0, -40.0, 233.15),
+            (-273.15, -459.67, 0.0),
+        ];
+        for (c, f, k) in cases {
+            let celsius = Temperature::<Celsius>::new(c);
+            assert_close(celsius.to::<Fahrenheit>().value(), f);
+            assert_close(celsius.to::<Kelvin>().value(), k);
+            assert_close(Temperature::<Fahrenheit>::new(f).to::<Celsius>().value(), c);
+            assert_close(Temperature::<Kelvin>::new(k).to::<Fahrenheit>().value(), f);
+        }
+    }
+
+    #[test]
+    fn test_parse_temperatures() {
+        // Test cases: (text, degrees Celsius)
+        let cases = vec![
+            ("98.6°F", 37.0),
+            ("98.6 °F", 37.0),
+            ("  -40 F ", -40.0),
+            ("37°C", 37.0),
+            ("37 Celsius", 37.0),
+            ("21℃", 21.0),
+            ("310.15 K", 37.0),
+            ("0 kelvin", -273.15),
+            ("1e2 degC", 100.0),
+            ("-459.67°F", -273.15),
+        ];
+        for (text, celsius) in cases {
+            let parsed: Temperature<Celsius> = text.parse().unwrap();
+            assert_close(parsed.value(), celsius);
+        }
+
+        let error = |text: &str| text.parse::<Temperature<Kelvin>>().unwrap_err();
+        assert_eq!(error("-300 °C"), UnitError::BelowAbsoluteZero("-300 °C".to_string()));
+        assert_eq!(error("hot"), UnitError::InvalidNumber("hot".to_string()));
+        assert_eq!(error("30"), UnitError::MissingUnit("30".to_string()));
+        assert_eq!(error("30 °R"), UnitError::UnknownUnit("°R".to_string()));
+        assert_eq!(error("30 k"), UnitError::UnknownUnit("k".to_string()));
+        assert_eq!(error("30 km"), UnitError::WrongDimension { unit: "km".to_string(), expected: "temperature" });
+        assert_eq!(error("inf K"), UnitError::InvalidNumber("inf K".to_string()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Temperature::<Fahrenheit>::new(98.6).to_string(), "98.6°F");
+        assert_eq!(format!("{:.1}", Temperature::<Fahrenheit>::new(98.6).to::<Celsius>()), "37.0°C");
+        assert_eq!(format!("{:.2}", Temperature::<Celsius>::new(37.0).to::<Kelvin>()), "310.15 K");
+        assert_eq!(Quantity::<Kilometer>::new(3.0).to_string(), "3 km");
+        assert_eq!(format!("{:.2}", Quantity::<Mile>::new(1.0).to::<Kilometer>()), "1.61 km");
+    }
+
+    #[test]
+    fn test_quantity_conversions() {
+        assert_close(Quantity::<Kilometer>::new(3.0).to::<Meter>().value(), 3000.0);
+        assert_close(Quantity::<Mile>::new(1.0).to::<Foot>().value(), 5280.0);
+        assert_close(Quantity::<Foot>::new(1.0).to::<Inch>().value(), 12.0);
+        assert_close(Quantity::<Pound>::new(1.0).to::<Ounce>().value(), 16.0);
+        assert_close(Quantity::<Tonne>::new(2.5).to::<Kilogram>().value(), 2500.0);
+        assert_close(Quantity::<Week>::new(1.0).to::<Hour>().value(), 168.0);
+        assert_close(Quantity::<Minute>::new(1.5).to::<Millisecond>().value(), 90_000.0);
+
+        // Converting there and back gives the same amount
+        for value in [0.0, 1.0, -2.5, 1234.5678] {
+            assert_close(Quantity::<Yard>::new(value).to::<Centimeter>().to::<Yard>().value(), value);
+            assert_close(Quantity::<Gram>::new(value).to::<Pound>().to::<Gram>().value(), value);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_in_one_unit() {
+        let leg = Quantity::<Kilometer>::new(3.0);
+        let detour: Quantity<Kilometer> = Quantity::<Mile>::new(1.0).to();
+        let total = leg + detour;
+        assert_close(total.value(), 4.609344);
+        assert_close((total - leg).to::<Mile>().value(), 1.0);
+        assert_close((leg * 2.0).value(), 6.0);
+        assert_close((leg / 4.0).value(), 0.75);
+
+        // Laps of a 400 m track in 10 km
+        let laps = Quantity::<Kilometer>::new(10.0).to::<Meter>() / Quantity::<Meter>::new(400.0);
+        assert_close(laps, 25.0);
+        assert!(Quantity::<Meter>::new(999.0) < Quantity::<Kilometer>::new(1.0).to());
+    }
+
+    #[test]
+    fn test_parse_quantities() {
+        let km = |text: &str| text.parse::<Quantity<Kilometer>>().map(|q| q.value());
+        assert_close(km("3 km").unwrap(), 3.0);
+        assert_close(km("3km").unwrap(), 3.0);
+        assert_close(km("3 Kilometres").unwrap(), 3.0);
+        assert_close(km("3 MILES").unwrap(), 4.828032);
+        assert_close(km("500 m").unwrap(), 0.5);
+        assert_close(km("1 mile").unwrap(), 1.609344);
+        assert_close(km("5280 feet").unwrap(), 1.609344);
+
+        assert_close("2.5 hours".parse::<Quantity<Minute>>().unwrap().value(), 150.0);
+        assert_close("8 oz".parse::<Quantity<Gram>>().unwrap().value(), 226.796185);
+
+        assert_eq!(km("3 kg"), Err(UnitError::WrongDimension { unit: "kg".to_string(), expected: "length" }));
+        assert_eq!(km("3 °F"), Err(UnitError::WrongDimension { unit: "°F".to_string(), expected: "length" }));
+        assert_eq!(km("3 parsecs"), Err(UnitError::UnknownUnit("parsecs".to_string())));
+        // Symbols are case-sensitive: Mm would be megametres, not millimetres
+        assert_eq!(km("3 Mm"), Err(UnitError::UnknownUnit("Mm".to_string())));
+        assert_eq!(km("3 KM"), Err(UnitError::UnknownUnit("KM".to_string())));
+        assert_eq!("2 T".parse::<Quantity<Kilogram>>(), Err(UnitError::UnknownUnit("T".to_string())));
+        assert_eq!("2 D".parse::<Quantity<Hour>>(), Err(UnitError::UnknownUnit("D".to_string())));
+        assert_close("2 Days".parse::<Quantity<Hour>>().unwrap().value(), 48.0);
+        assert_eq!(km("km"), Err(UnitError::InvalidNumber("km".to_string())));
+        assert_eq!(km(""), Err(UnitError::InvalidNumber("".to_string())));
+    }
+}